@@ -0,0 +1,326 @@
+// cluster.rs
+use crate::physics::{
+    bottle::{BottleCompliance, BottleSpec},
+    nozzle::Nozzle,
+};
+
+/// Cómo se conectan las botellas de un cúmulo.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Coupling {
+    /// Cada botella tiene su propio aire y su propia tobera.
+    #[default]
+    Independent,
+    /// Aire compartido por un colector; cada botella descarga por su tobera. La primera que
+    /// se vacía deja escapar el aire por la suya y las demás dejan de empujar su agua.
+    Parallel,
+    /// Aire compartido; el agua baja de botella en botella y sale solo por
+    /// la tobera de la primera (la inferior).
+    Series,
+}
+
+/// Botella adicional de un cúmulo (la principal la describe `SimulationConfig`).
+#[derive(Clone, Debug)]
+pub struct ClusterBottle {
+    /// Botella: volumen, deformación y límites.
+    pub bottle: BottleSpec,
+    /// Tobera por la que descarga.
+    pub nozzle: Nozzle,
+    /// Carga de agua al lanzar [kg].
+    pub water_mass: f64,
+}
+
+/// Estado de una botella dentro de un depósito.
+#[derive(Clone, Debug)]
+pub struct BottleState {
+    compliance: BottleCompliance,
+    nozzle: Nozzle,
+    water_mass: f64, // [kg]
+    volume: f64,     // volumen interno actual [m³]
+}
+
+impl BottleState {
+    pub(crate) fn new(bottle: &BottleSpec, nozzle: &Nozzle, water_mass: f64) -> Self {
+        Self {
+            compliance: bottle.compliance.clone(),
+            nozzle: nozzle.clone(),
+            water_mass,
+            volume: bottle.compliance.nominal_volume(),
+        }
+    }
+
+    /// Cómo se hincha con la presión.
+    pub fn compliance(&self) -> &BottleCompliance {
+        &self.compliance
+    }
+
+    /// Tobera de la botella.
+    pub fn nozzle(&self) -> &Nozzle {
+        &self.nozzle
+    }
+
+    /// Agua que queda [kg].
+    pub fn water_mass(&self) -> f64 {
+        self.water_mass
+    }
+
+    /// Volumen interno actual, ya hinchada [m³].
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+}
+
+/// Un volumen de aire a una sola presión: una botella suelta o varias unidas por un colector.
+#[derive(Clone, Debug)]
+pub struct PressureVessel {
+    bottles: Vec<BottleState>,
+    series: bool,
+    polytropic_constant: f64, // K = P * V^gamma
+    pressure_pa: f64,         // presión absoluta [Pa]
+    air_volume: f64,          // [m³]
+}
+
+impl PressureVessel {
+    /// Depósito inflado a `pressure_pa` (absoluta) con las botellas ya hinchadas.
+    pub(crate) fn new(
+        mut bottles: Vec<BottleState>,
+        series: bool,
+        pressure_pa: f64,
+        atmospheric_pressure: f64,
+        adiabatic_index: f64,
+        water_density: f64,
+    ) -> Self {
+        let gauge = pressure_pa - atmospheric_pressure;
+        for b in &mut bottles {
+            b.volume = b.compliance.volume_at(gauge);
+        }
+        let total_volume: f64 = bottles.iter().map(|b| b.volume).sum();
+        let water_mass: f64 = bottles.iter().map(|b| b.water_mass).sum();
+        let air_volume = (total_volume - water_mass / water_density).max(1e-12);
+
+        Self {
+            bottles,
+            series,
+            polytropic_constant: pressure_pa * air_volume.powf(adiabatic_index),
+            pressure_pa,
+            air_volume,
+        }
+    }
+
+    /// Botellas del depósito; en serie, la primera es la inferior.
+    pub fn bottles(&self) -> &[BottleState] {
+        &self.bottles
+    }
+
+    /// Botellas en serie (el agua sale solo por la inferior).
+    pub fn is_series(&self) -> bool {
+        self.series
+    }
+
+    /// K = P · Va^γ del aire de este depósito.
+    pub fn polytropic_constant(&self) -> f64 {
+        self.polytropic_constant
+    }
+
+    /// Presión absoluta actual [Pa].
+    pub fn pressure_pa(&self) -> f64 {
+        self.pressure_pa
+    }
+
+    /// Volumen de aire actual [m³].
+    pub fn air_volume(&self) -> f64 {
+        self.air_volume
+    }
+
+    /// Agua que queda en todas sus botellas [kg].
+    pub fn water_mass(&self) -> f64 {
+        self.bottles.iter().map(|b| b.water_mass).sum()
+    }
+
+    /// Volumen interno de todas sus botellas, ya hinchadas [m³].
+    pub fn bottle_volume(&self) -> f64 {
+        self.bottles.iter().map(|b| b.volume).sum()
+    }
+
+    // presión manométrica que tendría el aire ocupando `air_volume`
+    fn gauge_pressure_for(&self, air_volume: f64, atmospheric_pressure: f64, gamma: f64) -> f64 {
+        self.polytropic_constant / air_volume.powf(gamma) - atmospheric_pressure
+    }
+
+    // volumen de aire = Σ volumen botella(P) - volumen de agua
+    // Las botellas se hinchan con la presión y la presión depende del volumen de aire,
+    // así que se resuelve Va = Σ Vb(K / Va^gamma - P_atm) - Vw por bisección.
+    pub(crate) fn update_air_volume(
+        &mut self,
+        water_density: f64,
+        atmospheric_pressure: f64,
+        gamma: f64,
+    ) {
+        let water_volume = self.water_mass() / water_density;
+        let air_volume_for = |gauge: f64| {
+            let bottles: f64 = self.bottles.iter().map(|b| b.compliance.volume_at(gauge)).sum();
+            (bottles - water_volume).max(1e-12)
+        };
+
+        // cota inferior: botellas sin hinchar; cota superior: hinchadas a la presión de esa cota
+        let mut lo = air_volume_for(0.0);
+        let mut hi = air_volume_for(self.gauge_pressure_for(lo, atmospheric_pressure, gamma));
+
+        while hi - lo > 1e-15 {
+            let mid = 0.5 * (lo + hi);
+            let target = air_volume_for(self.gauge_pressure_for(mid, atmospheric_pressure, gamma));
+            if mid < target {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        self.air_volume = hi;
+        let gauge = self.gauge_pressure_for(hi, atmospheric_pressure, gamma);
+        for b in &mut self.bottles {
+            b.volume = b.compliance.volume_at(gauge);
+        }
+    }
+
+    // presión actual por politropía: P = K / Va^gamma
+    pub(crate) fn update_pressure(&mut self, adiabatic_index: f64) {
+        self.pressure_pa = self.polytropic_constant / self.air_volume.powf(adiabatic_index);
+    }
+
+    /// Abre el depósito a la atmósfera: las botellas vuelven a su volumen sin presión y el
+    /// aire queda a `atmospheric_pressure`, sin empujar el agua.
+    pub(crate) fn vent(
+        &mut self,
+        atmospheric_pressure: f64,
+        adiabatic_index: f64,
+        water_density: f64,
+    ) {
+        for b in &mut self.bottles {
+            b.volume = b.compliance.volume_at(0.0);
+        }
+        let water_volume = self.water_mass() / water_density;
+        self.air_volume = (self.bottle_volume() - water_volume).max(1e-12);
+        self.pressure_pa = atmospheric_pressure;
+        self.polytropic_constant = atmospheric_pressure * self.air_volume.powf(adiabatic_index);
+    }
+
+    /// Si el aire a presión escapa por la tobera de una botella ya vacía mientras otras
+    /// conservan agua (solo en paralelo: en serie no hay más tobera que la inferior).
+    pub fn is_blowing_air(&self, atmospheric_pressure: f64) -> bool {
+        let empty = self.bottles.iter().any(|b| b.water_mass <= 0.0);
+        !self.series && empty && self.water_mass() > 0.0 && self.pressure_pa > atmospheric_pressure
+    }
+
+    /// Índices de las botellas cuya tobera descarga agua en este momento.
+    pub fn discharging_bottles(&self) -> Vec<usize> {
+        if self.series {
+            if self.water_mass() > 0.0 { vec![0] } else { Vec::new() }
+        } else {
+            (0..self.bottles.len()).filter(|&i| self.bottles[i].water_mass > 0.0).collect()
+        }
+    }
+
+    /// Saca `dm` kg de agua por la tobera de la botella `index`.
+    /// En serie el agua sale primero de la botella más alta.
+    pub(crate) fn drain(&mut self, index: usize, dm: f64) {
+        if !self.series {
+            let b = &mut self.bottles[index];
+            b.water_mass = (b.water_mass - dm).max(0.0);
+            return;
+        }
+        let mut remaining = dm;
+        for b in self.bottles.iter_mut().rev() {
+            let taken = remaining.min(b.water_mass);
+            b.water_mass -= taken;
+            remaining -= taken;
+            if remaining <= 0.0 {
+                break;
+            }
+        }
+    }
+}
+
+/// Lo que cambia de un depósito durante el vuelo (para `SimulationState`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VesselState {
+    /// K = P · Va^γ del aire.
+    pub polytropic_constant: f64,
+    /// Presión absoluta [Pa].
+    pub pressure_pa: f64,
+    /// Volumen de aire [m³].
+    pub air_volume: f64,
+    /// Agua de cada botella [kg].
+    pub water_masses: Vec<f64>,
+    /// Volumen interno de cada botella, ya hinchada [m³].
+    pub volumes: Vec<f64>,
+}
+
+impl PressureVessel {
+    /// Lo que cambia del depósito, para guardarlo.
+    pub fn state(&self) -> VesselState {
+        VesselState {
+            polytropic_constant: self.polytropic_constant,
+            pressure_pa: self.pressure_pa,
+            air_volume: self.air_volume,
+            water_masses: self.bottles.iter().map(|b| b.water_mass).collect(),
+            volumes: self.bottles.iter().map(|b| b.volume).collect(),
+        }
+    }
+
+    /// Vuelve a un estado guardado; `false` (sin tocar nada) si no tiene las mismas botellas.
+    pub(crate) fn restore(&mut self, state: &VesselState) -> bool {
+        let n = self.bottles.len();
+        if state.water_masses.len() != n || state.volumes.len() != n {
+            return false;
+        }
+        self.polytropic_constant = state.polytropic_constant;
+        self.pressure_pa = state.pressure_pa;
+        self.air_volume = state.air_volume;
+        for (b, bottle) in self.bottles.iter_mut().enumerate() {
+            bottle.water_mass = state.water_masses[b];
+            bottle.volume = state.volumes[b];
+        }
+        true
+    }
+}
+
+/// Chorro de una tobera durante un paso.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NozzleFlow {
+    /// Índice del depósito.
+    pub vessel: usize,
+    /// Índice de la botella dentro del depósito.
+    pub bottle: usize,
+    /// Velocidad del chorro respecto al cohete [m/s].
+    pub exit_velocity: f64,
+    /// Caudal de agua [kg/s].
+    pub mass_flow: f64,
+    /// Empuje, ṁ·v + (P - P_atm)·A [N].
+    pub thrust: f64,
+}
+
+/// En serie el agua de arriba baja primero; en paralelo cada botella vacía la suya, y la
+/// vacía deja escapar el aire compartido.
+#[test]
+fn series_drains_top_bottle_first() {
+    let bottle = BottleSpec::pet_2l();
+    let nozzle = Nozzle::full_bore();
+    let bottles = vec![BottleState::new(&bottle, &nozzle, 0.6); 2];
+
+    let mut series = PressureVessel::new(bottles.clone(), true, 4.0e5, 1.0e5, 1.4, 1000.0);
+    assert_eq!(series.discharging_bottles(), vec![0]);
+    series.drain(0, 0.8);
+    assert!((series.bottles[1].water_mass - 0.0).abs() < 1e-12);
+    assert!((series.bottles[0].water_mass - 0.4).abs() < 1e-12);
+    assert!(!series.is_blowing_air(1.0e5));
+
+    let mut parallel = PressureVessel::new(bottles, false, 4.0e5, 1.0e5, 1.4, 1000.0);
+    assert_eq!(parallel.discharging_bottles(), vec![0, 1]);
+    assert!(!parallel.is_blowing_air(1.0e5));
+    parallel.drain(1, 0.8);
+    assert_eq!(parallel.bottles[1].water_mass, 0.0);
+    assert_eq!(parallel.discharging_bottles(), vec![0]);
+    assert!(parallel.is_blowing_air(1.0e5));
+    parallel.vent(1.0e5, 1.4, 1000.0);
+    assert!(!parallel.is_blowing_air(1.0e5));
+}
@@ -0,0 +1,549 @@
+// columnar.rs
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{
+    config::SimulationConfig,
+    events::FlightSummary,
+    json_export::{self, Json},
+};
+
+/// Valores de una columna, con su tipo.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnData {
+    /// DOUBLE de Parquet.
+    Double(Vec<f64>),
+    /// INT64 de Parquet.
+    Int64(Vec<i64>),
+    /// BYTE_ARRAY de Parquet, como texto UTF-8.
+    Utf8(Vec<String>),
+}
+
+impl ColumnData {
+    /// Número de filas.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Double(v) => v.len(),
+            Self::Int64(v) => v.len(),
+            Self::Utf8(v) => v.len(),
+        }
+    }
+
+    /// Sin filas.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // tipo físico de Parquet: INT64 = 2, DOUBLE = 5, BYTE_ARRAY = 6
+    fn parquet_type(&self) -> i32 {
+        match self {
+            Self::Double(_) => 5,
+            Self::Int64(_) => 2,
+            Self::Utf8(_) => 6,
+        }
+    }
+
+    // codificación PLAIN
+    fn plain_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Double(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            Self::Int64(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            Self::Utf8(values) => {
+                let mut out = Vec::new();
+                for s in values {
+                    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+                    out.extend_from_slice(s.as_bytes());
+                }
+                out
+            }
+        }
+    }
+}
+
+/// Columna con nombre de una `ColumnarTable`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    /// Nombre en el esquema.
+    pub name: String,
+    /// Valores, uno por fila.
+    pub data: ColumnData,
+}
+
+/// Tabla por columnas con metadatos clave/valor (p. ej. la configuración en JSON).
+/// Se escribe como Apache Parquet: un grupo de filas, una página PLAIN sin comprimir
+/// por columna, todas obligatorias (sin nulos: lo que falte va como NaN).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnarTable {
+    /// Columnas, todas con las mismas filas.
+    pub columns: Vec<Column>,
+    /// Pares clave/valor del pie del archivo.
+    pub metadata: Vec<(String, String)>,
+}
+
+impl ColumnarTable {
+    /// Tabla vacía.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Añade una columna al final.
+    pub fn push_column(&mut self, name: &str, data: ColumnData) {
+        self.columns.push(Column { name: name.to_string(), data });
+    }
+
+    /// Añade un par clave/valor a los metadatos.
+    pub fn push_metadata(&mut self, key: &str, value: String) {
+        self.metadata.push((key.to_string(), value));
+    }
+
+    /// Filas de la tabla (las de la primera columna).
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map_or(0, |c| c.data.len())
+    }
+
+    /// Escribe el archivo Parquet completo.
+    pub fn write_parquet<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let rows = self.num_rows();
+        if let Some(c) = self.columns.iter().find(|c| c.data.len() != rows) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("la columna {} tiene {} filas, se esperaban {}", c.name, c.data.len(), rows),
+            ));
+        }
+
+        writer.write_all(PARQUET_MAGIC)?;
+        let mut offset = PARQUET_MAGIC.len() as i64;
+        let mut chunks = Vec::new(); // (offset de la página, tamaño total)
+        for column in &self.columns {
+            let data = column.data.plain_bytes();
+            let header = page_header(rows, data.len())?;
+            writer.write_all(&header)?;
+            writer.write_all(&data)?;
+            let size = (header.len() + data.len()) as i64;
+            chunks.push((offset, size));
+            offset += size;
+        }
+
+        let footer = self.file_metadata(rows, &chunks);
+        writer.write_all(&footer)?;
+        writer.write_all(&(footer.len() as u32).to_le_bytes())?;
+        writer.write_all(PARQUET_MAGIC)
+    }
+
+    /// Escribe el archivo Parquet en `path`.
+    pub fn export_parquet<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_parquet(&mut writer)?;
+        writer.flush()
+    }
+
+    // FileMetaData (Thrift compact)
+    fn file_metadata(&self, rows: usize, chunks: &[(i64, i64)]) -> Vec<u8> {
+        let mut w = CompactWriter::default();
+        w.i32_field(1, 1); // version
+
+        // esquema: raíz + una hoja por columna
+        w.list_field(2, THRIFT_STRUCT, self.columns.len() + 1);
+        w.struct_begin();
+        w.binary_field(4, b"schema");
+        w.i32_field(5, self.columns.len() as i32);
+        w.struct_end();
+        for column in &self.columns {
+            w.struct_begin();
+            w.i32_field(1, column.data.parquet_type());
+            w.i32_field(3, 0); // REQUIRED
+            w.binary_field(4, column.name.as_bytes());
+            if let ColumnData::Utf8(_) = column.data {
+                w.i32_field(6, 0); // converted_type UTF8
+            }
+            w.struct_end();
+        }
+
+        w.i64_field(3, rows as i64);
+
+        // un solo grupo de filas
+        w.list_field(4, THRIFT_STRUCT, 1);
+        w.struct_begin();
+        w.list_field(1, THRIFT_STRUCT, self.columns.len());
+        for (column, &(page_offset, size)) in self.columns.iter().zip(chunks) {
+            w.struct_begin();
+            w.i64_field(2, page_offset); // file_offset
+            w.struct_field(3); // meta_data
+            w.i32_field(1, column.data.parquet_type());
+            w.list_field(2, THRIFT_I32, 1);
+            w.list_i32(0); // PLAIN
+            w.list_field(3, THRIFT_BINARY, 1);
+            w.list_binary(column.name.as_bytes());
+            w.i32_field(4, 0); // UNCOMPRESSED
+            w.i64_field(5, rows as i64);
+            w.i64_field(6, size);
+            w.i64_field(7, size);
+            w.i64_field(9, page_offset);
+            w.struct_end();
+            w.struct_end();
+        }
+        let total: i64 = chunks.iter().map(|&(_, size)| size).sum();
+        w.i64_field(2, total);
+        w.i64_field(3, rows as i64);
+        w.struct_end();
+
+        if !self.metadata.is_empty() {
+            w.list_field(5, THRIFT_STRUCT, self.metadata.len());
+            for (key, value) in &self.metadata {
+                w.struct_begin();
+                w.binary_field(1, key.as_bytes());
+                w.binary_field(2, value.as_bytes());
+                w.struct_end();
+            }
+        }
+        w.binary_field(6, concat!("physics_calculations ", env!("CARGO_PKG_VERSION")).as_bytes());
+        w.stop();
+        w.out
+    }
+}
+
+/// Tabla de un barrido: los parámetros que suelen variar y el resumen de cada corrida.
+/// Los metadatos llevan la primera configuración (`base_config`) y la de cada fila, en
+/// orden (`run_configs`, una lista JSON), para saber qué cambió aunque no tenga columna.
+pub(crate) fn sweep_summary_table(runs: &[(SimulationConfig, FlightSummary)]) -> ColumnarTable {
+    let f64_column = |f: fn(&(SimulationConfig, FlightSummary)) -> f64| {
+        ColumnData::Double(runs.iter().map(f).collect())
+    };
+    let mut table = ColumnarTable::new();
+    table.push_column("run", ColumnData::Int64((0..runs.len() as i64).collect()));
+    table.push_column(
+        "bottle",
+        ColumnData::Utf8(runs.iter().map(|(c, _)| c.bottle.name.clone()).collect()),
+    );
+    table.push_column(
+        "nozzle",
+        ColumnData::Utf8(runs.iter().map(|(c, _)| c.nozzle.name.clone()).collect()),
+    );
+    table.push_column("pressure_psi", f64_column(|(c, _)| c.initial_air_pressure_psi));
+    table.push_column("nozzle_diam_m", f64_column(|(c, _)| c.nozzle.exit_diameter));
+    table.push_column("water_mass_kg", f64_column(|(c, _)| c.initial_water_mass));
+    table.push_column("dry_mass_kg", f64_column(|(c, _)| c.dry_mass));
+    table.push_column("launching_angle_deg", f64_column(|(c, _)| c.launching_angle_deg));
+    // NaN: aterrizó con agua, sin fin de propulsión (no es un fallo numérico; esos paran
+    // la corrida con `SimulationError::UnhealthyState`)
+    table.push_column("t_burnout_s", f64_column(|(_, s)| s.burnout_time.unwrap_or(f64::NAN)));
+    table.push_column("apogee_m", f64_column(|(_, s)| s.apogee));
+    table.push_column("range_m", f64_column(|(_, s)| s.range));
+    table.push_column("flight_time_s", f64_column(|(_, s)| s.flight_time));
+    table.push_column("max_thrust_N", f64_column(|(_, s)| s.peaks.max_thrust));
+    table.push_column("max_speed_m_s", f64_column(|(_, s)| s.peaks.max_speed));
+    if let Some((config, _)) = runs.first() {
+        let json = json_export::config_json(config);
+        table.push_metadata("base_config", json.to_string());
+        let configs = runs.iter().map(|(c, _)| json_export::config_json(c)).collect();
+        table.push_metadata("run_configs", Json::Array(configs).to_string());
+    }
+    table
+}
+
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+// tipos del protocolo compacto de Thrift
+const THRIFT_I32: u8 = 5;
+const THRIFT_I64: u8 = 6;
+const THRIFT_BINARY: u8 = 8;
+const THRIFT_LIST: u8 = 9;
+const THRIFT_STRUCT: u8 = 12;
+
+// PageHeader de una página de datos v1, PLAIN y sin comprimir. El formato guarda tamaños
+// y filas de la página en i32: una página mayor no se puede escribir.
+fn page_header(rows: usize, data_len: usize) -> io::Result<Vec<u8>> {
+    let to_i32 = |value: usize, what: &str| {
+        i32::try_from(value).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("página Parquet de {} {}: el máximo es {}", value, what, i32::MAX),
+            )
+        })
+    };
+    let (rows, data_len) = (to_i32(rows, "filas")?, to_i32(data_len, "bytes")?);
+    let mut w = CompactWriter::default();
+    w.i32_field(1, 0); // DATA_PAGE
+    w.i32_field(2, data_len);
+    w.i32_field(3, data_len);
+    w.struct_field(5);
+    w.i32_field(1, rows);
+    w.i32_field(2, 0); // PLAIN
+    w.i32_field(3, 3); // niveles de definición: RLE (no hay, todo es obligatorio)
+    w.i32_field(4, 3); // niveles de repetición: RLE
+    w.struct_end();
+    w.stop();
+    Ok(w.out)
+}
+
+/// Escritor mínimo del protocolo compacto de Thrift (lo que necesita el pie de Parquet).
+#[derive(Default)]
+struct CompactWriter {
+    out: Vec<u8>,
+    last_field: i16,
+    stack: Vec<i16>,
+}
+
+impl CompactWriter {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.out.push(value as u8);
+    }
+
+    fn zigzag(&mut self, value: i64) {
+        self.varint(((value << 1) ^ (value >> 63)) as u64);
+    }
+
+    fn field(&mut self, id: i16, kind: u8) {
+        let delta = id - self.last_field;
+        if (1..=15).contains(&delta) {
+            self.out.push(((delta as u8) << 4) | kind);
+        } else {
+            self.out.push(kind);
+            self.zigzag(id as i64);
+        }
+        self.last_field = id;
+    }
+
+    fn i32_field(&mut self, id: i16, value: i32) {
+        self.field(id, THRIFT_I32);
+        self.zigzag(value as i64);
+    }
+
+    fn i64_field(&mut self, id: i16, value: i64) {
+        self.field(id, THRIFT_I64);
+        self.zigzag(value);
+    }
+
+    fn binary_field(&mut self, id: i16, bytes: &[u8]) {
+        self.field(id, THRIFT_BINARY);
+        self.list_binary(bytes);
+    }
+
+    fn list_field(&mut self, id: i16, element: u8, len: usize) {
+        self.field(id, THRIFT_LIST);
+        if len < 15 {
+            self.out.push(((len as u8) << 4) | element);
+        } else {
+            self.out.push(0xF0 | element);
+            self.varint(len as u64);
+        }
+    }
+
+    fn list_i32(&mut self, value: i32) {
+        self.zigzag(value as i64);
+    }
+
+    fn list_binary(&mut self, bytes: &[u8]) {
+        self.varint(bytes.len() as u64);
+        self.out.extend_from_slice(bytes);
+    }
+
+    // struct como campo
+    fn struct_field(&mut self, id: i16) {
+        self.field(id, THRIFT_STRUCT);
+        self.struct_begin();
+    }
+
+    // struct como elemento de lista (sin cabecera)
+    fn struct_begin(&mut self) {
+        self.stack.push(self.last_field);
+        self.last_field = 0;
+    }
+
+    fn struct_end(&mut self) {
+        self.stop();
+        self.last_field = self.stack.pop().unwrap_or(0);
+    }
+
+    fn stop(&mut self) {
+        self.out.push(0);
+    }
+}
+
+/// Estructura del archivo: marcas PAR1, pie con su longitud, datos PLAIN tras la cabecera
+/// de página y metadatos legibles en el pie.
+#[test]
+fn parquet_layout() {
+    let mut table = ColumnarTable::new();
+    table.push_column("t", ColumnData::Double(vec![0.0, 0.5, 1.0]));
+    table.push_column("name", ColumnData::Utf8(vec!["a".into(), "bc".into(), "".into()]));
+    table.push_metadata("config", r#"{"dt":0.001}"#.to_string());
+    let mut bytes = Vec::new();
+    table.write_parquet(&mut bytes).unwrap();
+
+    assert_eq!(&bytes[..4], b"PAR1");
+    assert_eq!(&bytes[bytes.len() - 4..], b"PAR1");
+    let n = bytes.len();
+    let footer_len = u32::from_le_bytes(bytes[n - 8..n - 4].try_into().unwrap()) as usize;
+    let footer = &bytes[n - 8 - footer_len..n - 8];
+    let contains = |haystack: &[u8], needle: &[u8]| {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    };
+    assert!(contains(footer, br#"{"dt":0.001}"#));
+    assert!(contains(footer, b"name"));
+
+    let doubles: Vec<u8> = [0.0f64, 0.5, 1.0].iter().flat_map(|v| v.to_le_bytes()).collect();
+    assert!(contains(&bytes, &doubles));
+    assert!(contains(&bytes, b"\x01\x00\x00\x00a\x02\x00\x00\x00bc\x00\x00\x00\x00"));
+
+    table.push_column("short", ColumnData::Int64(vec![1]));
+    assert!(table.write_parquet(&mut Vec::new()).is_err());
+    assert!(page_header(3, i32::MAX as usize + 1).is_err());
+    assert!(page_header(i32::MAX as usize + 1, 8).is_err());
+}
+
+/// El pie y las cabeceras de página se decodifican como Thrift compacto y apuntan a los
+/// datos de cada columna.
+#[test]
+fn parquet_footer_decodes() {
+    #[derive(Debug)]
+    enum Thrift {
+        Int(i64),
+        Binary(Vec<u8>),
+        List(Vec<Thrift>),
+        Struct(Vec<(i16, Thrift)>),
+    }
+
+    impl Thrift {
+        fn field(&self, id: i16) -> &Thrift {
+            let Thrift::Struct(fields) = self else { panic!("no es struct: {:?}", self) };
+            &fields.iter().find(|(i, _)| *i == id).unwrap_or_else(|| panic!("falta {}", id)).1
+        }
+        fn int(&self) -> i64 {
+            let Thrift::Int(v) = self else { panic!("no es entero: {:?}", self) };
+            *v
+        }
+        fn bytes(&self) -> &[u8] {
+            let Thrift::Binary(b) = self else { panic!("no es binario: {:?}", self) };
+            b
+        }
+        fn list(&self) -> &[Thrift] {
+            let Thrift::List(items) = self else { panic!("no es lista: {:?}", self) };
+            items
+        }
+    }
+
+    struct Reader<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+    }
+
+    impl Reader<'_> {
+        fn byte(&mut self) -> u8 {
+            self.pos += 1;
+            self.bytes[self.pos - 1]
+        }
+        fn varint(&mut self) -> u64 {
+            let (mut value, mut shift) = (0, 0);
+            loop {
+                let b = self.byte();
+                value |= ((b & 0x7F) as u64) << shift;
+                if b < 0x80 {
+                    return value;
+                }
+                shift += 7;
+            }
+        }
+        fn zigzag(&mut self) -> i64 {
+            let v = self.varint();
+            ((v >> 1) as i64) ^ -((v & 1) as i64)
+        }
+        fn value(&mut self, kind: u8) -> Thrift {
+            match kind {
+                THRIFT_I32 | THRIFT_I64 => Thrift::Int(self.zigzag()),
+                THRIFT_BINARY => {
+                    let n = self.varint() as usize;
+                    self.pos += n;
+                    Thrift::Binary(self.bytes[self.pos - n..self.pos].to_vec())
+                }
+                THRIFT_LIST => {
+                    let header = self.byte();
+                    let n = match header >> 4 {
+                        15 => self.varint() as usize,
+                        n => n as usize,
+                    };
+                    Thrift::List((0..n).map(|_| self.value(header & 0x0F)).collect())
+                }
+                THRIFT_STRUCT => {
+                    let (mut fields, mut last) = (Vec::new(), 0);
+                    loop {
+                        let header = self.byte();
+                        if header == 0 {
+                            return Thrift::Struct(fields);
+                        }
+                        let delta = (header >> 4) as i16;
+                        last = if delta == 0 { self.zigzag() as i16 } else { last + delta };
+                        fields.push((last, self.value(header & 0x0F)));
+                    }
+                }
+                other => panic!("tipo Thrift inesperado: {}", other),
+            }
+        }
+    }
+
+    let mut table = ColumnarTable::new();
+    table.push_column("t", ColumnData::Double(vec![0.0, 0.5, 1.0]));
+    table.push_column("name", ColumnData::Utf8(vec!["a".into(), "bc".into(), "".into()]));
+    table.push_column("n", ColumnData::Int64(vec![-1, 0, 7]));
+    table.push_metadata("config", r#"{"dt":0.001}"#.to_string());
+    let mut bytes = Vec::new();
+    table.write_parquet(&mut bytes).unwrap();
+
+    let n = bytes.len();
+    let footer_len = u32::from_le_bytes(bytes[n - 8..n - 4].try_into().unwrap()) as usize;
+    let footer_start = n - 8 - footer_len;
+    let mut reader = Reader { bytes: &bytes[footer_start..n - 8], pos: 0 };
+    let meta = reader.value(THRIFT_STRUCT);
+    assert_eq!(reader.pos, footer_len, "el pie se lee entero");
+
+    assert_eq!(meta.field(1).int(), 1);
+    assert_eq!(meta.field(3).int(), 3);
+    let schema = meta.field(2).list();
+    assert_eq!(schema.len(), 4);
+    assert_eq!(schema[0].field(4).bytes(), b"schema");
+    assert_eq!(schema[0].field(5).int(), 3);
+    for (element, column) in schema[1..].iter().zip(&table.columns) {
+        assert_eq!(element.field(4).bytes(), column.name.as_bytes());
+        assert_eq!(element.field(1).int(), column.data.parquet_type() as i64);
+        assert_eq!(element.field(3).int(), 0);
+    }
+    assert_eq!(schema[2].field(6).int(), 0, "UTF8");
+
+    let row_groups = meta.field(4).list();
+    assert_eq!(row_groups.len(), 1);
+    let chunks = row_groups[0].field(1).list();
+    assert_eq!(chunks.len(), 3);
+    let mut end = PARQUET_MAGIC.len();
+    for (chunk, column) in chunks.iter().zip(&table.columns) {
+        let info = chunk.field(3);
+        let offset = info.field(9).int() as usize;
+        assert_eq!(chunk.field(2).int() as usize, offset);
+        assert_eq!(offset, end, "las columnas van seguidas");
+        assert_eq!(info.field(1).int(), column.data.parquet_type() as i64);
+        assert_eq!(info.field(3).list()[0].bytes(), column.name.as_bytes());
+        assert_eq!(info.field(5).int(), 3);
+
+        let mut page = Reader { bytes: &bytes[offset..], pos: 0 };
+        let header = page.value(THRIFT_STRUCT);
+        let data = column.data.plain_bytes();
+        assert_eq!(header.field(1).int(), 0);
+        assert_eq!(header.field(2).int() as usize, data.len());
+        assert_eq!(header.field(5).field(1).int(), 3);
+        assert_eq!(&bytes[offset + page.pos..offset + page.pos + data.len()], &data[..]);
+        end = offset + page.pos + data.len();
+        assert_eq!(info.field(6).int() as usize, page.pos + data.len());
+    }
+    assert_eq!(end, footer_start);
+    assert_eq!(row_groups[0].field(2).int() as usize, end - PARQUET_MAGIC.len());
+
+    let key_value = meta.field(5).list();
+    assert_eq!(key_value.len(), 1);
+    assert_eq!(key_value[0].field(1).bytes(), b"config");
+    assert_eq!(key_value[0].field(2).bytes(), br#"{"dt":0.001}"#);
+    assert!(meta.field(6).bytes().starts_with(b"physics_calculations "));
+}
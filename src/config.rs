@@ -0,0 +1,405 @@
+// config.rs
+use crate::{
+    cluster::{ClusterBottle, Coupling},
+    constants::{
+        ADIABATIC_INDEX_AIR,
+        ATMOSFERIC_PRESSURE,
+        BOTTLE_VOLUME,
+        DRY_MASS,
+        GRAVITY,
+        INITIAL_ACCELERATION,
+        INITIAL_AIR_PRESSURE_PSI,
+        INITIAL_POSITION,
+        INITIAL_VELOCITY,
+        INITIAL_WATER_MASS,
+        LAUNCHING_ANGLE_DEG,
+        LAUNCH_TEMPERATURE_C,
+        NOZZLE_DIAMETER,
+        WATER_DENSITY,
+        WATER_VISCOSITY,
+    },
+    error::{check, SimulationError},
+    geodesy::LaunchSite,
+    logging::LoggingPolicy,
+    math_utils::vector_2d::Vector2D,
+    observer::Termination,
+    physics::{
+        bottle::{BottleCompliance, BottleSpec},
+        geometry::RocketGeometry,
+        nozzle::{DischargeModel, Nozzle},
+        stability::StabilityModel,
+    },
+    safety::SafetyPolicy,
+};
+
+/// Parámetros de entrada de una simulación.
+/// `Default` reproduce exactamente los valores de `constants.rs`.
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    // --- parámetros físicos ---
+    /// Gravedad [m/s²].
+    pub gravity: f64,
+    /// Densidad del agua [kg/m³].
+    pub water_density: f64,
+    /// Viscosidad dinámica del agua [Pa·s].
+    pub water_viscosity: f64,
+    /// Presión atmosférica [Pa].
+    pub atmospheric_pressure: f64,
+    /// Índice adiabático del aire.
+    pub adiabatic_index: f64,
+
+    // --- condiciones iniciales ---
+    /// Instante del lanzamiento [s].
+    pub initial_time: f64,
+    /// Posición del inicio de la rampa [m].
+    pub initial_position: Vector2D,
+    /// Velocidad al lanzar [m/s].
+    pub initial_velocity: Vector2D,
+    /// Aceleración al lanzar, la del primer registro [m/s²].
+    pub initial_acceleration: Vector2D,
+    /// Ángulo de lanzamiento sobre la horizontal [°].
+    pub launching_angle_deg: f64,
+    /// Carga de agua de la botella principal [kg].
+    pub initial_water_mass: f64,
+    /// Presión manométrica de lanzamiento [psi].
+    pub initial_air_pressure_psi: f64,
+    /// Temperatura de la botella al lanzar [°C].
+    pub launch_temperature_c: f64,
+
+    // --- cohete ---
+    /// Masa en seco [kg].
+    pub dry_mass: f64,
+    /// Deformación y límites de la botella principal.
+    pub bottle: BottleSpec,
+    /// Geometría, pérdidas y C_d(Re) de la tobera principal.
+    pub nozzle: Nozzle,
+
+    // --- cúmulo (vacío = una sola botella) ---
+    /// Botellas además de la principal.
+    pub cluster: Vec<ClusterBottle>,
+    /// Cómo se conectan las botellas del cúmulo.
+    pub coupling: Coupling,
+
+    // --- actitud (None = el cohete mantiene el ángulo de lanzamiento) ---
+    /// Modelo de cabeceo: aerodinámica, inercia, aire, viento y rampa.
+    pub stability: Option<StabilityModel>,
+    /// Si hay geometría, CP (Barrowman), CG e inercia se calculan con ella y se
+    /// actualizan al vaciarse la botella; `stability` solo aporta aire, viento y rampa.
+    /// `dry_mass` tiene que coincidir con `geometry.dry_mass()` (lo comprueba `validate`).
+    pub geometry: Option<RocketGeometry>,
+
+    // --- lugar de lanzamiento (None = solo coordenadas locales en metros) ---
+    /// Dónde está la rampa, para pasar la trayectoria a coordenadas geográficas.
+    pub launch_site: Option<LaunchSite>,
+
+    // --- parada (basta con una; vacío = hasta `t_max`) ---
+    /// Condiciones de parada de `run`.
+    pub termination: Vec<Termination>,
+
+    // --- seguridad ---
+    /// Margen exigido a las botellas al construir la simulación (rechazar o avisar).
+    pub safety: SafetyPolicy,
+
+    // --- salida ---
+    /// Qué pasos se guardan en los logs.
+    pub logging: LoggingPolicy,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            gravity: GRAVITY,
+            water_density: WATER_DENSITY,
+            water_viscosity: WATER_VISCOSITY,
+            atmospheric_pressure: ATMOSFERIC_PRESSURE,
+            adiabatic_index: ADIABATIC_INDEX_AIR,
+
+            initial_time: 0.0,
+            initial_position: INITIAL_POSITION,
+            initial_velocity: INITIAL_VELOCITY,
+            initial_acceleration: INITIAL_ACCELERATION,
+            launching_angle_deg: LAUNCHING_ANGLE_DEG,
+            initial_water_mass: INITIAL_WATER_MASS,
+            initial_air_pressure_psi: INITIAL_AIR_PRESSURE_PSI,
+            launch_temperature_c: LAUNCH_TEMPERATURE_C,
+
+            dry_mass: DRY_MASS,
+            bottle: BottleSpec {
+                name: "PET 2 L (rígida)".to_string(),
+                compliance: BottleCompliance::Rigid { volume: BOTTLE_VOLUME },
+                ..BottleSpec::pet_2l()
+            },
+            nozzle: Nozzle::ideal(NOZZLE_DIAMETER),
+
+            cluster: Vec::new(),
+            coupling: Coupling::Independent,
+
+            stability: None,
+            geometry: None,
+
+            launch_site: None,
+
+            termination: Termination::default_set(),
+
+            safety: SafetyPolicy::default(),
+
+            logging: LoggingPolicy::EveryStep,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Revisa rangos antes de simular: nada se recorta ni se sustituye en silencio.
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        check("gravity", self.gravity, self.gravity >= 0.0, ">= 0")?;
+        check("water_density", self.water_density, self.water_density > 0.0, "> 0")?;
+        check("water_viscosity", self.water_viscosity, self.water_viscosity >= 0.0, ">= 0")?;
+        let atmospheric = self.atmospheric_pressure;
+        check("atmospheric_pressure", atmospheric, atmospheric > 0.0, "> 0")?;
+        check("adiabatic_index", self.adiabatic_index, self.adiabatic_index >= 1.0, ">= 1")?;
+
+        check("initial_time", self.initial_time, true, "finito")?;
+        for (name, v) in [
+            ("initial_position", self.initial_position),
+            ("initial_velocity", self.initial_velocity),
+            ("initial_acceleration", self.initial_acceleration),
+        ] {
+            check(&format!("{}.x", name), v.x, true, "finito")?;
+            check(&format!("{}.y", name), v.y, true, "finito")?;
+        }
+        let angle = self.launching_angle_deg;
+        check("launching_angle_deg", angle, angle.abs() <= 180.0, "entre -180 y 180")?;
+        let psi = self.initial_air_pressure_psi;
+        check("initial_air_pressure_psi", psi, psi >= 0.0, ">= 0 (manométrica)")?;
+        let temperature = self.launch_temperature_c;
+        check("launch_temperature_c", temperature, temperature > -273.15, "> -273.15")?;
+        let factor = self.safety.safety_factor;
+        check("safety.safety_factor", factor, factor >= 1.0, ">= 1")?;
+        check("dry_mass", self.dry_mass, self.dry_mass > 0.0, "> 0")?;
+
+        // botella principal y cúmulo: carga, tobera y sitio para el aire
+        let bottles = std::iter::once((&self.bottle, &self.nozzle, self.initial_water_mass))
+            .chain(self.cluster.iter().map(|c| (&c.bottle, &c.nozzle, c.water_mass)));
+        for (i, (bottle, nozzle, water_mass)) in bottles.enumerate() {
+            let prefix = if i == 0 { String::new() } else { format!("cluster[{}].", i - 1) };
+            let water_name = if i == 0 { "initial_water_mass" } else { "water_mass" };
+            check(&format!("{}{}", prefix, water_name), water_mass, water_mass >= 0.0, ">= 0")?;
+            validate_bottle(&format!("{}bottle", prefix), bottle)?;
+            let volume = bottle.compliance.nominal_volume();
+            validate_nozzle(&format!("{}nozzle", prefix), nozzle)?;
+            let water_volume = water_mass / self.water_density;
+            if water_volume >= volume {
+                return Err(SimulationError::OverfilledBottle {
+                    bottle: i,
+                    water_volume,
+                    bottle_volume: volume,
+                });
+            }
+        }
+
+        if let Some(model) = &self.stability {
+            let d = model.reference_diameter;
+            check("stability.reference_diameter", d, d > 0.0, "> 0")?;
+            let inertia = model.pitch_inertia;
+            check("stability.pitch_inertia", inertia, inertia > 0.0, "> 0")?;
+            let rho = model.air_density;
+            check("stability.air_density", rho, rho >= 0.0, ">= 0")?;
+            let rail = model.launch_rail_length;
+            check("stability.launch_rail_length", rail, rail >= 0.0, ">= 0")?;
+            for (name, value) in [
+                ("stability.normal_force_slope", model.normal_force_slope),
+                ("stability.center_of_pressure", model.center_of_pressure),
+                ("stability.center_of_gravity", model.center_of_gravity),
+                ("stability.wind_speed", model.wind_speed),
+            ] {
+                check(name, value, true, "finito")?;
+            }
+        }
+
+        if let Some(geometry) = &self.geometry {
+            validate_geometry(geometry)?;
+            // la masa en seco sale de dos sitios: si no cuadran, CG e inercia no son del cohete
+            let (mass, layout) = (self.dry_mass, geometry.dry_mass());
+            let matches = (mass - layout).abs() <= 1e-9 * mass.abs().max(layout.abs());
+            check("dry_mass", mass, matches, "igual a geometry.dry_mass()")?;
+        }
+
+        if let Some(site) = &self.launch_site {
+            let lat = site.latitude_deg;
+            check("launch_site.latitude_deg", lat, lat.abs() <= 90.0, "entre -90 y 90")?;
+            let lon = site.longitude_deg;
+            check("launch_site.longitude_deg", lon, lon.abs() <= 180.0, "entre -180 y 180")?;
+            check("launch_site.elevation_m", site.elevation_m, true, "finita")?;
+            check("launch_site.azimuth_deg", site.azimuth_deg, true, "finito")?;
+        }
+
+        match self.logging {
+            LoggingPolicy::EveryStep | LoggingPolicy::EveryNSteps(_) => {}
+            LoggingPolicy::FixedInterval(i) => check("logging.interval", i, i >= 0.0, ">= 0")?,
+            LoggingPolicy::Adaptive { burn_interval, coast_interval } => {
+                let (burn, coast) = (burn_interval, coast_interval);
+                check("logging.burn_interval", burn, burn >= 0.0, ">= 0")?;
+                check("logging.coast_interval", coast, coast >= 0.0, ">= 0")?;
+            }
+        }
+        for termination in &self.termination {
+            match *termination {
+                Termination::AltitudeBelow(h) => check("termination.altitude", h, true, "finita")?,
+                Termination::Time(t) => check("termination.time", t, true, "finito")?,
+                Termination::Landing | Termination::Event(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_geometry(geometry: &RocketGeometry) -> Result<(), SimulationError> {
+    let d = geometry.body_diameter;
+    check("geometry.body_diameter", d, d > 0.0, "> 0")?;
+    let nose = geometry.nose_length;
+    check("geometry.nose_length", nose, nose >= 0.0, ">= 0")?;
+    let body = geometry.body_length;
+    check("geometry.body_length", body, body > 0.0, "> 0")?;
+    let tank = geometry.water_tank_end;
+    let inside = tank > 0.0 && tank <= geometry.total_length();
+    check("geometry.water_tank_end", tank, inside, "dentro del cohete, en (0, longitud]")?;
+    for (i, component) in geometry.components.iter().enumerate() {
+        let name = |field: &str| format!("geometry.components[{}].{}", i, field);
+        let mass = component.mass;
+        check(&name("mass"), mass, mass >= 0.0, ">= 0")?;
+        check(&name("position"), component.position, true, "finita")?;
+    }
+    let mass = geometry.dry_mass();
+    check("geometry.dry_mass", mass, mass > 0.0, "> 0")?;
+    if let Some(fins) = &geometry.fins {
+        let count = fins.count as f64;
+        check("geometry.fins.count", count, fins.count > 0, "> 0")?;
+        check("geometry.fins.root_chord", fins.root_chord, fins.root_chord > 0.0, "> 0")?;
+        check("geometry.fins.tip_chord", fins.tip_chord, fins.tip_chord >= 0.0, ">= 0")?;
+        check("geometry.fins.span", fins.span, fins.span > 0.0, "> 0")?;
+        check("geometry.fins.sweep", fins.sweep, true, "finito")?;
+        check("geometry.fins.position", fins.position, true, "finita")?;
+    }
+    Ok(())
+}
+
+fn validate_bottle(name: &str, bottle: &BottleSpec) -> Result<(), SimulationError> {
+    match &bottle.compliance {
+        BottleCompliance::Rigid { .. } => {}
+        BottleCompliance::Linear { expansion_per_pa, .. } => {
+            let k = *expansion_per_pa;
+            check(&format!("{}.expansion_per_pa", name), k, k >= 0.0, ">= 0 (no encoge)")?;
+        }
+        BottleCompliance::Table { points } => {
+            // la interpolación supone puntos ordenados; vacía daría volumen 0 sin avisar
+            let count = points.len() as f64;
+            check(&format!("{}.points", name), count, !points.is_empty(), "al menos un punto")?;
+            let mut previous = f64::NEG_INFINITY;
+            for (i, &(pressure, volume)) in points.iter().enumerate() {
+                let point = |field: &str| format!("{}.points[{}].{}", name, i, field);
+                let increasing = pressure > previous;
+                check(&point("pressure"), pressure, increasing, "estrictamente creciente")?;
+                check(&point("volume"), volume, volume > 0.0, "> 0")?;
+                previous = pressure;
+            }
+        }
+    }
+    let volume = bottle.compliance.nominal_volume();
+    check(&format!("{}.volume", name), volume, volume > 0.0, "> 0")?;
+    let rated = bottle.rated_pressure_pa;
+    check(&format!("{}.rated_pressure_pa", name), rated, rated > 0.0, "> 0")?;
+    let burst = bottle.burst_pressure_pa;
+    check(&format!("{}.burst_pressure_pa", name), burst, burst >= rated, ">= rated_pressure_pa")?;
+    let temperature = bottle.rated_temperature_c;
+    check(&format!("{}.rated_temperature_c", name), temperature, temperature > -273.15, "> -273.15")
+}
+
+fn validate_nozzle(name: &str, nozzle: &Nozzle) -> Result<(), SimulationError> {
+    let d = nozzle.exit_diameter;
+    check(&format!("{}.exit_diameter", name), d, d > 0.0, "> 0")?;
+    let k = nozzle.contraction_loss;
+    check(&format!("{}.contraction_loss", name), k, k >= 0.0, ">= 0")?;
+    match nozzle.discharge {
+        DischargeModel::Ideal => Ok(()),
+        DischargeModel::Constant(c_d) => {
+            let name = format!("{}.discharge_coefficient", name);
+            check(&name, c_d, c_d > 0.0 && c_d <= 1.0, "en (0, 1]; sin corrección, `Ideal`")
+        }
+        DischargeModel::ShortTube { cd_max, length_over_diameter } => {
+            check(&format!("{}.cd_max", name), cd_max, cd_max > 0.0 && cd_max <= 1.0, "en (0, 1]")?;
+            let ratio = length_over_diameter;
+            check(&format!("{}.length_over_diameter", name), ratio, ratio >= 0.0, ">= 0")
+        }
+    }
+}
+
+/// La configuración por defecto es válida; lo absurdo se rechaza con el campo culpable.
+#[test]
+fn validation_rejects_bad_inputs() {
+    assert_eq!(SimulationConfig::default().validate(), Ok(()));
+
+    let bad = |config: SimulationConfig| config.validate().unwrap_err();
+    let name_of = |error: SimulationError| match error {
+        SimulationError::InvalidParameter { name, .. } => name,
+        other => panic!("{:?}", other),
+    };
+    let cd_zero = SimulationConfig {
+        nozzle: Nozzle::custom(NOZZLE_DIAMETER, 0.0),
+        ..SimulationConfig::default()
+    };
+    assert_eq!(name_of(bad(cd_zero)), "nozzle.discharge_coefficient");
+    let no_air = SimulationConfig { initial_water_mass: 2.5, ..SimulationConfig::default() };
+    assert!(matches!(bad(no_air), SimulationError::OverfilledBottle { bottle: 0, .. }));
+    let nan = SimulationConfig { dry_mass: f64::NAN, ..SimulationConfig::default() };
+    assert_eq!(name_of(bad(nan)), "dry_mass");
+    let mut cluster = SimulationConfig::default();
+    cluster.cluster.push(ClusterBottle {
+        bottle: cluster.bottle.clone(),
+        nozzle: cluster.nozzle.clone(),
+        water_mass: -0.1,
+    });
+    assert_eq!(name_of(bad(cluster)), "cluster[0].water_mass");
+
+    // botella: curva de hinchado y límites
+    let with_compliance = |compliance: BottleCompliance| {
+        let mut config = SimulationConfig::default();
+        config.bottle.compliance = compliance;
+        config
+    };
+    let shrinking = BottleCompliance::Linear { nominal_volume: 2e-3, expansion_per_pa: -1e-9 };
+    assert_eq!(name_of(bad(with_compliance(shrinking))), "bottle.expansion_per_pa");
+    let empty = BottleCompliance::Table { points: Vec::new() };
+    assert_eq!(name_of(bad(with_compliance(empty))), "bottle.points");
+    let points = vec![(0.0, 2e-3), (2e5, 2.05e-3), (1e5, 2.02e-3)];
+    let unsorted = BottleCompliance::Table { points };
+    assert_eq!(name_of(bad(with_compliance(unsorted))), "bottle.points[2].pressure");
+    let repeated = BottleCompliance::Table { points: vec![(0.0, 2e-3), (0.0, 2.01e-3)] };
+    assert_eq!(name_of(bad(with_compliance(repeated))), "bottle.points[1].pressure");
+    let hollow = BottleCompliance::Table { points: vec![(0.0, 2e-3), (1e5, 0.0)] };
+    assert_eq!(name_of(bad(with_compliance(hollow))), "bottle.points[1].volume");
+    let mut weak = SimulationConfig::default();
+    weak.bottle.burst_pressure_pa = weak.bottle.rated_pressure_pa * 0.5;
+    assert_eq!(name_of(bad(weak)), "bottle.burst_pressure_pa");
+
+    // geometría y lugar de lanzamiento
+    let geometry = RocketGeometry::two_liter_finned();
+    let designed = SimulationConfig {
+        dry_mass: geometry.dry_mass(),
+        geometry: Some(geometry.clone()),
+        ..SimulationConfig::default()
+    };
+    assert_eq!(designed.validate(), Ok(()));
+    let mismatch = SimulationConfig { dry_mass: 1.0, ..designed.clone() };
+    assert_eq!(name_of(bad(mismatch)), "dry_mass");
+    let mut thin = designed.clone();
+    thin.geometry.as_mut().unwrap().body_diameter = 0.0;
+    assert_eq!(name_of(bad(thin)), "geometry.body_diameter");
+    let mut negative = designed.clone();
+    negative.geometry.as_mut().unwrap().components[1].mass = -0.15;
+    assert_eq!(name_of(bad(negative)), "geometry.components[1].mass");
+    let pole = SimulationConfig {
+        launch_site: Some(LaunchSite::new(91.0, 0.0, 0.0, 0.0)),
+        ..SimulationConfig::default()
+    };
+    assert_eq!(name_of(bad(pole)), "launch_site.latitude_deg");
+}
@@ -1,19 +1,44 @@
 // constants.rs
 use crate::math_utils::vector_2d::Vector2D;
 
-pub const GRAVITY: f64 = 9.81; // m/s^2 (standard approx.)
-pub const WATER_DENSITY: f64 = 1000.0; // kg/m^3
-pub const ATMOSFERIC_PRESSURE: f64 = 101325.0; // Pa at sea level
-pub const ADIABATIC_INDEX_AIR: f64 = 1.4; // for air
+/// Gravedad estándar aproximada [m/s²].
+pub const GRAVITY: f64 = 9.81;
+/// Densidad del agua [kg/m³].
+pub const WATER_DENSITY: f64 = 1000.0;
+/// Viscosidad dinámica del agua a unos 20 °C [Pa·s].
+pub const WATER_VISCOSITY: f64 = 1.0e-3;
+/// Presión atmosférica al nivel del mar [Pa].
+pub const ATMOSFERIC_PRESSURE: f64 = 101325.0;
+/// Densidad del aire al nivel del mar [kg/m³].
+pub const AIR_DENSITY: f64 = 1.225;
+/// Índice adiabático del aire.
+pub const ADIABATIC_INDEX_AIR: f64 = 1.4;
+/// Pascales por psi.
+pub const PSI_TO_PA: f64 = 6894.757293168361;
+/// Paso de integración más largo admitido [s].
+pub const MAX_TIME_STEP: f64 = 0.01;
+/// Tope de cada corrida en barridos y estudios de convergencia; un cohete de agua no vuela
+/// tanto [s].
+pub const MAX_FLIGHT_TIME: f64 = 60.0;
 
+/// Posición inicial por defecto [m].
 pub const INITIAL_POSITION: Vector2D = Vector2D::ZERO;
+/// Velocidad inicial por defecto [m/s].
 pub const INITIAL_VELOCITY: Vector2D = Vector2D::ZERO;
+/// Aceleración inicial por defecto [m/s²].
 pub const INITIAL_ACCELERATION: Vector2D = Vector2D::ZERO;
-pub const LAUNCHING_ANGLE_DEG: f64 = 45.0; // degrees
-pub const DRY_MASS: f64 = 0.6; // kg
-pub const INITIAL_WATER_MASS: f64 = 1.0; // kg
-pub const INITIAL_AIR_PRESSURE_PSI: f64 = 45.0; // psi
-pub const DISCHARGE_COEFFICIENT : f64 = 0.0;
+/// Ángulo de lanzamiento sobre la horizontal por defecto [°].
+pub const LAUNCHING_ANGLE_DEG: f64 = 45.0;
+/// Masa en seco por defecto [kg].
+pub const DRY_MASS: f64 = 0.6;
+/// Carga de agua por defecto [kg].
+pub const INITIAL_WATER_MASS: f64 = 1.0;
+/// Presión manométrica de lanzamiento por defecto [psi].
+pub const INITIAL_AIR_PRESSURE_PSI: f64 = 45.0;
+/// Temperatura de la botella al lanzar por defecto [°C].
+pub const LAUNCH_TEMPERATURE_C: f64 = 20.0;
 
-pub const BOTTLE_VOLUME : f64 = 0.002; // m^3 (2 liters)
-pub const NOZZLE_DIAMETER : f64 = 0.021; // m
\ No newline at end of file
+/// Volumen de la botella por defecto, 2 litros [m³].
+pub const BOTTLE_VOLUME : f64 = 0.002;
+/// Diámetro de salida de la tobera por defecto [m].
+pub const NOZZLE_DIAMETER : f64 = 0.021;
@@ -0,0 +1,172 @@
+// convergence.rs
+use std::fmt;
+
+use crate::{
+    config::SimulationConfig,
+    constants::MAX_FLIGHT_TIME,
+    error::{check, SimulationError},
+    logging::NullLogger,
+    simulation::Simulation,
+};
+
+/// Una corrida del estudio.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConvergenceRun {
+    /// Paso de integración [s].
+    pub step: f64,
+    /// Apogeo con ese paso [m].
+    pub apogee: f64,
+    /// Alcance con ese paso [m].
+    pub range: f64,
+}
+
+/// Cómo converge una magnitud al reducir el paso.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConvergenceEstimate {
+    /// Orden observado p (error ≈ C·dt^p): pendiente del ajuste por mínimos cuadrados de
+    /// log|f(dt) - f(dt/2)| frente a log dt. Con todas las corridas y no solo las tres
+    /// últimas, porque el fin de propulsión cae entre pasos y mete ruido.
+    /// `None` si las diferencias no bajan con el paso.
+    pub order: Option<f64>,
+    /// Extrapolación de Richardson al paso nulo con las dos corridas más finas;
+    /// sin orden, el valor más fino.
+    pub extrapolated: f64,
+}
+
+impl ConvergenceEstimate {
+    // `values[i]` con paso `steps[i]`, cada paso la mitad del anterior
+    fn from_values(steps: &[f64], values: &[f64]) -> Self {
+        let finest = values.last().copied().unwrap_or(f64::NAN);
+        let points: Vec<(f64, f64)> = values
+            .windows(2)
+            .zip(steps)
+            .filter(|(pair, _)| pair[0] != pair[1])
+            .map(|(pair, step)| (step.ln(), (pair[0] - pair[1]).abs().ln()))
+            .collect();
+        if points.len() < 2 {
+            return Self { order: None, extrapolated: finest };
+        }
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+        let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        let order = covariance / variance;
+        if !order.is_finite() || order <= 0.0 {
+            return Self { order: None, extrapolated: finest };
+        }
+        let previous = values[values.len() - 2];
+        let extrapolated = finest + (finest - previous) / (2f64.powf(order) - 1.0);
+        Self { order: Some(order), extrapolated }
+    }
+
+    /// Error relativo de `value` frente al extrapolado.
+    pub fn relative_error(&self, value: f64) -> f64 {
+        ((value - self.extrapolated) / self.extrapolated).abs()
+    }
+}
+
+/// Estudio de convergencia con el paso: la misma configuración con el paso a la mitad
+/// cada vez, orden observado y extrapolación de Richardson del apogeo y el alcance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvergenceStudy {
+    /// Corridas, del paso más largo al más corto.
+    pub runs: Vec<ConvergenceRun>,
+    /// Convergencia del apogeo.
+    pub apogee: ConvergenceEstimate,
+    /// Convergencia del alcance.
+    pub range: ConvergenceEstimate,
+    /// Error relativo admitido.
+    pub tolerance: f64,
+    /// El paso probado más largo desde el que apogeo y alcance, con ese paso y con todos
+    /// los más cortos, quedan dentro de `tolerance` respecto a los extrapolados.
+    pub recommended_step: Option<f64>,
+}
+
+impl ConvergenceStudy {
+    /// Corre `config` con `coarsest_step` y `refinements` mitades más (al menos 2, para
+    /// tener tres corridas con las que estimar el orden).
+    pub fn run(
+        config: &SimulationConfig,
+        coarsest_step: f64,
+        refinements: usize,
+        tolerance: f64,
+    ) -> Result<Self, SimulationError> {
+        check("refinements", refinements as f64, refinements >= 2, ">= 2")?;
+        check("tolerance", tolerance, tolerance > 0.0, "> 0")?;
+
+        let mut runs = Vec::with_capacity(refinements + 1);
+        let mut step = coarsest_step;
+        for _ in 0..=refinements {
+            // solo cuenta el resumen: con pasos finos, guardar cada registro sobra
+            let mut sim = Simulation::with_config(step, config.clone())?;
+            sim.set_logger(Box::new(NullLogger));
+            sim.run(MAX_FLIGHT_TIME)?;
+            let summary = sim.summary();
+            runs.push(ConvergenceRun { step, apogee: summary.apogee, range: summary.range });
+            step /= 2.0;
+        }
+
+        let steps: Vec<f64> = runs.iter().map(|r| r.step).collect();
+        let apogees: Vec<f64> = runs.iter().map(|r| r.apogee).collect();
+        let ranges: Vec<f64> = runs.iter().map(|r| r.range).collect();
+        let apogee = ConvergenceEstimate::from_values(&steps, &apogees);
+        let range = ConvergenceEstimate::from_values(&steps, &ranges);
+        let converged = |r: &ConvergenceRun| {
+            apogee.relative_error(r.apogee) <= tolerance
+                && range.relative_error(r.range) <= tolerance
+        };
+        let first = (0..runs.len()).find(|&i| runs[i..].iter().all(converged));
+        let recommended_step = first.map(|i| runs[i].step);
+        Ok(Self { runs, apogee, range, tolerance, recommended_step })
+    }
+}
+
+impl fmt::Display for ConvergenceStudy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Convergencia con el paso (tolerancia {:.3} %):", self.tolerance * 100.0)?;
+        writeln!(f, "{:>12} {:>12} {:>12}", "dt [s]", "apogeo [m]", "alcance [m]")?;
+        for run in &self.runs {
+            writeln!(f, "{:>12.3e} {:>12.4} {:>12.4}", run.step, run.apogee, run.range)?;
+        }
+        for (name, estimate) in [("Apogeo", &self.apogee), ("Alcance", &self.range)] {
+            match estimate.order {
+                Some(order) => writeln!(
+                    f,
+                    "{}: orden {:.2}, extrapolado {:.4} m",
+                    name, order, estimate.extrapolated
+                )?,
+                None => writeln!(
+                    f,
+                    "{}: orden no estimable, {:.4} m con el paso más fino",
+                    name, estimate.extrapolated
+                )?,
+            }
+        }
+        match self.recommended_step {
+            Some(step) => write!(f, "Paso recomendado: {:.3e} s", step),
+            None => write!(f, "Ningún paso probado cumple la tolerancia; pruebe más mitades"),
+        }
+    }
+}
+
+/// Con un error exacto de orden 2 se recuperan el orden y el límite; con el simulador,
+/// el integrador es de primer orden.
+#[test]
+fn order_and_richardson_extrapolation() {
+    let steps = [0.04, 0.02, 0.01, 0.005];
+    let values: Vec<f64> = steps.iter().map(|dt| 3.0 + 5.0 * dt * dt).collect();
+    let estimate = ConvergenceEstimate::from_values(&steps, &values);
+    assert!((estimate.order.unwrap() - 2.0).abs() < 1e-9);
+    assert!((estimate.extrapolated - 3.0).abs() < 1e-12);
+    let flat = ConvergenceEstimate::from_values(&steps, &[1.0; 4]);
+    assert_eq!(flat, ConvergenceEstimate { order: None, extrapolated: 1.0 });
+
+    let config = SimulationConfig::default();
+    assert!(ConvergenceStudy::run(&config, 1e-3, 1, 1e-3).is_err());
+    let study = ConvergenceStudy::run(&config, 1e-2, 4, 2e-2).unwrap();
+    assert_eq!(study.runs.len(), 5);
+    let order = study.apogee.order.unwrap();
+    assert!(order > 0.5 && order < 2.0, "{}", study);
+    assert!(study.recommended_step.is_some(), "{}", study);
+}
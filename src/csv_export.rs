@@ -0,0 +1,575 @@
+// csv_export.rs
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{
+    constants::PSI_TO_PA,
+    logging::{LogField, LogRecord, FIELDS},
+};
+
+const FOOT: f64 = 0.3048; // [m]
+const POUND: f64 = 0.453_592_37; // [kg]
+const POUND_FORCE: f64 = 4.448_221_615_260_5; // [N]
+
+/// Una columna de los logs. Los valores se guardan en SI; la conversión la hace `CsvOptions`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvColumn {
+    /// Tiempo.
+    Time,
+    /// Posición horizontal.
+    X,
+    /// Altura.
+    Y,
+    /// Velocidad horizontal.
+    Vx,
+    /// Velocidad vertical.
+    Vy,
+    /// Módulo de la velocidad.
+    VelocityMagnitude,
+    /// Aceleración horizontal.
+    Ax,
+    /// Aceleración vertical.
+    Ay,
+    /// Módulo de la aceleración.
+    AccelerationMagnitude,
+    /// Agua que queda.
+    WaterMass,
+    /// Masa total.
+    TotalMass,
+    /// Empuje.
+    Thrust,
+    /// Presión del depósito más cargado.
+    Pressure,
+    /// Cabeceo.
+    Pitch,
+    /// Velocidad de cabeceo.
+    PitchRate,
+    /// Ángulo de ataque.
+    AngleOfAttack,
+    /// Latitud (solo con lugar de lanzamiento).
+    Latitude,
+    /// Longitud (solo con lugar de lanzamiento).
+    Longitude,
+    /// Altitud sobre el elipsoide (solo con lugar de lanzamiento).
+    Altitude,
+    /// Presión de la botella N (desde 1).
+    BottlePressure(usize),
+    /// Agua de la botella N (desde 1).
+    BottleWaterMass(usize),
+    /// Posición lateral (solo 6-DOF).
+    Z,
+    /// Velocidad lateral (solo 6-DOF).
+    Vz,
+    /// Parte escalar del cuaternión de actitud, cuerpo -> mundo (solo 6-DOF).
+    Qw,
+    /// Componente x del cuaternión de actitud (solo 6-DOF).
+    Qx,
+    /// Componente y del cuaternión de actitud (solo 6-DOF).
+    Qy,
+    /// Componente z del cuaternión de actitud (solo 6-DOF).
+    Qz,
+    /// Velocidad angular en ejes del mundo, componente x (solo 6-DOF).
+    Wx,
+    /// Velocidad angular en ejes del mundo, componente y (solo 6-DOF).
+    Wy,
+    /// Velocidad angular en ejes del mundo, componente z (solo 6-DOF).
+    Wz,
+}
+
+/// Magnitud física de una columna, para elegir unidad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Quantity {
+    Time,
+    Length,
+    Velocity,
+    Acceleration,
+    Mass,
+    Force,
+    Pressure,
+    Angle,
+    AngularRate,
+    Ratio,
+}
+
+// todo lo de una columna: nombre de máquina (sin unidad), etiquetas, magnitud y decimales
+// del CSV de siempre
+struct ColumnSpec {
+    key: Cow<'static, str>,
+    english: Cow<'static, str>,
+    spanish: Cow<'static, str>,
+    quantity: Quantity,
+    digits: usize,
+}
+
+impl ColumnSpec {
+    fn new(
+        key: impl Into<Cow<'static, str>>,
+        english: impl Into<Cow<'static, str>>,
+        spanish: impl Into<Cow<'static, str>>,
+        quantity: Quantity,
+    ) -> Self {
+        let (key, english, spanish) = (key.into(), english.into(), spanish.into());
+        Self { key, english, spanish, quantity, digits: 6 }
+    }
+}
+
+impl CsvColumn {
+    // una fila por columna; grados con 8 decimales (~1 mm), altitud con 3, el resto con 6
+    fn spec(self) -> ColumnSpec {
+        use Quantity::*;
+        fn fixed(key: &'static str, en: &'static str, es: &'static str, q: Quantity) -> ColumnSpec {
+            ColumnSpec::new(key, en, es, q)
+        }
+        match self {
+            Self::Time => fixed("time", "Time", "Tiempo", Time),
+            Self::X => fixed("x", "Downrange distance", "Distancia horizontal", Length),
+            Self::Y => fixed("y", "Height", "Altura", Length),
+            Self::Vx => fixed("vx", "Horizontal velocity", "Velocidad horizontal", Velocity),
+            Self::Vy => fixed("vy", "Vertical velocity", "Velocidad vertical", Velocity),
+            Self::VelocityMagnitude => fixed("v_mag", "Speed", "Rapidez", Velocity),
+            Self::Ax => {
+                fixed("ax", "Horizontal acceleration", "Aceleración horizontal", Acceleration)
+            }
+            Self::Ay => fixed("ay", "Vertical acceleration", "Aceleración vertical", Acceleration),
+            Self::AccelerationMagnitude => {
+                fixed("a_mag", "Acceleration", "Aceleración", Acceleration)
+            }
+            Self::WaterMass => fixed("water_mass", "Water mass", "Masa de agua", Mass),
+            Self::TotalMass => fixed("total_mass", "Total mass", "Masa total", Mass),
+            Self::Thrust => fixed("thrust", "Thrust", "Empuje", Force),
+            Self::Pressure => fixed("pressure", "Pressure", "Presión", Pressure),
+            Self::Pitch => fixed("pitch", "Pitch", "Cabeceo", Angle),
+            Self::PitchRate => {
+                fixed("pitch_rate", "Pitch rate", "Velocidad de cabeceo", AngularRate)
+            }
+            Self::AngleOfAttack => fixed("aoa", "Angle of attack", "Ángulo de ataque", Angle),
+            Self::Latitude => {
+                ColumnSpec { digits: 8, ..fixed("latitude", "Latitude", "Latitud", Angle) }
+            }
+            Self::Longitude => {
+                ColumnSpec { digits: 8, ..fixed("longitude", "Longitude", "Longitud", Angle) }
+            }
+            Self::Altitude => {
+                ColumnSpec { digits: 3, ..fixed("altitude", "Altitude", "Altitud", Length) }
+            }
+            Self::BottlePressure(b) => ColumnSpec::new(
+                format!("bottle{}_pressure", b),
+                format!("Bottle {} pressure", b),
+                format!("Presión botella {}", b),
+                Pressure,
+            ),
+            Self::BottleWaterMass(b) => ColumnSpec::new(
+                format!("bottle{}_water_mass", b),
+                format!("Bottle {} water", b),
+                format!("Agua botella {}", b),
+                Mass,
+            ),
+            Self::Z => fixed("z", "Crossrange distance", "Distancia lateral", Length),
+            Self::Vz => fixed("vz", "Crossrange velocity", "Velocidad lateral", Velocity),
+            Self::Qw => fixed("qw", "Attitude quaternion w", "Cuaternión de actitud w", Ratio),
+            Self::Qx => fixed("qx", "Attitude quaternion x", "Cuaternión de actitud x", Ratio),
+            Self::Qy => fixed("qy", "Attitude quaternion y", "Cuaternión de actitud y", Ratio),
+            Self::Qz => fixed("qz", "Attitude quaternion z", "Cuaternión de actitud z", Ratio),
+            Self::Wx => fixed("wx", "Angular velocity x", "Velocidad angular x", AngularRate),
+            Self::Wy => fixed("wy", "Angular velocity y", "Velocidad angular y", AngularRate),
+            Self::Wz => fixed("wz", "Angular velocity z", "Velocidad angular z", AngularRate),
+        }
+    }
+
+    // los ángulos se guardan en rad y se escriben en grados; latitud y longitud ya lo están
+    pub(crate) fn is_stored_in_radians(self) -> bool {
+        matches!(self.spec().quantity, Quantity::Angle | Quantity::AngularRate)
+            && !matches!(self, Self::Latitude | Self::Longitude)
+    }
+}
+
+/// Unidades de longitud, masa y fuerza (y sus derivadas).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnitSystem {
+    /// m, kg, N.
+    #[default]
+    Si,
+    /// ft, lb, lbf.
+    Imperial,
+}
+
+/// Unidad de las columnas de presión.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PressureUnit {
+    /// Pascales.
+    #[default]
+    Pa,
+    /// Libras por pulgada cuadrada.
+    Psi,
+}
+
+/// Presión absoluta o manométrica en las columnas de presión.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PressureReference {
+    /// Sobre el vacío.
+    #[default]
+    Absolute,
+    /// Sobre la presión atmosférica de la simulación.
+    Gauge,
+}
+
+/// Cómo se escriben los números.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberFormat {
+    /// Los decimales de siempre de cada columna: 8 en latitud y longitud, 3 en altitud,
+    /// 6 en el resto.
+    #[default]
+    Standard,
+    /// Decimales fijos en todas las columnas.
+    Fixed(usize),
+    /// Notación científica con tantos decimales en la mantisa.
+    Scientific(usize),
+}
+
+/// Estilo del encabezado.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeaderLanguage {
+    /// Nombres de máquina con la unidad de sufijo (`thrust_N`), como siempre.
+    #[default]
+    Code,
+    /// Nombres en inglés con la unidad entre corchetes (`Thrust [N]`).
+    English,
+    /// Nombres en castellano con la unidad entre corchetes (`Empuje [N]`).
+    Spanish,
+}
+
+/// Opciones de exportación. `Default` reproduce el CSV de siempre.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvOptions {
+    /// Separador de campos.
+    pub delimiter: char,
+    /// None = todas las columnas disponibles para esa simulación.
+    pub columns: Option<Vec<CsvColumn>>,
+    /// Unidades de longitud, masa y fuerza.
+    pub units: UnitSystem,
+    /// Unidad de las presiones.
+    pub pressure_unit: PressureUnit,
+    /// Presiones absolutas o manométricas.
+    pub pressure_reference: PressureReference,
+    /// Decimales o notación científica.
+    pub number_format: NumberFormat,
+    /// BOM UTF-8 al principio (Excel lo necesita para los acentos).
+    pub bom: bool,
+    /// Estilo del encabezado.
+    pub header_language: HeaderLanguage,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            columns: None,
+            units: UnitSystem::Si,
+            pressure_unit: PressureUnit::Pa,
+            pressure_reference: PressureReference::Absolute,
+            number_format: NumberFormat::Standard,
+            bom: true,
+            header_language: HeaderLanguage::Code,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Atajo para unidades imperiales con presión manométrica en psi.
+    pub fn imperial() -> Self {
+        Self {
+            units: UnitSystem::Imperial,
+            pressure_unit: PressureUnit::Psi,
+            pressure_reference: PressureReference::Gauge,
+            ..Self::default()
+        }
+    }
+
+    // (factor SI -> unidad, sufijo de máquina, símbolo legible)
+    fn unit(&self, quantity: Quantity) -> (f64, &'static str, &'static str) {
+        let imperial = self.units == UnitSystem::Imperial;
+        match quantity {
+            Quantity::Time => (1.0, "s", "s"),
+            Quantity::Length if imperial => (1.0 / FOOT, "ft", "ft"),
+            Quantity::Length => (1.0, "m", "m"),
+            Quantity::Velocity if imperial => (1.0 / FOOT, "ft_s", "ft/s"),
+            Quantity::Velocity => (1.0, "m_s", "m/s"),
+            Quantity::Acceleration if imperial => (1.0 / FOOT, "ft_s2", "ft/s²"),
+            Quantity::Acceleration => (1.0, "m_s2", "m/s²"),
+            Quantity::Mass if imperial => (1.0 / POUND, "lb", "lb"),
+            Quantity::Mass => (1.0, "kg", "kg"),
+            Quantity::Force if imperial => (1.0 / POUND_FORCE, "lbf", "lbf"),
+            Quantity::Force => (1.0, "N", "N"),
+            Quantity::Pressure => match self.pressure_unit {
+                PressureUnit::Pa => (1.0, "Pa", "Pa"),
+                PressureUnit::Psi => (1.0 / PSI_TO_PA, "psi", "psi"),
+            },
+            Quantity::Angle => (1.0, "deg", "°"),
+            Quantity::AngularRate => (1.0, "deg_s", "°/s"),
+            Quantity::Ratio => (1.0, "", ""),
+        }
+    }
+
+    /// Encabezado de una columna.
+    pub fn header(&self, column: CsvColumn) -> String {
+        let spec = column.spec();
+        let quantity = spec.quantity;
+        let (_, suffix, symbol) = self.unit(quantity);
+        let gauge = self.pressure_reference == PressureReference::Gauge;
+        match self.header_language {
+            HeaderLanguage::Code if quantity == Quantity::Pressure => {
+                let reference = if gauge { "gauge" } else { "abs" };
+                format!("{}_{}_{}", spec.key, reference, suffix)
+            }
+            HeaderLanguage::Code if suffix.is_empty() => spec.key.into_owned(),
+            HeaderLanguage::Code => format!("{}_{}", spec.key, suffix),
+            language => {
+                let label = match language {
+                    HeaderLanguage::English => spec.english,
+                    _ => spec.spanish,
+                };
+                if symbol.is_empty() {
+                    return label.into_owned();
+                }
+                let reference = match (quantity, gauge, language) {
+                    (Quantity::Pressure, true, HeaderLanguage::English) => " (gauge)",
+                    (Quantity::Pressure, false, HeaderLanguage::English) => " (abs)",
+                    (Quantity::Pressure, true, _) => " (man.)",
+                    (Quantity::Pressure, false, _) => " (abs.)",
+                    _ => "",
+                };
+                format!("{}{} [{}]", label, reference, symbol)
+            }
+        }
+    }
+
+    /// Convierte un valor SI (ángulos en rad salvo latitud/longitud) y le da formato.
+    pub fn format_value(
+        &self,
+        column: CsvColumn,
+        si_value: f64,
+        atmospheric_pressure: f64,
+    ) -> String {
+        let ColumnSpec { quantity, digits, .. } = column.spec();
+        let value = match column {
+            _ if column.is_stored_in_radians() => si_value.to_degrees(),
+            _ if quantity == Quantity::Pressure
+                && self.pressure_reference == PressureReference::Gauge =>
+            {
+                si_value - atmospheric_pressure
+            }
+            _ => si_value,
+        };
+        let value = value * self.unit(quantity).0;
+        match self.number_format {
+            NumberFormat::Standard => format!("{:.*}", digits, value),
+            NumberFormat::Fixed(digits) => format!("{:.*}", digits, value),
+            NumberFormat::Scientific(digits) => format!("{:.*e}", digits, value),
+        }
+    }
+
+    /// Escribe `rows` filas; `value(i, columna)` devuelve el valor SI de la fila `i`.
+    pub fn write<W: Write>(
+        &self,
+        writer: &mut W,
+        columns: &[CsvColumn],
+        rows: usize,
+        atmospheric_pressure: f64,
+        value: impl Fn(usize, CsvColumn) -> f64,
+    ) -> io::Result<()> {
+        self.write_header(writer, columns)?;
+        for i in 0..rows {
+            self.write_row(writer, columns, atmospheric_pressure, |c| value(i, c))?;
+        }
+        Ok(())
+    }
+
+    /// BOM (si toca) y línea de encabezados.
+    pub fn write_header<W: Write>(&self, writer: &mut W, columns: &[CsvColumn]) -> io::Result<()> {
+        if self.bom {
+            // BOM UTF-8 (Excel)
+            writer.write_all("\u{FEFF}".as_bytes())?;
+        }
+        let header: Vec<String> = columns.iter().map(|&c| self.header(c)).collect();
+        writeln!(writer, "{}", header.join(&self.delimiter.to_string()))
+    }
+
+    /// Una fila; `value(columna)` devuelve el valor SI.
+    pub fn write_row<W: Write>(
+        &self,
+        writer: &mut W,
+        columns: &[CsvColumn],
+        atmospheric_pressure: f64,
+        value: impl Fn(CsvColumn) -> f64,
+    ) -> io::Result<()> {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|&c| self.format_value(c, value(c), atmospheric_pressure))
+            .collect();
+        writeln!(writer, "{}", cells.join(&self.delimiter.to_string()))
+    }
+
+    /// Como `write`, a un archivo.
+    pub fn write_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        columns: &[CsvColumn],
+        rows: usize,
+        atmospheric_pressure: f64,
+        value: impl Fn(usize, CsvColumn) -> f64,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, columns, rows, atmospheric_pressure, value)?;
+        writer.flush()
+    }
+}
+
+/// Lee un CSV escrito con las opciones por defecto (encabezados de código, SI, presión
+/// absoluta). Las columnas que no conoce se ignoran; las que se grafican (tiempo, posición,
+/// velocidad, aceleración, masas, empuje y presión) son obligatorias, y si falta alguna
+/// (p. ej. un CSV en unidades imperiales o con encabezados en español) da error en vez de
+/// leerla como ceros. También si una fila trae menos valores que el encabezado.
+pub fn read_records<P: AsRef<Path>>(path: P) -> io::Result<Vec<LogRecord>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let text = std::fs::read_to_string(path)?;
+    let mut lines = text.trim_start_matches('\u{feff}').lines().filter(|l| !l.trim().is_empty());
+    let header = lines.next().ok_or_else(|| invalid("CSV vacío".to_string()))?;
+    let delimiter = [',', ';', '\t'].into_iter().find(|&d| header.contains(d)).unwrap_or(',');
+
+    let names = CsvOptions::default();
+    // los módulos se recalculan; las de cabeceo solo existen con modelo de estabilidad
+    let readable: Vec<&LogField> = FIELDS.iter().filter(|f| !f.is_derived()).collect();
+    let columns: Vec<Option<&LogField>> = header
+        .split(delimiter)
+        .map(|h| readable.iter().copied().find(|f| names.header(f.column) == h.trim()))
+        .collect();
+    let missing: Vec<String> = readable
+        .iter()
+        .filter(|f| !f.attitude && !columns.iter().flatten().any(|c| c.column == f.column))
+        .map(|f| names.header(f.column))
+        .collect();
+    if !missing.is_empty() {
+        return Err(invalid(format!(
+            "faltan las columnas {} (se leen CSV con el formato de CsvOptions::default())",
+            missing.join(", ")
+        )));
+    }
+
+    let mut records = Vec::new();
+    for (n, line) in lines.enumerate() {
+        let fields: Vec<&str> = line.split(delimiter).collect();
+        if fields.len() < columns.len() {
+            let (found, expected) = (fields.len(), columns.len());
+            return Err(invalid(format!(
+                "línea {}: {} valores, se esperaban {}",
+                n + 2,
+                found,
+                expected
+            )));
+        }
+        let mut record = LogRecord::default();
+        for (text, field) in fields.into_iter().zip(&columns) {
+            let Some(field) = field else { continue };
+            let value: f64 = text.trim().parse().map_err(|_| {
+                invalid(format!("línea {}: valor no numérico {:?}", n + 2, text))
+            })?;
+            let radians = field.column.is_stored_in_radians();
+            field.set(&mut record, if radians { value.to_radians() } else { value });
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Encabezados y valores en psi manométricos / pies, sin BOM y en notación científica.
+#[test]
+fn options_convert_units_and_headers() {
+    let columns = [CsvColumn::Time, CsvColumn::Y, CsvColumn::Pressure, CsvColumn::Thrust];
+    let options = CsvOptions {
+        delimiter: ';',
+        bom: false,
+        number_format: NumberFormat::Scientific(3),
+        ..CsvOptions::imperial()
+    };
+    let mut out = Vec::new();
+    let atm = 101_325.0;
+    options
+        .write(&mut out, &columns, 1, atm, |_, c| match c {
+            CsvColumn::Y => 3.048,
+            CsvColumn::Pressure => atm + 10.0 * PSI_TO_PA,
+            CsvColumn::Thrust => POUND_FORCE,
+            _ => 0.5,
+        })
+        .unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(
+        text,
+        "time_s;y_ft;pressure_gauge_psi;thrust_lbf\n5.000e-1;1.000e1;1.000e1;1.000e0\n"
+    );
+
+    let spanish = CsvOptions { header_language: HeaderLanguage::Spanish, ..CsvOptions::default() };
+    assert_eq!(spanish.header(CsvColumn::Pressure), "Presión (abs.) [Pa]");
+    let code = CsvOptions::default();
+    assert_eq!(code.header(CsvColumn::BottleWaterMass(2)), "bottle2_water_mass_kg");
+
+    // por defecto, la precisión de siempre de cada columna
+    let format = |column, value| code.format_value(column, value, atm);
+    assert_eq!(format(CsvColumn::Latitude, 40.5), "40.50000000");
+    assert_eq!(format(CsvColumn::Altitude, 650.0), "650.000");
+    assert_eq!(format(CsvColumn::Thrust, 1.5), "1.500000");
+    let fixed = CsvOptions { number_format: NumberFormat::Fixed(2), ..CsvOptions::default() };
+    assert_eq!(fixed.format_value(CsvColumn::Latitude, 40.5, atm), "40.50");
+}
+
+/// Un CSV por defecto se vuelve a leer; si faltan columnas que se grafican o valores de
+/// una fila, error en vez de ceros.
+#[test]
+fn read_records_requires_plotted_columns() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("read_records_{}.csv", std::process::id()));
+    let write = |options: &CsvOptions, columns: &[CsvColumn]| {
+        options.write_file(&path, columns, 2, 101_325.0, |i, c| match c {
+            CsvColumn::Time => i as f64 * 0.5,
+            CsvColumn::Thrust => 12.0,
+            _ => 1.0,
+        })
+    };
+    let all = [
+        CsvColumn::Time,
+        CsvColumn::X,
+        CsvColumn::Y,
+        CsvColumn::Vx,
+        CsvColumn::Vy,
+        CsvColumn::VelocityMagnitude,
+        CsvColumn::Ax,
+        CsvColumn::Ay,
+        CsvColumn::AccelerationMagnitude,
+        CsvColumn::WaterMass,
+        CsvColumn::TotalMass,
+        CsvColumn::Thrust,
+        CsvColumn::Pressure,
+    ];
+
+    write(&CsvOptions::default(), &all).unwrap();
+    let records = read_records(&path).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!((records[1].time, records[1].thrust), (0.5, 12.0));
+
+    let spanish = CsvOptions { header_language: HeaderLanguage::Spanish, ..CsvOptions::default() };
+    write(&spanish, &all).unwrap();
+    let error = read_records(&path).unwrap_err().to_string();
+    assert!(error.contains("time_s") && error.contains("thrust_N"), "{}", error);
+
+    write(&CsvOptions::default(), &all[..12]).unwrap();
+    let error = read_records(&path).unwrap_err().to_string();
+    assert!(error.contains("pressure_abs_Pa") && !error.contains("thrust_N"), "{}", error);
+
+    write(&CsvOptions::default(), &all).unwrap();
+    let mut text = std::fs::read_to_string(&path).unwrap();
+    text.push_str("1.0,2.0\n");
+    std::fs::write(&path, text).unwrap();
+    assert!(read_records(&path).unwrap_err().to_string().contains("línea 4"));
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,228 @@
+// energy.rs
+use std::fmt;
+
+use crate::math_utils::vector_2d::Vector2D;
+
+/// Adónde va la energía de un vuelo [J], acumulado paso a paso por `Simulation`.
+/// El aire cuenta como energía interna P·V / (γ - 1) y la potencial se mide sobre el
+/// inicio de la rampa. El agua expulsada se anota con lo que lleva al salir (después ya
+/// no la empuja nada). La rotación del cohete y la elasticidad de la botella quedan fuera
+/// y acaban en el descuadre.
+///
+/// El empuje del modelo es ṁ·v + (P - P_atm)·A: el chorro (ṁ·v) sale del aire, pero el
+/// término de presión no se descuenta de ningún sitio, así que crea energía y aparece en
+/// el descuadre. Su trabajo se lleva aparte (`pressure_thrust_work`) para separar ese fallo
+/// conocido del modelo de cualquier otro (`unexplained_error`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EnergyBalance {
+    /// Aire + cohete al lanzar (o al encender la etapa).
+    pub initial: f64,
+    /// Trabajo del término (P - P_atm)·A del empuje: energía que el modelo crea.
+    pub pressure_thrust_work: f64,
+    /// Energía interna del aire que queda.
+    pub gas: f64,
+    /// Trabajo del aire al empujar la atmósfera, P_atm · ΔV.
+    pub atmosphere_work: f64,
+    /// Cinética del cohete con el agua que lleva dentro.
+    pub rocket_kinetic: f64,
+    /// Potencial del cohete sobre el inicio de la rampa.
+    pub rocket_potential: f64,
+    /// Cinética del agua expulsada, respecto al suelo, al salir.
+    pub water_kinetic: f64,
+    /// Potencial del agua expulsada, al salir.
+    pub water_potential: f64,
+    /// Lo que las pérdidas de la tobera quitan al chorro ideal.
+    pub nozzle_loss: f64,
+    /// Trabajo de las fuerzas aerodinámicas, con el signo cambiado.
+    pub drag_loss: f64,
+}
+
+impl EnergyBalance {
+    /// Al lanzar: todo está en el aire y en el movimiento inicial del cohete.
+    pub fn at_launch(gas: f64, rocket_kinetic: f64, rocket_potential: f64) -> Self {
+        Self {
+            initial: gas + rocket_kinetic + rocket_potential,
+            gas,
+            rocket_kinetic,
+            rocket_potential,
+            ..Self::default()
+        }
+    }
+
+    /// Lo que entra en el vuelo [J]: solo la energía inicial.
+    pub fn supplied(&self) -> f64 {
+        self.initial
+    }
+
+    /// Suma de todos los destinos [J].
+    pub fn accounted(&self) -> f64 {
+        self.gas
+            + self.atmosphere_work
+            + self.rocket_kinetic
+            + self.rocket_potential
+            + self.water_kinetic
+            + self.water_potential
+            + self.nozzle_loss
+            + self.drag_loss
+    }
+
+    /// Lo que no cuadra, `accounted - supplied` [J]: > 0 si el modelo crea energía,
+    /// < 0 si la pierde.
+    pub fn error(&self) -> f64 {
+        self.accounted() - self.supplied()
+    }
+
+    /// `error` en fracción de lo aportado.
+    pub fn relative_error(&self) -> f64 {
+        let supplied = self.supplied();
+        if supplied > 0.0 { self.error() / supplied } else { 0.0 }
+    }
+
+    /// Descuadre que no explica el término de presión del empuje, `error -
+    /// pressure_thrust_work` [J]: con paso fino debería ser casi nulo.
+    pub fn unexplained_error(&self) -> f64 {
+        self.error() - self.pressure_thrust_work
+    }
+
+    /// `unexplained_error` en fracción de lo aportado.
+    pub fn relative_unexplained_error(&self) -> f64 {
+        let supplied = self.supplied();
+        if supplied > 0.0 { self.unexplained_error() / supplied } else { 0.0 }
+    }
+
+    /// Cada destino en fracción de lo aportado, para informes.
+    pub fn breakdown(&self) -> [(&'static str, f64); 6] {
+        let supplied = self.supplied();
+        let share = |energy: f64| if supplied > 0.0 { energy / supplied } else { 0.0 };
+        [
+            ("cohete", share(self.rocket_kinetic + self.rocket_potential)),
+            ("agua expulsada", share(self.water_kinetic + self.water_potential)),
+            ("atmósfera", share(self.atmosphere_work)),
+            ("queda en el aire", share(self.gas)),
+            ("tobera", share(self.nozzle_loss)),
+            ("aerodinámica", share(self.drag_loss)),
+        ]
+    }
+}
+
+impl fmt::Display for EnergyBalance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Energía inicial: {:.1} J ->", self.initial)?;
+        for (i, (name, share)) in self.breakdown().into_iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(f, "{} {} {:.1} %", separator, name, share * 100.0)?;
+        }
+        write!(f, "; descuadre {:+.2} %", self.relative_error() * 100.0)?;
+        if self.pressure_thrust_work != 0.0 {
+            write!(
+                f,
+                " ({:+.1} J del término de presión del empuje, {:+.2} % sin explicar)",
+                self.pressure_thrust_work,
+                self.relative_unexplained_error() * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Cantidad de movimiento del vuelo [kg·m/s], acumulada paso a paso como `EnergyBalance`.
+/// El cohete y el agua expulsada (con la velocidad que lleva al salir) solo cambian por
+/// los impulsos de fuerzas externas al sistema: gravedad, aerodinámica y el término de
+/// presión del empuje, que el chorro no compensa.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MomentumBalance {
+    /// Del cohete al lanzar.
+    pub initial: Vector2D,
+    /// Del cohete con el agua que lleva dentro.
+    pub rocket: Vector2D,
+    /// Del agua expulsada, al salir.
+    pub water: Vector2D,
+    /// Impulso del peso del cohete [N·s].
+    pub gravity_impulse: Vector2D,
+    /// Impulso de la fuerza normal [N·s].
+    pub aero_impulse: Vector2D,
+    /// Impulso del término (P - P_atm)·A del empuje [N·s].
+    pub pressure_thrust_impulse: Vector2D,
+}
+
+impl MomentumBalance {
+    /// Al lanzar: solo la del cohete.
+    pub fn at_launch(rocket: Vector2D) -> Self {
+        Self { initial: rocket, rocket, ..Self::default() }
+    }
+
+    /// Inicial más los impulsos recibidos.
+    pub fn supplied(&self) -> Vector2D {
+        self.initial + self.gravity_impulse + self.aero_impulse + self.pressure_thrust_impulse
+    }
+
+    /// Cohete más agua expulsada.
+    pub fn accounted(&self) -> Vector2D {
+        self.rocket + self.water
+    }
+
+    /// Lo que no cuadra, `accounted - supplied`.
+    pub fn error(&self) -> Vector2D {
+        self.accounted() - self.supplied()
+    }
+
+    /// Módulo de `error` en fracción de la cantidad de movimiento del chorro.
+    pub fn relative_error(&self) -> f64 {
+        let scale = self.water.length();
+        if scale > 0.0 { self.error().length() / scale } else { 0.0 }
+    }
+}
+
+impl fmt::Display for MomentumBalance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let v = |p: Vector2D| format!("({:.2}, {:.2})", p.x, p.y);
+        write!(
+            f,
+            "Cantidad de movimiento [kg·m/s]: cohete {}, agua {}; impulsos: gravedad {}, \
+             aerodinámica {}, presión del empuje {}; descuadre {:.2} %",
+            v(self.rocket),
+            v(self.water),
+            v(self.gravity_impulse),
+            v(self.aero_impulse),
+            v(self.pressure_thrust_impulse),
+            self.relative_error() * 100.0
+        )
+    }
+}
+
+/// Energía interna del aire a presión absoluta `pressure` [Pa] en `volume` [m³]:
+/// el trabajo que daría expandiéndose hasta presión nula, P·V / (γ - 1).
+pub fn gas_energy(pressure: f64, volume: f64, adiabatic_index: f64) -> f64 {
+    pressure * volume / (adiabatic_index - 1.0)
+}
+
+/// Una tobera con pérdidas se come parte del chorro y la ideal no; con paso fino, la
+/// cantidad de movimiento cuadra y la energía solo descuadra por el término de presión.
+#[test]
+fn nozzle_losses_and_balance() {
+    use crate::{config::SimulationConfig, physics::nozzle::Nozzle, simulation::Simulation};
+
+    let fly = |nozzle: Nozzle| {
+        let config = SimulationConfig { nozzle, ..SimulationConfig::default() };
+        let mut sim = Simulation::with_config(1e-4, config).unwrap();
+        sim.run(30.0).unwrap();
+        (sim.energy_balance(), sim.momentum_balance())
+    };
+    let (ideal, ideal_momentum) = fly(Nozzle::ideal(0.021));
+    let (lossy, lossy_momentum) = fly(Nozzle::custom(0.021, 0.8));
+    assert_eq!(ideal.nozzle_loss, 0.0);
+    assert!(lossy.nozzle_loss > 0.0);
+    assert_eq!(ideal.initial, lossy.initial);
+    let rocket = |b: EnergyBalance| b.rocket_kinetic + b.rocket_potential;
+    assert!(rocket(lossy) < rocket(ideal));
+    for balance in [ideal, lossy] {
+        assert!(balance.gas < balance.initial && balance.water_kinetic > 0.0);
+        // el término de presión crea energía: el descuadre lo delata y es casi todo suyo
+        assert!(balance.pressure_thrust_work > 0.01 * balance.initial);
+        assert!(balance.relative_error() > 0.01, "{}", balance);
+        assert!(balance.relative_unexplained_error().abs() < 0.01, "{}", balance);
+    }
+    for momentum in [ideal_momentum, lossy_momentum] {
+        assert!(momentum.relative_error() < 0.01, "{}", momentum);
+    }
+}
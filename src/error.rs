@@ -0,0 +1,97 @@
+// error.rs
+use std::fmt;
+
+use crate::safety::SafetyReport;
+
+/// Por qué no se puede construir o seguir una simulación.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimulationError {
+    /// Un parámetro de entrada fuera de rango (`name` como en la configuración).
+    InvalidParameter {
+        /// Parámetro.
+        name: String,
+        /// Valor recibido.
+        value: f64,
+        /// Rango admitido.
+        expected: &'static str,
+    },
+    /// El agua no deja aire en la botella (0 = la principal, 1.. = las del cúmulo).
+    OverfilledBottle {
+        /// Índice de la botella.
+        bottle: usize,
+        /// Volumen del agua [m³].
+        water_volume: f64,
+        /// Volumen de la botella [m³].
+        bottle_volume: f64,
+    },
+    /// El paso de integración no sirve.
+    InvalidStep {
+        /// Paso recibido [s].
+        step: f64,
+        /// Rango admitido.
+        expected: &'static str,
+    },
+    /// Un paso dejó el estado sin sentido físico (no finito, masa negativa, presión bajo
+    /// el vacío, energía desbocada); `iteration` es el número de paso.
+    UnhealthyState {
+        /// Número de paso.
+        iteration: usize,
+        /// Instante del paso [s].
+        time: f64,
+        /// Magnitud enferma.
+        quantity: &'static str,
+        /// Su valor.
+        value: f64,
+        /// Qué le pasa.
+        problem: &'static str,
+    },
+    /// La política de seguridad rechaza el lanzamiento.
+    Unsafe(SafetyReport),
+    /// Un `SimulationState` con otras botellas que la configuración que lo restaura.
+    IncompatibleState,
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidParameter { name, value, expected } => {
+                write!(f, "parámetro `{}` = {} no válido: debe ser {}", name, value, expected)
+            }
+            Self::OverfilledBottle { bottle, water_volume, bottle_volume } => write!(
+                f,
+                "botella {}: {:.3} L de agua no caben en {:.3} L (no queda aire)",
+                bottle,
+                water_volume * 1e3,
+                bottle_volume * 1e3
+            ),
+            Self::InvalidStep { step, expected } => {
+                write!(f, "paso de integración {} s no válido: debe ser {}", step, expected)
+            }
+            Self::UnhealthyState { iteration, time, quantity, value, problem } => write!(
+                f,
+                "paso {} (t = {:.6} s): `{}` = {} {}",
+                iteration, time, quantity, value, problem
+            ),
+            Self::Unsafe(report) => write!(f, "configuración rechazada por seguridad:\n{}", report),
+            Self::IncompatibleState => {
+                f.write_str("las botellas de la configuración no coinciden con las del estado")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+/// `Ok` si `value` es finito y cumple `valid`; si no, `InvalidParameter` con `expected`.
+pub(crate) fn check(
+    name: &str,
+    value: f64,
+    valid: bool,
+    expected: &'static str,
+) -> Result<(), SimulationError> {
+    if value.is_finite() && valid {
+        Ok(())
+    } else {
+        Err(SimulationError::InvalidParameter { name: name.to_string(), value, expected })
+    }
+}
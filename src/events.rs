@@ -0,0 +1,179 @@
+// events.rs
+use std::fmt;
+
+use crate::{energy::EnergyBalance, logging::LogRecord, math_utils::vector_2d::Vector2D};
+
+/// Momentos señalados de un vuelo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlightEventKind {
+    /// Empieza la simulación, en la rampa.
+    Launch,
+    /// Deja la rampa (solo con modelo de actitud).
+    RailExit,
+    /// Se acaba el agua.
+    Burnout,
+    /// Altura máxima.
+    Apogee,
+    /// Vuelve a la altura de la rampa.
+    Landing,
+}
+
+impl FlightEventKind {
+    /// Nombre corto para tablas y exportaciones.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Launch => "launch",
+            Self::RailExit => "rail_exit",
+            Self::Burnout => "burnout",
+            Self::Apogee => "apogee",
+            Self::Landing => "landing",
+        }
+    }
+
+    /// Inversa de `key`.
+    pub fn from_key(key: &str) -> Option<Self> {
+        [Self::Launch, Self::RailExit, Self::Burnout, Self::Apogee, Self::Landing]
+            .into_iter()
+            .find(|kind| kind.key() == key)
+    }
+}
+
+impl fmt::Display for FlightEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Launch => "lanzamiento",
+            Self::RailExit => "salida de rampa",
+            Self::Burnout => "fin de propulsión",
+            Self::Apogee => "apogeo",
+            Self::Landing => "impacto",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Un evento con el estado del cohete en ese instante.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlightEvent {
+    /// Qué ha pasado.
+    pub kind: FlightEventKind,
+    /// Cuándo [s].
+    pub time: f64,
+    /// Dónde [m].
+    pub position: Vector2D,
+    /// Velocidad del cohete [m/s].
+    pub velocity: Vector2D,
+}
+
+/// Eventos reconstruidos a partir de registros ya guardados (p. ej. leídos de un CSV).
+/// Menos finos que los de la simulación: cada uno cae en una muestra, sin interpolar.
+pub fn detect_events(records: &[LogRecord]) -> Vec<FlightEvent> {
+    let event = |kind, r: &LogRecord| FlightEvent {
+        kind,
+        time: r.time,
+        position: r.position,
+        velocity: r.velocity,
+    };
+    let Some(first) = records.first() else {
+        return Vec::new();
+    };
+    let mut events = vec![event(FlightEventKind::Launch, first)];
+    let emptied = |w: &&[LogRecord]| w[0].water_mass > 0.0 && w[1].water_mass <= 0.0;
+    if let Some(pair) = records.windows(2).find(emptied) {
+        events.push(event(FlightEventKind::Burnout, &pair[1]));
+    }
+    let top = records
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, r)| if r.position.y > records[best].position.y { i } else { best });
+    if top > 0 && top + 1 < records.len() {
+        events.push(event(FlightEventKind::Apogee, &records[top]));
+    }
+    let last = &records[records.len() - 1];
+    if top > 0 && last.position.y <= first.position.y {
+        events.push(event(FlightEventKind::Landing, last));
+    }
+    events
+}
+
+/// Máximos de la corrida, llevados paso a paso (no dependen de lo que guarde el logger).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlightPeaks {
+    /// Empuje máximo [N].
+    pub max_thrust: f64,
+    /// Velocidad máxima [m/s].
+    pub max_speed: f64,
+    /// Aceleración máxima [m/s²].
+    pub max_acceleration: f64,
+    /// Presión absoluta máxima [Pa].
+    pub max_pressure: f64,
+}
+
+impl FlightPeaks {
+    pub(crate) fn update(&mut self, thrust: f64, speed: f64, acceleration: f64, pressure: f64) {
+        self.max_thrust = self.max_thrust.max(thrust);
+        self.max_speed = self.max_speed.max(speed);
+        self.max_acceleration = self.max_acceleration.max(acceleration);
+        self.max_pressure = self.max_pressure.max(pressure);
+    }
+}
+
+/// Resumen de un vuelo: cifras clave a partir de eventos y máximos.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlightSummary {
+    /// Altura máxima [m].
+    pub apogee: f64,
+    /// Instante del apogeo [s].
+    pub apogee_time: f64,
+    /// x del impacto (o la actual si no ha caído) [m].
+    pub range: f64,
+    /// Del lanzamiento al impacto (o hasta ahora) [s].
+    pub flight_time: f64,
+    /// Instante del fin de propulsión, si lo hay [s].
+    pub burnout_time: Option<f64>,
+    /// Máximos del vuelo.
+    pub peaks: FlightPeaks,
+    /// Balance de energía.
+    pub energy: EnergyBalance,
+}
+
+impl FlightSummary {
+    /// `current` = posición y tiempo actuales, para vuelos que aún no han acabado.
+    pub fn from_events(
+        events: &[FlightEvent],
+        peaks: FlightPeaks,
+        current: (f64, Vector2D),
+        energy: EnergyBalance,
+    ) -> Self {
+        let find = |kind| events.iter().find(|e| e.kind == kind);
+        let start = find(FlightEventKind::Launch).map_or(0.0, |e| e.time);
+        let (end_time, end_position) = find(FlightEventKind::Landing)
+            .map_or(current, |e| (e.time, e.position));
+        let apogee = find(FlightEventKind::Apogee);
+        Self {
+            apogee: apogee.map_or(end_position.y.max(0.0), |e| e.position.y),
+            apogee_time: apogee.map_or(end_time, |e| e.time),
+            range: end_position.x,
+            flight_time: end_time - start,
+            burnout_time: find(FlightEventKind::Burnout).map(|e| e.time),
+            peaks,
+            energy,
+        }
+    }
+}
+
+impl fmt::Display for FlightSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Apogeo: {:.2} m a los {:.3} s", self.apogee, self.apogee_time)?;
+        writeln!(f, "Alcance: {:.2} m en {:.3} s de vuelo", self.range, self.flight_time)?;
+        match self.burnout_time {
+            Some(t) => writeln!(f, "Fin de propulsión: {:.4} s", t)?,
+            None => writeln!(f, "Fin de propulsión: no llegó a vaciarse")?,
+        }
+        writeln!(
+            f,
+            "Empuje máx.: {:.1} N, velocidad máx.: {:.2} m/s, aceleración máx.: {:.1} m/s²",
+            self.peaks.max_thrust, self.peaks.max_speed, self.peaks.max_acceleration
+        )?;
+        write!(f, "{}", self.energy)
+    }
+}
@@ -0,0 +1,180 @@
+// geodesy.rs
+use std::fmt;
+
+use crate::math_utils::{vector_2d::Vector2D, vector_3d::Vector3D};
+
+// elipsoide WGS-84
+const WGS84_A: f64 = 6_378_137.0; // semieje mayor [m]
+const WGS84_F: f64 = 1.0 / 298.257_223_563; // achatamiento
+const WGS84_E2: f64 = WGS84_F * (2.0 - WGS84_F); // excentricidad²
+
+/// Punto en coordenadas geodésicas WGS-84.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GeodeticPoint {
+    /// Latitud, + al norte [°].
+    pub latitude_deg: f64,
+    /// Longitud, + al este [°].
+    pub longitude_deg: f64,
+    /// Altura sobre el elipsoide [m].
+    pub altitude_m: f64,
+}
+
+impl fmt::Display for GeodeticPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ns = if self.latitude_deg >= 0.0 { 'N' } else { 'S' };
+        let ew = if self.longitude_deg >= 0.0 { 'E' } else { 'W' };
+        write!(
+            f,
+            "{:.6}° {}, {:.6}° {}, {:.1} m",
+            self.latitude_deg.abs(),
+            ns,
+            self.longitude_deg.abs(),
+            ew,
+            self.altitude_m
+        )
+    }
+}
+
+/// Lugar y dirección de lanzamiento. El origen de la simulación (x = y = 0) está
+/// en el sitio; +x apunta al rumbo `azimuth_deg`, +y hacia arriba y +z a la derecha.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LaunchSite {
+    /// Latitud de la rampa, + al norte [°].
+    pub latitude_deg: f64,
+    /// Longitud de la rampa, + al este [°].
+    pub longitude_deg: f64,
+    /// Altura del suelo sobre el elipsoide [m].
+    pub elevation_m: f64,
+    /// Rumbo de +x desde el norte, en sentido horario (90° = este) [°].
+    pub azimuth_deg: f64,
+}
+
+impl LaunchSite {
+    /// Sitio con todos sus campos.
+    pub fn new(latitude_deg: f64, longitude_deg: f64, elevation_m: f64, azimuth_deg: f64) -> Self {
+        Self { latitude_deg, longitude_deg, elevation_m, azimuth_deg }
+    }
+
+    /// La rampa como punto geodésico.
+    pub fn origin(&self) -> GeodeticPoint {
+        GeodeticPoint {
+            latitude_deg: self.latitude_deg,
+            longitude_deg: self.longitude_deg,
+            altitude_m: self.elevation_m,
+        }
+    }
+
+    /// Ejes de la simulación -> plano tangente local ENU (x = este, y = norte, z = arriba).
+    pub fn local_to_enu(&self, local: Vector3D) -> Vector3D {
+        let (sin, cos) = self.azimuth_deg.to_radians().sin_cos();
+        Vector3D::new(
+            local.x * sin + local.z * cos,
+            local.x * cos - local.z * sin,
+            local.y,
+        )
+    }
+
+    /// ENU -> ejes de la simulación.
+    pub fn enu_to_local(&self, enu: Vector3D) -> Vector3D {
+        let (sin, cos) = self.azimuth_deg.to_radians().sin_cos();
+        Vector3D::new(
+            enu.x * sin + enu.y * cos,
+            enu.z,
+            enu.x * cos - enu.y * sin,
+        )
+    }
+
+    /// ENU centrado en el sitio -> latitud/longitud/altura.
+    pub fn enu_to_geodetic(&self, enu: Vector3D) -> GeodeticPoint {
+        let (sin_lat, cos_lat) = self.latitude_deg.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = self.longitude_deg.to_radians().sin_cos();
+        let offset = Vector3D::new(
+            -sin_lon * enu.x - sin_lat * cos_lon * enu.y + cos_lat * cos_lon * enu.z,
+            cos_lon * enu.x - sin_lat * sin_lon * enu.y + cos_lat * sin_lon * enu.z,
+            cos_lat * enu.y + sin_lat * enu.z,
+        );
+        ecef_to_geodetic(geodetic_to_ecef(self.origin()) + offset)
+    }
+
+    /// Latitud/longitud/altura -> ENU centrado en el sitio.
+    pub fn geodetic_to_enu(&self, point: GeodeticPoint) -> Vector3D {
+        let (sin_lat, cos_lat) = self.latitude_deg.to_radians().sin_cos();
+        let (sin_lon, cos_lon) = self.longitude_deg.to_radians().sin_cos();
+        let d = geodetic_to_ecef(point) - geodetic_to_ecef(self.origin());
+        Vector3D::new(
+            -sin_lon * d.x + cos_lon * d.y,
+            -sin_lat * cos_lon * d.x - sin_lat * sin_lon * d.y + cos_lat * d.z,
+            cos_lat * cos_lon * d.x + cos_lat * sin_lon * d.y + sin_lat * d.z,
+        )
+    }
+
+    /// Punto de la simulación 3D en coordenadas geodésicas.
+    pub fn local_to_geodetic(&self, local: Vector3D) -> GeodeticPoint {
+        self.enu_to_geodetic(self.local_to_enu(local))
+    }
+
+    /// Punto de la simulación plana (vuelo en el plano vertical del acimut).
+    pub fn planar_to_geodetic(&self, position: Vector2D) -> GeodeticPoint {
+        self.local_to_geodetic(Vector3D::from_planar(position))
+    }
+}
+
+/// Geodésicas -> ECEF [m].
+fn geodetic_to_ecef(point: GeodeticPoint) -> Vector3D {
+    let (sin_lat, cos_lat) = point.latitude_deg.to_radians().sin_cos();
+    let (sin_lon, cos_lon) = point.longitude_deg.to_radians().sin_cos();
+    let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+    let h = point.altitude_m;
+    Vector3D::new(
+        (n + h) * cos_lat * cos_lon,
+        (n + h) * cos_lat * sin_lon,
+        (n * (1.0 - WGS84_E2) + h) * sin_lat,
+    )
+}
+
+/// ECEF -> geodésicas, iterando la latitud (converge en pocas vueltas cerca del suelo).
+fn ecef_to_geodetic(ecef: Vector3D) -> GeodeticPoint {
+    let p = (ecef.x * ecef.x + ecef.y * ecef.y).sqrt();
+    let longitude = ecef.y.atan2(ecef.x);
+    let mut latitude = ecef.z.atan2(p * (1.0 - WGS84_E2));
+    let mut altitude = 0.0;
+    for _ in 0..10 {
+        let sin_lat = latitude.sin();
+        let n = WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+        altitude = if latitude.cos().abs() > 1e-10 {
+            p / latitude.cos() - n
+        } else {
+            ecef.z.abs() - n * (1.0 - WGS84_E2)
+        };
+        let next = ecef.z.atan2(p * (1.0 - WGS84_E2 * n / (n + altitude)));
+        if (next - latitude).abs() < 1e-14 {
+            latitude = next;
+            break;
+        }
+        latitude = next;
+    }
+    GeodeticPoint {
+        latitude_deg: latitude.to_degrees(),
+        longitude_deg: longitude.to_degrees(),
+        altitude_m: altitude,
+    }
+}
+
+/// Hacia el este, 111 m de alcance a 45° de latitud son ~0.0014° de longitud, y la ida
+/// y vuelta ENU <-> geodésicas no pierde más de un milímetro.
+#[test]
+fn enu_round_trip_and_azimuth() {
+    let site = LaunchSite::new(45.0, -3.0, 650.0, 90.0);
+    let downrange = site.local_to_enu(Vector3D::new(111.0, 10.0, 0.0));
+    assert!((downrange.x - 111.0).abs() < 1e-9 && downrange.y.abs() < 1e-9);
+    let right = site.local_to_enu(Vector3D::new(0.0, 0.0, 5.0));
+    assert!((right.y + 5.0).abs() < 1e-9, "a la derecha mirando al este está el sur");
+
+    let point = site.local_to_geodetic(Vector3D::new(111.0, 10.0, 0.0));
+    assert!((point.latitude_deg - 45.0).abs() < 1e-5);
+    assert!((point.longitude_deg - (-3.0 + 0.00141)).abs() < 2e-5, "{}", point);
+    assert!((point.altitude_m - 660.0).abs() < 0.01);
+
+    let back = site.enu_to_local(site.geodetic_to_enu(point));
+    assert!((back - Vector3D::new(111.0, 10.0, 0.0)).magnitude() < 1e-3);
+}
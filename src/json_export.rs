@@ -0,0 +1,780 @@
+// json_export.rs
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::{
+    cluster::{ClusterBottle, Coupling},
+    config::SimulationConfig,
+    csv_export::{CsvColumn, CsvOptions},
+    energy::{EnergyBalance, MomentumBalance},
+    events::{FlightEvent, FlightSummary},
+    geodesy::{GeodeticPoint, LaunchSite},
+    logging::{LogRecord, Logger, LoggingPolicy},
+    math_utils::vector_2d::Vector2D,
+    observer::Termination,
+    physics::{
+        bottle::{BottleCompliance, BottleSpec},
+        geometry::{FinSet, MassComponent, NoseShape, RocketGeometry},
+        nozzle::{DischargeModel, Nozzle},
+        stability::StabilityModel,
+    },
+    safety::SafetyMode,
+};
+
+/// Valor JSON mínimo, sin crates externos. NaN e infinitos se escriben como `null`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    /// `null`.
+    Null,
+    /// `true` o `false`.
+    Bool(bool),
+    /// Número.
+    Number(f64),
+    /// Cadena.
+    String(String),
+    /// Lista.
+    Array(Vec<Json>),
+    /// Objeto, con los campos en el orden de escritura.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Objeto a partir de pares (clave, valor), en ese orden.
+    pub fn object<K: Into<String>>(fields: impl IntoIterator<Item = (K, Json)>) -> Self {
+        Self::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    /// Lee un documento JSON completo (RFC 8259).
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut parser = Parser { bytes: text.as_bytes(), pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.bytes.len() {
+            return Err(parser.error("sobra texto tras el valor"));
+        }
+        Ok(value)
+    }
+
+    /// Campo de un objeto.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Número; `null` cuenta como NaN (así se escriben los no finitos).
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            Self::Null => Some(f64::NAN),
+            _ => None,
+        }
+    }
+
+    /// Cadena.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Elementos de una lista.
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+// descenso recursivo sobre los bytes; las cadenas se validan como UTF-8 al cortarlas
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, format!("JSON: {} (byte {})", message, self.pos))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.bytes.get(self.pos).is_some_and(|b| b" \t\r\n".contains(b)) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> io::Result<()> {
+        if self.bytes[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("se esperaba `{}`", literal)))
+        }
+    }
+
+    fn value(&mut self) -> io::Result<Json> {
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            Some(b'n') => self.expect("null").map(|_| Json::Null),
+            Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.bytes.get(self.pos) == Some(&b']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.bytes.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(Json::Array(items));
+                        }
+                        _ => return Err(self.error("se esperaba `,` o `]`")),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.bytes.get(self.pos) == Some(&b'}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.bytes.get(self.pos) != Some(&b'"') {
+                        return Err(self.error("se esperaba una clave"));
+                    }
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.bytes.get(self.pos) {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {
+                            self.pos += 1;
+                            return Ok(Json::Object(fields));
+                        }
+                        _ => return Err(self.error("se esperaba `,` o `}`")),
+                    }
+                }
+            }
+            Some(b'-' | b'0'..=b'9') => {
+                let start = self.pos;
+                while self.bytes.get(self.pos).is_some_and(|b| b"+-.eE0123456789".contains(b)) {
+                    self.pos += 1;
+                }
+                // los bytes son ASCII, así que el corte es UTF-8 válido
+                let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or_default();
+                text.parse().map(Json::Number).map_err(|_| self.error("número no válido"))
+            }
+            Some(_) => Err(self.error("valor no válido")),
+            None => Err(self.error("fin inesperado")),
+        }
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        self.pos += 1; // comilla inicial
+        let mut out = String::new();
+        let mut start = self.pos;
+        loop {
+            let Some(&byte) = self.bytes.get(self.pos) else {
+                return Err(self.error("cadena sin cerrar"));
+            };
+            if byte != b'"' && byte != b'\\' {
+                self.pos += 1;
+                continue;
+            }
+            let chunk = std::str::from_utf8(&self.bytes[start..self.pos])
+                .map_err(|_| self.error("UTF-8 no válido"))?;
+            out.push_str(chunk);
+            self.pos += 1;
+            if byte == b'"' {
+                return Ok(out);
+            }
+            let escaped = match self.bytes.get(self.pos) {
+                Some(b'"') => '"',
+                Some(b'\\') => '\\',
+                Some(b'/') => '/',
+                Some(b'b') => '\u{8}',
+                Some(b'f') => '\u{c}',
+                Some(b'n') => '\n',
+                Some(b'r') => '\r',
+                Some(b't') => '\t',
+                Some(b'u') => {
+                    let mut code = self.hex4()?;
+                    // par sustituto (fuera del plano básico)
+                    if (0xD800..0xDC00).contains(&code)
+                        && self.bytes[self.pos + 1..].starts_with(b"\\u")
+                    {
+                        self.pos += 2;
+                        let low = self.hex4()?.wrapping_sub(0xDC00) & 0x3FF;
+                        code = 0x10000 + ((code - 0xD800) << 10) + low;
+                    }
+                    char::from_u32(code).unwrap_or('\u{FFFD}')
+                }
+                _ => return Err(self.error("escape no válido")),
+            };
+            out.push(escaped);
+            self.pos += 1;
+            start = self.pos;
+        }
+    }
+
+    // las cuatro cifras de `\uXXXX`; deja `pos` en la última
+    fn hex4(&mut self) -> io::Result<u32> {
+        let digits =
+            self.bytes.get(self.pos + 1..self.pos + 5).ok_or_else(|| self.error("\\u corto"))?;
+        let code = std::str::from_utf8(digits)
+            .ok()
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.error("\\u no válido"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Vector2D> for Json {
+    fn from(v: Vector2D) -> Self {
+        Self::Array(vec![v.x.into(), v.y.into()])
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Number(n) if n.is_finite() => write!(f, "{}", n),
+            Self::Number(_) => f.write_str("null"),
+            Self::String(s) => write_json_string(f, s),
+            Self::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            Self::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_json_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_json_string(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Parámetros de entrada, tal como se configuraron: todos los campos, con los enums como
+/// objetos etiquetados (`"model"`, `"kind"`) para poder leerlos sin el `Debug` de Rust.
+pub fn config_json(config: &SimulationConfig) -> Json {
+    let cluster = |c: &ClusterBottle| {
+        Json::object([
+            ("bottle", bottle_json(&c.bottle)),
+            ("nozzle", nozzle_json(&c.nozzle)),
+            ("water_mass_kg", c.water_mass.into()),
+        ])
+    };
+    let coupling = match config.coupling {
+        Coupling::Independent => "independent",
+        Coupling::Parallel => "parallel",
+        Coupling::Series => "series",
+    };
+    let safety_mode = match config.safety.mode {
+        SafetyMode::Refuse => "refuse",
+        SafetyMode::Warn => "warn",
+    };
+    Json::object([
+        ("gravity_m_s2", config.gravity.into()),
+        ("water_density_kg_m3", config.water_density.into()),
+        ("water_viscosity_pa_s", config.water_viscosity.into()),
+        ("atmospheric_pressure_pa", config.atmospheric_pressure.into()),
+        ("adiabatic_index", config.adiabatic_index.into()),
+        ("initial_time_s", config.initial_time.into()),
+        ("initial_position_m", config.initial_position.into()),
+        ("initial_velocity_m_s", config.initial_velocity.into()),
+        ("initial_acceleration_m_s2", config.initial_acceleration.into()),
+        ("launching_angle_deg", config.launching_angle_deg.into()),
+        ("initial_water_mass_kg", config.initial_water_mass.into()),
+        ("initial_air_pressure_psi", config.initial_air_pressure_psi.into()),
+        ("launch_temperature_c", config.launch_temperature_c.into()),
+        ("dry_mass_kg", config.dry_mass.into()),
+        ("bottle", bottle_json(&config.bottle)),
+        ("nozzle", nozzle_json(&config.nozzle)),
+        ("cluster", Json::Array(config.cluster.iter().map(cluster).collect())),
+        ("coupling", coupling.into()),
+        ("stability", config.stability.as_ref().map(stability_json).into()),
+        ("geometry", config.geometry.as_ref().map(geometry_json).into()),
+        ("launch_site", config.launch_site.as_ref().map(site_json).into()),
+        (
+            "termination",
+            Json::Array(config.termination.iter().map(termination_json).collect()),
+        ),
+        (
+            "safety",
+            Json::object([
+                ("safety_factor", config.safety.safety_factor.into()),
+                ("mode", safety_mode.into()),
+            ]),
+        ),
+        ("logging", logging_json(&config.logging)),
+    ])
+}
+
+fn bottle_json(bottle: &BottleSpec) -> Json {
+    let compliance = match &bottle.compliance {
+        BottleCompliance::Rigid { volume } => {
+            Json::object([("model", "rigid".into()), ("volume_m3", (*volume).into())])
+        }
+        BottleCompliance::Linear { nominal_volume, expansion_per_pa } => Json::object([
+            ("model", "linear".into()),
+            ("nominal_volume_m3", (*nominal_volume).into()),
+            ("expansion_per_pa", (*expansion_per_pa).into()),
+        ]),
+        BottleCompliance::Table { points } => {
+            let point = |&(pressure, volume): &(f64, f64)| {
+                Json::object([("gauge_pressure_pa", pressure.into()), ("volume_m3", volume.into())])
+            };
+            Json::object([
+                ("model", "table".into()),
+                ("points", Json::Array(points.iter().map(point).collect())),
+            ])
+        }
+    };
+    Json::object([
+        ("name", bottle.name.as_str().into()),
+        ("nominal_volume_m3", bottle.compliance.nominal_volume().into()),
+        ("compliance", compliance),
+        ("rated_pressure_pa", bottle.rated_pressure_pa.into()),
+        ("burst_pressure_pa", bottle.burst_pressure_pa.into()),
+        ("rated_temperature_c", bottle.rated_temperature_c.into()),
+    ])
+}
+
+fn nozzle_json(nozzle: &Nozzle) -> Json {
+    let discharge = match nozzle.discharge {
+        DischargeModel::Ideal => Json::object([("model", "ideal".into())]),
+        DischargeModel::Constant(c_d) => {
+            Json::object([("model", "constant".into()), ("discharge_coefficient", c_d.into())])
+        }
+        DischargeModel::ShortTube { cd_max, length_over_diameter } => Json::object([
+            ("model", "short_tube".into()),
+            ("cd_max", cd_max.into()),
+            ("length_over_diameter", length_over_diameter.into()),
+        ]),
+    };
+    Json::object([
+        ("name", nozzle.name.as_str().into()),
+        ("exit_diameter_m", nozzle.exit_diameter.into()),
+        ("contraction_loss", nozzle.contraction_loss.into()),
+        ("discharge", discharge),
+    ])
+}
+
+fn stability_json(model: &StabilityModel) -> Json {
+    Json::object([
+        ("reference_diameter_m", model.reference_diameter.into()),
+        ("normal_force_slope", model.normal_force_slope.into()),
+        ("center_of_pressure_m", model.center_of_pressure.into()),
+        ("center_of_gravity_m", model.center_of_gravity.into()),
+        ("pitch_inertia_kg_m2", model.pitch_inertia.into()),
+        ("air_density_kg_m3", model.air_density.into()),
+        ("wind_speed_m_s", model.wind_speed.into()),
+        ("launch_rail_length_m", model.launch_rail_length.into()),
+    ])
+}
+
+fn geometry_json(geometry: &RocketGeometry) -> Json {
+    let nose_shape = match geometry.nose_shape {
+        NoseShape::Cone => "cone",
+        NoseShape::Ogive => "ogive",
+        NoseShape::Parabolic => "parabolic",
+    };
+    let fins = |fins: &FinSet| {
+        Json::object([
+            ("count", (fins.count as usize).into()),
+            ("root_chord_m", fins.root_chord.into()),
+            ("tip_chord_m", fins.tip_chord.into()),
+            ("span_m", fins.span.into()),
+            ("sweep_m", fins.sweep.into()),
+            ("position_m", fins.position.into()),
+        ])
+    };
+    let component = |c: &MassComponent| {
+        Json::object([
+            ("name", c.name.as_str().into()),
+            ("mass_kg", c.mass.into()),
+            ("position_m", c.position.into()),
+        ])
+    };
+    Json::object([
+        ("nose_shape", nose_shape.into()),
+        ("nose_length_m", geometry.nose_length.into()),
+        ("body_diameter_m", geometry.body_diameter.into()),
+        ("body_length_m", geometry.body_length.into()),
+        ("fins", geometry.fins.as_ref().map(fins).into()),
+        ("components", Json::Array(geometry.components.iter().map(component).collect())),
+        ("water_tank_end_m", geometry.water_tank_end.into()),
+        // derivados, para leer el informe sin recalcular
+        ("total_length_m", geometry.total_length().into()),
+        ("dry_mass_kg", geometry.dry_mass().into()),
+        ("center_of_pressure_m", geometry.barrowman().cp.into()),
+    ])
+}
+
+fn termination_json(termination: &Termination) -> Json {
+    match *termination {
+        Termination::Landing => Json::object([("kind", "landing".into())]),
+        Termination::Event(event) => {
+            Json::object([("kind", "event".into()), ("event", event.key().into())])
+        }
+        Termination::AltitudeBelow(altitude) => {
+            Json::object([("kind", "altitude_below".into()), ("altitude_m", altitude.into())])
+        }
+        Termination::Time(time) => Json::object([("kind", "time".into()), ("time_s", time.into())]),
+    }
+}
+
+fn logging_json(policy: &LoggingPolicy) -> Json {
+    match *policy {
+        LoggingPolicy::EveryStep => Json::object([("kind", "every_step".into())]),
+        LoggingPolicy::EveryNSteps(steps) => {
+            Json::object([("kind", "every_n_steps".into()), ("steps", steps.into())])
+        }
+        LoggingPolicy::FixedInterval(interval) => {
+            Json::object([("kind", "fixed_interval".into()), ("interval_s", interval.into())])
+        }
+        LoggingPolicy::Adaptive { burn_interval, coast_interval } => Json::object([
+            ("kind", "adaptive".into()),
+            ("burn_interval_s", burn_interval.into()),
+            ("coast_interval_s", coast_interval.into()),
+        ]),
+    }
+}
+
+pub(crate) fn site_json(site: &LaunchSite) -> Json {
+    Json::object([
+        ("latitude_deg", site.latitude_deg.into()),
+        ("longitude_deg", site.longitude_deg.into()),
+        ("elevation_m", site.elevation_m.into()),
+        ("azimuth_deg", site.azimuth_deg.into()),
+    ])
+}
+
+pub(crate) fn geodetic_json(point: &GeodeticPoint) -> Json {
+    Json::object([
+        ("latitude_deg", point.latitude_deg.into()),
+        ("longitude_deg", point.longitude_deg.into()),
+        ("altitude_m", point.altitude_m.into()),
+    ])
+}
+
+pub(crate) fn summary_json(summary: &FlightSummary, landing_point: Option<GeodeticPoint>) -> Json {
+    Json::object([
+        ("apogee_m", summary.apogee.into()),
+        ("apogee_time_s", summary.apogee_time.into()),
+        ("range_m", summary.range.into()),
+        ("flight_time_s", summary.flight_time.into()),
+        ("burnout_time_s", summary.burnout_time.into()),
+        ("max_thrust_n", summary.peaks.max_thrust.into()),
+        ("max_speed_m_s", summary.peaks.max_speed.into()),
+        ("max_acceleration_m_s2", summary.peaks.max_acceleration.into()),
+        ("max_pressure_abs_pa", summary.peaks.max_pressure.into()),
+        ("landing_point", landing_point.as_ref().map(geodetic_json).into()),
+        ("energy", energy_json(&summary.energy)),
+    ])
+}
+
+/// Balance de energía [J], con el descuadre relativo.
+pub(crate) fn energy_json(energy: &EnergyBalance) -> Json {
+    Json::object([
+        ("initial_j", energy.initial.into()),
+        ("pressure_thrust_work_j", energy.pressure_thrust_work.into()),
+        ("gas_j", energy.gas.into()),
+        ("atmosphere_work_j", energy.atmosphere_work.into()),
+        ("rocket_kinetic_j", energy.rocket_kinetic.into()),
+        ("rocket_potential_j", energy.rocket_potential.into()),
+        ("water_kinetic_j", energy.water_kinetic.into()),
+        ("water_potential_j", energy.water_potential.into()),
+        ("nozzle_loss_j", energy.nozzle_loss.into()),
+        ("drag_loss_j", energy.drag_loss.into()),
+        ("relative_error", energy.relative_error().into()),
+        ("relative_unexplained_error", energy.relative_unexplained_error().into()),
+    ])
+}
+
+/// Balance de cantidad de movimiento [kg·m/s], con el descuadre relativo.
+pub(crate) fn momentum_json(momentum: &MomentumBalance) -> Json {
+    Json::object([
+        ("initial_kg_m_s", momentum.initial.into()),
+        ("rocket_kg_m_s", momentum.rocket.into()),
+        ("water_kg_m_s", momentum.water.into()),
+        ("gravity_impulse_n_s", momentum.gravity_impulse.into()),
+        ("aero_impulse_n_s", momentum.aero_impulse.into()),
+        ("pressure_thrust_impulse_n_s", momentum.pressure_thrust_impulse.into()),
+        ("relative_error", momentum.relative_error().into()),
+    ])
+}
+
+pub(crate) fn event_json(event: &FlightEvent) -> Json {
+    Json::object([
+        ("event", event.kind.key().into()),
+        ("time_s", event.time.into()),
+        ("position_m", event.position.into()),
+        ("velocity_m_s", event.velocity.into()),
+    ])
+}
+
+/// Un registro como objeto; las claves son los encabezados del CSV (`time_s`, `x_m`, ...).
+pub fn record_json(record: &LogRecord, columns: &[CsvColumn], site: Option<&LaunchSite>) -> Json {
+    let names = CsvOptions::default();
+    Json::object(columns.iter().map(|&c| (names.header(c), record.csv_value(c, site).into())))
+}
+
+/// Series temporales por columnas: `{"time_s": [...], "x_m": [...], ...}`.
+pub fn time_series_json(
+    records: &[LogRecord],
+    columns: &[CsvColumn],
+    site: Option<&LaunchSite>,
+) -> Json {
+    let names = CsvOptions::default();
+    Json::object(columns.iter().map(|&c| {
+        let values = records.iter().map(|r| r.csv_value(c, site).into()).collect();
+        (names.header(c), Json::Array(values))
+    }))
+}
+
+/// Escribe un objeto JSON por registro y línea (NDJSON) según avanza la simulación.
+/// Vacía el búfer cada `NDJSON_FLUSH_ROWS` líneas o `NDJSON_FLUSH_INTERVAL`, lo que llegue
+/// antes: quien siga el archivo (tail -f) ve líneas enteras con poco retraso sin pagar
+/// una escritura por paso.
+pub struct NdjsonStreamLogger {
+    writer: BufWriter<File>,
+    columns: Vec<CsvColumn>,
+    site: Option<LaunchSite>,
+    pending_rows: usize, // líneas escritas desde el último vaciado
+    last_flush: Instant,
+}
+
+const NDJSON_FLUSH_ROWS: usize = 1000;
+const NDJSON_BUFFER_BYTES: usize = 64 * 1024;
+const NDJSON_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+impl NdjsonStreamLogger {
+    /// Crea (o vacía) el archivo en `path`; `site` da valor a latitud, longitud y altitud.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        columns: Vec<CsvColumn>,
+        site: Option<LaunchSite>,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::with_capacity(NDJSON_BUFFER_BYTES, File::create(path)?),
+            columns,
+            site,
+            pending_rows: 0,
+            last_flush: Instant::now(),
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.pending_rows = 0;
+        self.last_flush = Instant::now();
+        self.writer.flush()
+    }
+}
+
+impl Logger for NdjsonStreamLogger {
+    fn log(&mut self, record: &LogRecord) -> io::Result<()> {
+        let line = format!("{}\n", record_json(record, &self.columns, self.site.as_ref()));
+        // el búfer no debe partir una línea al llenarse
+        if self.writer.buffer().len() + line.len() > self.writer.capacity() {
+            self.flush()?;
+        }
+        self.writer.write_all(line.as_bytes())?;
+        self.pending_rows += 1;
+        if self.pending_rows >= NDJSON_FLUSH_ROWS
+            || self.last_flush.elapsed() >= NDJSON_FLUSH_INTERVAL
+        {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+/// Los números no finitos salen como null y las cadenas se escapan.
+#[test]
+fn json_escapes_and_nulls() {
+    let value = Json::object([
+        ("name", Json::from("botella \"2 L\"\n")),
+        ("values", Json::Array(vec![1.5.into(), f64::NAN.into(), Json::from(None::<f64>)])),
+        ("ok", true.into()),
+    ]);
+    assert_eq!(
+        value.to_string(),
+        r#"{"name":"botella \"2 L\"\n","values":[1.5,null,null],"ok":true}"#
+    );
+}
+
+/// Lo escrito se vuelve a leer igual (los números, bit a bit) y los errores se detectan.
+#[test]
+fn json_parse_round_trip() {
+    let value = Json::object([
+        ("name", Json::from("tobera \"9 mm\"\t¡ñ!")),
+        ("values", Json::Array(vec![0.1.into(), (-2.5e-7).into(), 1e300.into(), 0.0.into()])),
+        ("nested", Json::object([("ok", true.into()), ("none", Json::Null)])),
+        ("empty", Json::Array(Vec::new())),
+    ]);
+    assert_eq!(Json::parse(&value.to_string()).unwrap(), value);
+    let spaced = " { \"a\" : [ 1 , 2 ] , \"b\" : \"\\u00e9\\ud83d\\ude80\" } ";
+    let parsed = Json::parse(spaced).unwrap();
+    assert_eq!(parsed.get("b").and_then(Json::as_str), Some("é🚀"));
+    assert_eq!(parsed.get("a").and_then(Json::as_array).map(<[Json]>::len), Some(2));
+    for bad in ["", "[1,", "{\"a\" 1}", "nul", "[1] 2", "\"sin cerrar"] {
+        assert!(Json::parse(bad).is_err(), "{}", bad);
+    }
+}
+
+/// La configuración sale entera y estructurada: sin cadenas `Debug` y con los modelos
+/// etiquetados.
+#[test]
+fn config_json_is_structured() {
+    use crate::events::FlightEventKind;
+
+    let geometry = RocketGeometry::two_liter_finned();
+    let mut config = SimulationConfig {
+        dry_mass: geometry.dry_mass(),
+        geometry: Some(geometry),
+        termination: vec![Termination::Event(FlightEventKind::Apogee), Termination::Time(5.0)],
+        logging: LoggingPolicy::Adaptive { burn_interval: 0.0, coast_interval: 0.05 },
+        coupling: Coupling::Parallel,
+        ..SimulationConfig::default()
+    };
+    config.nozzle.discharge = DischargeModel::Constant(0.9);
+    config.cluster.push(ClusterBottle {
+        bottle: BottleSpec::pet_1_5l(),
+        nozzle: Nozzle::ideal(0.009),
+        water_mass: 0.3,
+    });
+    let json = Json::parse(&config_json(&config).to_string()).unwrap();
+    let at = |path: &[&str]| {
+        let value = path.iter().try_fold(&json, |value, key| value.get(key));
+        value.unwrap_or_else(|| panic!("{:?}", path))
+    };
+    assert_eq!(at(&["coupling"]).as_str(), Some("parallel"));
+    assert_eq!(at(&["initial_acceleration_m_s2"]).as_array().map(<[Json]>::len), Some(2));
+    assert_eq!(at(&["nozzle", "discharge", "model"]).as_str(), Some("constant"));
+    assert_eq!(at(&["nozzle", "discharge", "discharge_coefficient"]).as_f64(), Some(0.9));
+    assert_eq!(at(&["bottle", "compliance", "model"]).as_str(), Some("rigid"));
+    assert_eq!(at(&["bottle", "rated_temperature_c"]).as_f64(), Some(23.0));
+    assert_eq!(at(&["safety", "mode"]).as_str(), Some("refuse"));
+    assert_eq!(at(&["logging", "coast_interval_s"]).as_f64(), Some(0.05));
+    assert_eq!(at(&["geometry", "fins", "count"]).as_f64(), Some(3.0));
+    assert!(at(&["geometry", "components"]).as_array().is_some_and(|c| !c.is_empty()));
+    let cluster = &at(&["cluster"]).as_array().unwrap()[0];
+    let diameter = cluster.get("nozzle").and_then(|n| n.get("exit_diameter_m"));
+    assert_eq!(diameter.and_then(Json::as_f64), Some(0.009));
+    let compliance = cluster.get("bottle").and_then(|b| b.get("compliance"));
+    assert!(compliance.and_then(|c| c.get("points")).and_then(Json::as_array).is_some());
+    let termination = at(&["termination"]).as_array().unwrap();
+    assert_eq!(termination[0].get("event").and_then(Json::as_str), Some("apogee"));
+    assert_eq!(termination[1].get("time_s").and_then(Json::as_f64), Some(5.0));
+    assert!(!json.to_string().contains("Some("));
+}
+
+/// El NDJSON se vacía por tandas de líneas enteras, y del todo al acabar.
+#[test]
+fn ndjson_stream_flushes_in_batches() {
+    let path = std::env::temp_dir().join(format!("stream_{}.ndjson", std::process::id()));
+    let columns = vec![CsvColumn::Time, CsvColumn::Y];
+    let mut logger = NdjsonStreamLogger::create(&path, columns, None).unwrap();
+    let lines = || std::fs::read_to_string(&path).unwrap().lines().count();
+    let record = |i: usize| LogRecord { time: i as f64, ..LogRecord::default() };
+
+    for i in 0..NDJSON_FLUSH_ROWS {
+        logger.log(&record(i)).unwrap();
+    }
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(written.lines().count(), NDJSON_FLUSH_ROWS);
+    assert!(written.ends_with('\n'));
+    logger.log(&record(NDJSON_FLUSH_ROWS)).unwrap();
+    logger.finish().unwrap();
+    assert_eq!(lines(), NDJSON_FLUSH_ROWS + 1);
+    let _ = std::fs::remove_file(&path);
+}
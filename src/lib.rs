@@ -0,0 +1,88 @@
+//! Simulación de cohetes de agua: propulsión por aire comprimido, vuelo plano (o 6-DOF y
+//! por etapas), eventos del vuelo y exportación de resultados (CSV, JSON, Parquet, KML,
+//! GeoJSON, gráficas SVG/PNG e informe HTML).
+//!
+//! El punto de entrada es [`Simulation`], que se construye a partir de un
+//! [`SimulationConfig`]:
+//!
+//! ```
+//! use physics_calculations::{Simulation, SimulationConfig};
+//!
+//! let mut sim = Simulation::with_config(0.001, SimulationConfig::default())?;
+//! sim.run(30.0)?;
+//! let summary = sim.summary();
+//! assert!(summary.apogee > 0.0 && summary.range > 0.0);
+//! # Ok::<(), physics_calculations::SimulationError>(())
+//! ```
+//!
+//! El estado de la simulación solo se lee mediante métodos (`time()`, `position()`,
+//! `water_mass()`, ...); la configuración y los registros son tipos de datos públicos.
+//! Construir y correr devuelven [`SimulationError`] si la entrada no es válida.
+
+#![warn(missing_docs)]
+// las unidades van entre corchetes (`[m]`, `[kg]`), que rustdoc tomaría por enlaces
+#![allow(rustdoc::broken_intra_doc_links)]
+
+/// Varias botellas en un cohete: depósitos de aire y chorros de cada tobera.
+pub mod cluster;
+/// Tablas por columnas y su escritura en Parquet.
+pub mod columnar;
+/// Parámetros de entrada y su validación.
+pub mod config;
+/// Valores físicos y de la configuración por defecto.
+pub mod constants;
+/// Estudio de convergencia con el paso de integración.
+pub mod convergence;
+/// Exportación y lectura de los logs en CSV.
+pub mod csv_export;
+/// Balances de energía y de cantidad de movimiento.
+pub mod energy;
+/// Errores de construcción y de integración.
+pub mod error;
+/// Eventos, máximos y resumen de un vuelo.
+pub mod events;
+/// Coordenadas geodésicas WGS-84 y lugar de lanzamiento.
+pub mod geodesy;
+/// JSON mínimo y exportación de logs y resúmenes en JSON/NDJSON.
+pub mod json_export;
+/// Registros de la simulación, política de muestreo y loggers.
+pub mod logging;
+/// Trayectoria en KML y GeoJSON.
+pub mod map_export;
+/// Vectores y cuaterniones.
+pub mod math_utils;
+/// Cohetes de varias etapas.
+pub mod multistage;
+/// Observadores de la corrida y condiciones de parada.
+pub mod observer;
+/// Modelos físicos: botella, chorro, geometría, tobera y actitud.
+pub mod physics;
+/// Gráficas SVG/PNG sin dependencias.
+pub mod plotting;
+/// Informe HTML de una o varias corridas.
+pub mod report;
+/// Límites de presión de la botella antes de lanzar.
+pub mod safety;
+/// La simulación plana.
+pub mod simulation;
+/// La simulación en 3D con actitud por cuaternión.
+pub mod six_dof;
+/// Puntos de control para reanudar o ramificar una corrida.
+pub mod state;
+/// Barridos de parámetros.
+pub mod sweep;
+
+pub use config::SimulationConfig;
+pub use convergence::ConvergenceStudy;
+pub use energy::EnergyBalance;
+pub use error::SimulationError;
+pub use events::{FlightEvent, FlightEventKind, FlightSummary};
+pub use logging::LogRecord;
+pub use math_utils::{vector_2d::Vector2D, vector_3d::Vector3D};
+pub use multistage::MultiStageSimulation;
+pub use observer::{Flight, Observer, Termination};
+pub use safety::{SafetyPolicy, SafetyReport};
+pub use simulation::Simulation;
+pub use six_dof::SixDofSimulation;
+pub use state::SimulationState;
+pub use sweep::Sweep;
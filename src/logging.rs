@@ -0,0 +1,403 @@
+// logging.rs
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{
+    csv_export::{CsvColumn, CsvOptions},
+    geodesy::LaunchSite,
+    math_utils::vector_2d::Vector2D,
+    physics::stability::wrap_angle,
+};
+
+/// Cuándo guarda una muestra la simulación. La integración no cambia: solo la salida.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LoggingPolicy {
+    /// Una fila por paso (como siempre).
+    #[default]
+    EveryStep,
+    /// Una fila cada N pasos.
+    EveryNSteps(usize),
+    /// Una fila cada tantos segundos, interpolando entre los pasos que lo rodean.
+    FixedInterval(f64),
+    /// Intervalos distintos con empuje y sin él (0 = cada paso), interpolando como `FixedInterval`.
+    Adaptive {
+        /// Con empuje [s].
+        burn_interval: f64,
+        /// Sin empuje [s].
+        coast_interval: f64,
+    },
+}
+
+/// Lleva la cuenta de la política entre pasos.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LogScheduler {
+    policy: LoggingPolicy,
+    steps_since_log: usize,
+    last_log_time: f64,
+}
+
+impl LogScheduler {
+    pub fn new(policy: LoggingPolicy, start_time: f64) -> Self {
+        Self { policy, steps_since_log: 0, last_log_time: start_time }
+    }
+
+    /// Sigue la cuenta donde la dejó otro (al restaurar una `SimulationState`).
+    pub fn resume(policy: LoggingPolicy, steps_since_log: usize, last_log_time: f64) -> Self {
+        Self { policy, steps_since_log, last_log_time }
+    }
+
+    pub fn steps_since_log(&self) -> usize {
+        self.steps_since_log
+    }
+
+    /// Instante de la última muestra programada [s].
+    pub fn last_log_time(&self) -> f64 {
+        self.last_log_time
+    }
+
+    /// Instantes a registrar dentro del paso (`previous`, `now`], en orden.
+    /// `burning` indica si el cohete todavía tiene empuje.
+    pub fn due(&mut self, previous: f64, now: f64, burning: bool) -> Vec<f64> {
+        let interval = match self.policy {
+            LoggingPolicy::EveryStep => 0.0,
+            LoggingPolicy::EveryNSteps(n) => {
+                self.steps_since_log += 1;
+                if self.steps_since_log < n.max(1) {
+                    return Vec::new();
+                }
+                self.steps_since_log = 0;
+                0.0
+            }
+            LoggingPolicy::FixedInterval(interval) => interval,
+            LoggingPolicy::Adaptive { burn_interval, coast_interval } => {
+                if burning { burn_interval } else { coast_interval }
+            }
+        };
+
+        if interval <= 0.0 {
+            self.last_log_time = now;
+            return vec![now];
+        }
+
+        // margen para no perder una muestra que cae justo en `now` por redondeo
+        let tolerance = 1e-9 * (now - previous).abs();
+        let mut times = Vec::new();
+        let mut next = self.last_log_time + interval;
+        while next <= now + tolerance {
+            times.push(next.min(now).max(previous));
+            self.last_log_time = next;
+            next += interval;
+        }
+        times
+    }
+}
+
+/// Presión y agua de una botella en un instante.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BottleSample {
+    /// Presión absoluta, la de su depósito [Pa].
+    pub pressure_pa: f64,
+    /// Agua que le queda [kg].
+    pub water_mass: f64,
+}
+
+/// Todo lo que se registra en un instante. Una magnitud escalar nueva necesita su campo
+/// aquí, su fila en `FIELDS` (de la que salen la interpolación, el CSV, la lectura y las
+/// columnas disponibles), su `CsvColumn` con su fila en `CsvColumn::spec` y su valor en
+/// `Simulation::log_record`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogRecord {
+    /// Tiempo [s].
+    pub time: f64,
+    /// Posición [m].
+    pub position: Vector2D,
+    /// Velocidad [m/s].
+    pub velocity: Vector2D,
+    /// Aceleración [m/s²].
+    pub acceleration: Vector2D,
+    /// Agua que queda [kg].
+    pub water_mass: f64,
+    /// Masa total [kg].
+    pub total_mass: f64,
+    /// Empuje [N].
+    pub thrust: f64,
+    /// Presión absoluta del depósito más cargado [Pa].
+    pub pressure: f64,
+    /// Cabeceo sobre la horizontal [rad].
+    pub pitch: f64,
+    /// Velocidad de cabeceo [rad/s].
+    pub pitch_rate: f64,
+    /// Ángulo de ataque [rad].
+    pub angle_of_attack: f64,
+    /// Cada botella, en el orden de la configuración.
+    pub bottles: Vec<BottleSample>,
+}
+
+// cómo se interpola una magnitud entre dos pasos
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mix {
+    Linear,
+    Angle,   // por el giro más corto, en ±π
+    Derived, // se recalcula de otras (módulos): ni se interpola ni se lee
+}
+
+// una magnitud escalar de `LogRecord` y su columna
+pub(crate) struct LogField {
+    pub(crate) column: CsvColumn,
+    get: fn(&LogRecord) -> f64,
+    set: fn(&mut LogRecord, f64),
+    mix: Mix,
+    // solo con modelo de actitud; sin él no va al CSV y leerla es opcional
+    pub(crate) attitude: bool,
+}
+
+impl LogField {
+    const fn new(
+        column: CsvColumn,
+        get: fn(&LogRecord) -> f64,
+        set: fn(&mut LogRecord, f64),
+    ) -> Self {
+        Self { column, get, set, mix: Mix::Linear, attitude: false }
+    }
+
+    const fn derived(column: CsvColumn, get: fn(&LogRecord) -> f64) -> Self {
+        Self { column, get, set: |_, _| {}, mix: Mix::Derived, attitude: false }
+    }
+
+    const fn attitude(
+        column: CsvColumn,
+        mix: Mix,
+        get: fn(&LogRecord) -> f64,
+        set: fn(&mut LogRecord, f64),
+    ) -> Self {
+        Self { column, get, set, mix, attitude: true }
+    }
+
+    pub(crate) fn is_derived(&self) -> bool {
+        self.mix == Mix::Derived
+    }
+
+    pub(crate) fn set(&self, record: &mut LogRecord, value: f64) {
+        (self.set)(record, value)
+    }
+}
+
+/// Las magnitudes escalares de `LogRecord`, en el orden de las columnas del CSV. Latitud,
+/// longitud, altitud y botellas se añaden aparte: dependen del lugar y del cúmulo.
+pub(crate) const FIELDS: &[LogField] = &[
+    LogField::new(CsvColumn::Time, |r| r.time, |r, v| r.time = v),
+    LogField::new(CsvColumn::X, |r| r.position.x, |r, v| r.position.x = v),
+    LogField::new(CsvColumn::Y, |r| r.position.y, |r, v| r.position.y = v),
+    LogField::new(CsvColumn::Vx, |r| r.velocity.x, |r, v| r.velocity.x = v),
+    LogField::new(CsvColumn::Vy, |r| r.velocity.y, |r, v| r.velocity.y = v),
+    LogField::derived(CsvColumn::VelocityMagnitude, |r| r.velocity.magnitude()),
+    LogField::new(CsvColumn::Ax, |r| r.acceleration.x, |r, v| r.acceleration.x = v),
+    LogField::new(CsvColumn::Ay, |r| r.acceleration.y, |r, v| r.acceleration.y = v),
+    LogField::derived(CsvColumn::AccelerationMagnitude, |r| r.acceleration.magnitude()),
+    LogField::new(CsvColumn::WaterMass, |r| r.water_mass, |r, v| r.water_mass = v),
+    LogField::new(CsvColumn::TotalMass, |r| r.total_mass, |r, v| r.total_mass = v),
+    LogField::new(CsvColumn::Thrust, |r| r.thrust, |r, v| r.thrust = v),
+    LogField::new(CsvColumn::Pressure, |r| r.pressure, |r, v| r.pressure = v),
+    LogField::attitude(CsvColumn::Pitch, Mix::Angle, |r| r.pitch, |r, v| r.pitch = v),
+    LogField::attitude(
+        CsvColumn::PitchRate,
+        Mix::Linear,
+        |r| r.pitch_rate,
+        |r, v| r.pitch_rate = v,
+    ),
+    LogField::attitude(
+        CsvColumn::AngleOfAttack,
+        Mix::Angle,
+        |r| r.angle_of_attack,
+        |r, v| r.angle_of_attack = v,
+    ),
+];
+
+impl LogRecord {
+    /// Interpolación lineal: 0 = `self`, 1 = `other`. Los ángulos van por el giro más corto
+    /// y quedan en ±π.
+    pub fn lerp(&self, other: &Self, t: f64) -> Self {
+        let mix = |a: f64, b: f64| a + (b - a) * t;
+        let mut out = Self {
+            bottles: self
+                .bottles
+                .iter()
+                .zip(&other.bottles)
+                .map(|(a, b)| BottleSample {
+                    pressure_pa: mix(a.pressure_pa, b.pressure_pa),
+                    water_mass: mix(a.water_mass, b.water_mass),
+                })
+                .collect(),
+            ..Self::default()
+        };
+        for field in FIELDS {
+            let (a, b) = ((field.get)(self), (field.get)(other));
+            match field.mix {
+                Mix::Linear => field.set(&mut out, mix(a, b)),
+                Mix::Angle => field.set(&mut out, wrap_angle(a + wrap_angle(b - a) * t)),
+                Mix::Derived => {}
+            }
+        }
+        out
+    }
+
+    /// Valor SI de una columna del CSV (ángulos en rad, coordenadas en grados).
+    /// Latitud/longitud/altitud necesitan `site`; sin él valen NaN.
+    pub fn csv_value(&self, column: CsvColumn, site: Option<&LaunchSite>) -> f64 {
+        if let Some(field) = FIELDS.iter().find(|f| f.column == column) {
+            return (field.get)(self);
+        }
+        let geodetic = |pick: fn(&crate::geodesy::GeodeticPoint) -> f64| {
+            site.map_or(f64::NAN, |s| pick(&s.planar_to_geodetic(self.position)))
+        };
+        let bottle = |b: usize| self.bottles.get(b.wrapping_sub(1)).copied();
+        match column {
+            CsvColumn::Latitude => geodetic(|p| p.latitude_deg),
+            CsvColumn::Longitude => geodetic(|p| p.longitude_deg),
+            CsvColumn::Altitude => geodetic(|p| p.altitude_m),
+            CsvColumn::BottlePressure(b) => bottle(b).map_or(f64::NAN, |s| s.pressure_pa),
+            CsvColumn::BottleWaterMass(b) => bottle(b).map_or(f64::NAN, |s| s.water_mass),
+            // el resto solo lo da el modelo 6-DOF
+            _ => f64::NAN,
+        }
+    }
+}
+
+/// Destino de los registros de una simulación.
+pub trait Logger {
+    /// Recibe un registro.
+    fn log(&mut self, record: &LogRecord) -> io::Result<()>;
+
+    /// Registros guardados en memoria (vacío si el logger no los guarda).
+    fn records(&self) -> &[LogRecord] {
+        &[]
+    }
+
+    /// Vacía lo pendiente a disco.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Guarda todo en memoria (el comportamiento por defecto).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MemoryLogger {
+    /// Registros recibidos, en orden.
+    pub records: Vec<LogRecord>,
+}
+
+impl Logger for MemoryLogger {
+    fn log(&mut self, record: &LogRecord) -> io::Result<()> {
+        self.records.push(record.clone());
+        Ok(())
+    }
+
+    fn records(&self) -> &[LogRecord] {
+        &self.records
+    }
+}
+
+/// Descarta todo (barridos de parámetros, donde solo interesa el estado final).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NullLogger;
+
+impl Logger for NullLogger {
+    fn log(&mut self, _record: &LogRecord) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Escribe cada registro al CSV según llega, sin guardarlo en memoria.
+pub struct CsvStreamLogger {
+    writer: BufWriter<File>,
+    options: CsvOptions,
+    columns: Vec<CsvColumn>,
+    atmospheric_pressure: f64,
+    site: Option<LaunchSite>,
+}
+
+impl CsvStreamLogger {
+    /// Crea el archivo y escribe el encabezado.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        options: CsvOptions,
+        columns: Vec<CsvColumn>,
+        atmospheric_pressure: f64,
+        site: Option<LaunchSite>,
+    ) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        options.write_header(&mut writer, &columns)?;
+        Ok(Self { writer, options, columns, atmospheric_pressure, site })
+    }
+}
+
+impl Logger for CsvStreamLogger {
+    fn log(&mut self, record: &LogRecord) -> io::Result<()> {
+        let site = self.site.as_ref();
+        self.options.write_row(&mut self.writer, &self.columns, self.atmospheric_pressure, |c| {
+            record.csv_value(c, site)
+        })
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Cada N pasos y a intervalo fijo; el adaptativo cambia de ritmo al acabar el empuje.
+#[test]
+fn scheduler_follows_policy() {
+    let dt = 0.01;
+    let count = |policy: LoggingPolicy, burning_until: f64| {
+        let mut scheduler = LogScheduler::new(policy, 0.0);
+        let mut times = Vec::new();
+        for k in 0..100 {
+            let (previous, now) = (k as f64 * dt, (k + 1) as f64 * dt);
+            times.extend(scheduler.due(previous, now, now <= burning_until));
+        }
+        times
+    };
+
+    assert_eq!(count(LoggingPolicy::EveryStep, 0.0).len(), 100);
+    assert_eq!(count(LoggingPolicy::EveryNSteps(10), 0.0).len(), 10);
+
+    let fixed = count(LoggingPolicy::FixedInterval(0.025), 0.0);
+    assert_eq!(fixed.len(), 40);
+    assert!((fixed[0] - 0.025).abs() < 1e-12 && (fixed[39] - 1.0).abs() < 1e-9);
+
+    let adaptive = LoggingPolicy::Adaptive { burn_interval: 0.0, coast_interval: 0.1 };
+    let times = count(adaptive, 0.2);
+    assert_eq!(times.len(), 20 + 8);
+}
+
+/// El cabeceo se interpola por el giro corto aunque cruce ±π.
+#[test]
+fn lerp_takes_shortest_pitch_turn() {
+    use std::f64::consts::PI;
+
+    let a = LogRecord { pitch: PI - 0.1, ..LogRecord::default() };
+    let b = LogRecord { time: 1.0, pitch: -PI + 0.1, ..LogRecord::default() };
+    let half = a.lerp(&b, 0.5);
+    assert!((half.pitch.abs() - PI).abs() < 1e-12, "{}", half.pitch);
+    let quarter = a.lerp(&b, 0.75);
+    assert!((quarter.pitch - (-PI + 0.05)).abs() < 1e-12, "{}", quarter.pitch);
+    assert!((half.time - 0.5).abs() < 1e-12);
+}
+
+/// Cada fila de `FIELDS` lee y escribe el mismo campo, y su columna no se repite.
+#[test]
+fn log_fields_are_consistent() {
+    let mut record = LogRecord::default();
+    for (i, field) in FIELDS.iter().enumerate().filter(|(_, f)| !f.is_derived()) {
+        field.set(&mut record, i as f64 + 1.0);
+    }
+    for (i, field) in FIELDS.iter().enumerate().filter(|(_, f)| !f.is_derived()) {
+        assert_eq!(record.csv_value(field.column, None), i as f64 + 1.0, "{:?}", field.column);
+    }
+    let speed = record.csv_value(CsvColumn::VelocityMagnitude, None);
+    assert_eq!(speed, record.velocity.magnitude());
+    for (i, field) in FIELDS.iter().enumerate() {
+        assert!(FIELDS[..i].iter().all(|f| f.column != field.column), "{:?}", field.column);
+    }
+}
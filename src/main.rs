@@ -1,19 +1,82 @@
-use crate::simulation::Simulation;
+mod tui;
 
-mod constants;
-mod math_utils;
-mod physics;
-mod simulation;
+use physics_calculations::{
+    constants::MAX_TIME_STEP, plotting::PlotFormat, report, ConvergenceStudy, Simulation,
+    SimulationConfig, Sweep,
+};
 
 fn main() {
     
-    let mut sim = Simulation::new(0.00001);
+    let config = SimulationConfig::default();
 
-    sim.run(30.0);
+    // `cargo run -- --tui`: vista en vivo en la terminal
+    if std::env::args().any(|a| a == "--tui") {
+        if let Err(e) = tui::run(config) {
+            eprintln!("Error en la vista en vivo: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // `cargo run -- --convergence`: la misma corrida con el paso a la mitad cada vez
+    if std::env::args().any(|a| a == "--convergence") {
+        match ConvergenceStudy::run(&config, MAX_TIME_STEP, 6, 1e-3) {
+            Ok(study) => println!("{}", study),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // `cargo run -- --sweep`: barrido de presión inicial, tabla en barrido.parquet
+    if std::env::args().any(|a| a == "--sweep") {
+        let pressures = [40.0, 50.0, 60.0, 70.0, 80.0, 90.0];
+        match Sweep::over_pressure(&config, &pressures, 0.001) {
+            Ok(sweep) => {
+                print!("{}", sweep);
+                sweep.export_parquet("barrido.parquet").expect("Error al exportar el barrido");
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut sim = match Simulation::with_config(0.00001, config) {
+        Ok(sim) => sim,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("{}", sim.safety_report());
+    if let Some(report) = sim.design_report() {
+        println!("{}", report);
+    }
+    if let Some(margin) = sim.static_margin_calibers() {
+        println!("Margen estático: {:.2} calibres", margin);
+    }
+
+    if let Err(e) = sim.run(30.0) {
+        eprintln!("La simulación se detuvo: {}", e);
+    }
+    println!("{}", sim.summary());
+    if let Some(landing) = sim.landing_point() {
+        println!("Punto de impacto: {}", landing);
+    }
 
     // Exportar con coma (,) o punto y coma (;)
     sim.export_logs_to_csv("logs.csv", ',').expect("Error al exportar CSV");
+    sim.export_plots("graficas", PlotFormat::Svg).expect("Error al exportar las gráficas");
+    report::export_html("informe.html", "Informe de vuelo", &[sim.report("Vuelo")])
+        .expect("Error al exportar el informe");
+    if sim.landing_point().is_some() {
+        sim.export_logs_to_kml("trayectoria.kml", &[]).expect("Error al exportar KML");
+        sim.export_logs_to_geojson("trayectoria.geojson", &[]).expect("Error al exportar GeoJSON");
+    }
 
 }
-
-
@@ -0,0 +1,247 @@
+// map_export.rs
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{
+    geodesy::{GeodeticPoint, LaunchSite},
+    json_export::Json,
+    math_utils::vector_3d::Vector3D,
+};
+
+/// Como mucho tantos vértices en la línea; con pasos de 10 µs un vuelo tiene cientos de miles.
+const MAX_TRACK_POINTS: usize = 5000;
+
+/// Vuelo en coordenadas geodésicas, listo para KML/GeoJSON.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeoTrack {
+    /// Nombre del documento.
+    pub name: String,
+    /// Lugar de lanzamiento.
+    pub site: LaunchSite,
+    /// Línea del vuelo, ya aclarada a `MAX_TRACK_POINTS` vértices.
+    pub points: Vec<GeodeticPoint>,
+    /// Punto de lanzamiento.
+    pub launch: GeodeticPoint,
+    /// Punto más alto.
+    pub apogee: GeodeticPoint,
+    /// Punto de impacto.
+    pub landing: GeodeticPoint,
+}
+
+impl GeoTrack {
+    /// `trajectory` en ejes de la simulación (x en el acimut, y arriba, z a la derecha).
+    pub fn from_local(
+        name: &str,
+        site: &LaunchSite,
+        trajectory: &[Vector3D],
+        landing: Vector3D,
+    ) -> Self {
+        let stride = trajectory.len().div_ceil(MAX_TRACK_POINTS).max(1);
+        let mut points: Vec<GeodeticPoint> =
+            trajectory.iter().step_by(stride).map(|&p| site.local_to_geodetic(p)).collect();
+        let landing = site.local_to_geodetic(landing);
+        points.push(landing);
+
+        let start = trajectory.first().copied().unwrap_or(Vector3D::ZERO);
+        let top = trajectory
+            .iter()
+            .copied()
+            .fold(start, |best, p| if p.y > best.y { p } else { best });
+
+        Self {
+            name: name.to_string(),
+            site: *site,
+            points,
+            launch: site.local_to_geodetic(start),
+            apogee: site.local_to_geodetic(top),
+            landing,
+        }
+    }
+
+    /// Documento KML: línea 3D con altitud absoluta y marcas de lanzamiento, apogeo,
+    /// impacto y de cada impacto de `landing_scatter` (p. ej. de un Monte Carlo).
+    pub fn write_kml<W: Write>(
+        &self,
+        w: &mut W,
+        landing_scatter: &[GeodeticPoint],
+    ) -> io::Result<()> {
+        writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(w, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+        writeln!(w, "<Document>")?;
+        writeln!(w, "<name>{}</name>", escape_xml(&self.name))?;
+        writeln!(
+            w,
+            "<description>Origen {} (rumbo {:.1}°)</description>",
+            escape_xml(&self.site.origin().to_string()),
+            self.site.azimuth_deg
+        )?;
+        writeln!(
+            w,
+            r#"<Style id="trayectoria"><LineStyle><color>ff0000ff</color><width>3</width></LineStyle><PolyStyle><color>400000ff</color></PolyStyle></Style>"#
+        )?;
+
+        writeln!(w, "<Placemark>")?;
+        writeln!(w, "<name>Trayectoria</name>")?;
+        writeln!(w, "<styleUrl>#trayectoria</styleUrl>")?;
+        writeln!(w, "<LineString>")?;
+        writeln!(w, "<extrude>1</extrude>")?;
+        writeln!(w, "<altitudeMode>absolute</altitudeMode>")?;
+        writeln!(w, "<coordinates>")?;
+        for p in &self.points {
+            writeln!(w, "{:.8},{:.8},{:.3}", p.longitude_deg, p.latitude_deg, p.altitude_m)?;
+        }
+        writeln!(w, "</coordinates>")?;
+        writeln!(w, "</LineString>")?;
+        writeln!(w, "</Placemark>")?;
+
+        let markers =
+            [("Lanzamiento", self.launch), ("Apogeo", self.apogee), ("Impacto", self.landing)];
+        for (name, p) in markers {
+            write_kml_point(w, name, &p)?;
+        }
+        for (i, p) in landing_scatter.iter().enumerate() {
+            write_kml_point(w, &format!("Impacto {}", i + 1), p)?;
+        }
+
+        writeln!(w, "</Document>")?;
+        writeln!(w, "</kml>")
+    }
+
+    /// FeatureCollection GeoJSON: LineString del vuelo y Points de lanzamiento, apogeo,
+    /// impacto y de cada impacto de `landing_scatter`. Coordenadas [lon, lat, altura].
+    pub fn write_geojson<W: Write>(
+        &self,
+        w: &mut W,
+        landing_scatter: &[GeodeticPoint],
+    ) -> io::Result<()> {
+        let mut features = Vec::new();
+
+        let line = Json::Array(self.points.iter().map(geojson_position).collect());
+        let properties = Json::object([
+            ("name", self.name.as_str().into()),
+            ("kind", "trajectory".into()),
+            ("azimuth_deg", self.site.azimuth_deg.into()),
+        ]);
+        features.push(geojson_feature(properties, "LineString", line));
+
+        let markers = [("launch", self.launch), ("apogee", self.apogee), ("landing", self.landing)];
+        for (kind, p) in markers {
+            let properties = Json::object([("kind", kind.into())]);
+            features.push(geojson_feature(properties, "Point", geojson_position(&p)));
+        }
+        for (i, p) in landing_scatter.iter().enumerate() {
+            let properties =
+                Json::object([("kind", "monte_carlo_landing".into()), ("run", i.into())]);
+            features.push(geojson_feature(properties, "Point", geojson_position(p)));
+        }
+
+        // una Feature por línea, para que el archivo se pueda leer a ojo
+        writeln!(w, r#"{{"type":"FeatureCollection","features":["#)?;
+        for (i, feature) in features.iter().enumerate() {
+            let separator = if i + 1 < features.len() { "," } else { "" };
+            writeln!(w, "{}{}", feature, separator)?;
+        }
+        writeln!(w, "]}}")
+    }
+
+    /// `write_kml` a un archivo.
+    pub fn export_kml<P: AsRef<Path>>(
+        &self,
+        path: P,
+        landing_scatter: &[GeodeticPoint],
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_kml(&mut writer, landing_scatter)?;
+        writer.flush()
+    }
+
+    /// `write_geojson` a un archivo.
+    pub fn export_geojson<P: AsRef<Path>>(
+        &self,
+        path: P,
+        landing_scatter: &[GeodeticPoint],
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_geojson(&mut writer, landing_scatter)?;
+        writer.flush()
+    }
+}
+
+/// Error de exportación cuando la simulación no tiene `launch_site`.
+pub fn missing_launch_site() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "la exportación a mapas necesita un lugar de lanzamiento (SimulationConfig::launch_site)",
+    )
+}
+
+fn write_kml_point<W: Write>(w: &mut W, name: &str, p: &GeodeticPoint) -> io::Result<()> {
+    writeln!(
+        w,
+        "<Placemark><name>{}</name><Point><altitudeMode>absolute</altitudeMode><coordinates>{:.8},{:.8},{:.3}</coordinates></Point></Placemark>",
+        escape_xml(name),
+        p.longitude_deg,
+        p.latitude_deg,
+        p.altitude_m
+    )
+}
+
+// [lon, lat, altura], redondeadas como en el KML (8 decimales de grado, mm de altura)
+fn geojson_position(p: &GeodeticPoint) -> Json {
+    let round = |value: f64, digits: i32| {
+        let scale = 10f64.powi(digits);
+        (value * scale).round() / scale
+    };
+    Json::Array(vec![
+        round(p.longitude_deg, 8).into(),
+        round(p.latitude_deg, 8).into(),
+        round(p.altitude_m, 3).into(),
+    ])
+}
+
+fn geojson_feature(properties: Json, kind: &str, coordinates: Json) -> Json {
+    Json::object([
+        ("type", "Feature".into()),
+        ("properties", properties),
+        ("geometry", Json::object([("type", kind.into()), ("coordinates", coordinates)])),
+    ])
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// La línea acaba en el punto de impacto y los marcadores salen en ambos formatos.
+#[test]
+fn track_exports_line_and_markers() {
+    let site = LaunchSite::new(40.4, -3.7, 650.0, 0.0);
+    let trajectory: Vec<Vector3D> =
+        (0..=20).map(|i| Vector3D::new(i as f64, (i * (20 - i)) as f64 / 10.0, 0.0)).collect();
+    let landing = Vector3D::new(20.0, 0.0, 0.0);
+    let track = GeoTrack::from_local("Vuelo <1>", &site, &trajectory, landing);
+    assert_eq!(track.points.len(), 22);
+    assert!((track.apogee.altitude_m - 660.0).abs() < 0.01);
+    assert!(track.landing.latitude_deg > track.launch.latitude_deg, "rumbo 0° = hacia el norte");
+
+    let scatter = [track.landing, track.launch];
+    let mut kml = Vec::new();
+    track.write_kml(&mut kml, &scatter).unwrap();
+    let kml = String::from_utf8(kml).unwrap();
+    assert!(kml.contains("<name>Vuelo &lt;1&gt;</name>"));
+    assert!(kml.contains("<altitudeMode>absolute</altitudeMode>"));
+    assert_eq!(kml.matches("<Point>").count(), 5);
+
+    let mut json = Vec::new();
+    track.write_geojson(&mut json, &scatter).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains(r#""type":"LineString""#));
+    assert!(json.contains(r#""kind":"apogee""#));
+    assert_eq!(json.matches(r#""kind":"monte_carlo_landing""#).count(), 2);
+    assert!(json.contains("[-3.7,40.4,650]"));
+    let parsed = Json::parse(&json).unwrap();
+    let features = parsed.get("features").and_then(Json::as_array).unwrap();
+    assert_eq!(features.len(), 6);
+    let name = features[0].get("properties").and_then(|p| p.get("name"));
+    assert_eq!(name.and_then(Json::as_str), Some("Vuelo <1>"));
+}
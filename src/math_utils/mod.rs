@@ -1 +1,6 @@
-pub mod vector_2d;
\ No newline at end of file
+/// Attitude quaternions.
+pub mod quaternion;
+/// Vectors in the vertical plane of the planar model.
+pub mod vector_2d;
+/// Vectors of the 6-DOF model and of the geodesy.
+pub mod vector_3d;
\ No newline at end of file
@@ -0,0 +1,106 @@
+// quaternion.rs
+use core::ops::Mul;
+
+use crate::math_utils::vector_3d::Vector3D;
+
+/// Unit quaternion used as an attitude: rotates body-frame vectors into the world frame.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quaternion {
+    /// Scalar part.
+    pub w: f64,
+    /// Vector part, x.
+    pub x: f64,
+    /// Vector part, y.
+    pub y: f64,
+    /// Vector part, z.
+    pub z: f64,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self { Self::IDENTITY }
+}
+
+impl Quaternion {
+    /// No rotation.
+    pub const IDENTITY: Self = Self { w: 1.0, x: 0.0, y: 0.0, z: 0.0 };
+
+    /// Quaternion from its components, as given (not normalized).
+    pub const fn new(w: f64, x: f64, y: f64, z: f64) -> Self { Self { w, x, y, z } }
+
+    /// Rotation of `angle` radians about `axis` (need not be normalized).
+    pub fn from_axis_angle(axis: Vector3D, angle: f64) -> Self {
+        let a = axis.normalized();
+        let (s, c) = (0.5 * angle).sin_cos();
+        Self::new(c, a.x * s, a.y * s, a.z * s)
+    }
+
+    /// Rotation by the rotation vector `v` (axis · angle).
+    pub fn from_rotation_vector(v: Vector3D) -> Self {
+        let angle = v.length();
+        if angle > 0.0 { Self::from_axis_angle(v, angle) } else { Self::IDENTITY }
+    }
+
+    /// Euclidean norm; 1 for a rotation.
+    #[inline]
+    pub fn norm(self) -> f64 {
+        (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+    }
+
+    /// Scaled to unit norm (identity if zero).
+    pub fn normalized(self) -> Self {
+        let n = self.norm();
+        if n > 0.0 { Self::new(self.w / n, self.x / n, self.y / n, self.z / n) } else { Self::IDENTITY }
+    }
+
+    /// Inverse rotation of a unit quaternion.
+    #[inline]
+    pub fn conjugate(self) -> Self { Self::new(self.w, -self.x, -self.y, -self.z) }
+
+    /// Rotates `v` by this quaternion: q · v · q*.
+    pub fn rotate(self, v: Vector3D) -> Vector3D {
+        let u = Vector3D::new(self.x, self.y, self.z);
+        let t = u.cross(v) * 2.0;
+        v + t * self.w + u.cross(t)
+    }
+
+    /// Normalized linear interpolation along the shorter arc: 0 = `self`, 1 = `other`.
+    pub fn nlerp(self, other: Self, t: f64) -> Self {
+        let dot = self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z;
+        let o = if dot < 0.0 { Self::new(-other.w, -other.x, -other.y, -other.z) } else { other };
+        let mix = |a: f64, b: f64| a + (b - a) * t;
+        Self::new(mix(self.w, o.w), mix(self.x, o.x), mix(self.y, o.y), mix(self.z, o.z))
+            .normalized()
+    }
+
+    /// Advances the attitude by a world-frame angular velocity `omega` [rad/s] over `dt`.
+    pub fn integrate(self, omega: Vector3D, dt: f64) -> Self {
+        (Self::from_rotation_vector(omega * dt) * self).normalized()
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Self;
+    #[inline]
+    fn mul(self, r: Self) -> Self {
+        Self::new(
+            self.w * r.w - self.x * r.x - self.y * r.y - self.z * r.z,
+            self.w * r.x + self.x * r.w + self.y * r.z - self.z * r.y,
+            self.w * r.y - self.x * r.z + self.y * r.w + self.z * r.x,
+            self.w * r.z + self.x * r.y - self.y * r.x + self.z * r.w,
+        )
+    }
+}
+
+#[test]
+fn rotation_about_z_matches_planar_angle() {
+    let q = Quaternion::from_axis_angle(Vector3D::UNIT_Z, 30f64.to_radians());
+    let v = q.rotate(Vector3D::UNIT_X);
+    assert!((v.x - 30f64.to_radians().cos()).abs() < 1e-15);
+    assert!((v.y - 30f64.to_radians().sin()).abs() < 1e-15);
+    assert_eq!(v.z, 0.0);
+
+    // integrar 1 rad/s durante 0.5 s equivale a girar 0.5 rad más
+    let q2 = q.integrate(Vector3D::new(0.0, 0.0, 1.0), 0.5);
+    let v2 = q2.rotate(Vector3D::UNIT_X);
+    assert!((v2.y.atan2(v2.x) - (30f64.to_radians() + 0.5)).abs() < 1e-12);
+}
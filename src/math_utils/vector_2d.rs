@@ -1,21 +1,28 @@
 // math.rs
 use core::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Neg};
 
+/// Vector in the vertical plane: x downrange, y up.
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Vector2D {
+    /// Horizontal component.
     pub x: f64,
+    /// Vertical component.
     pub y: f64,
 }
 
 impl Vector2D {
+    /// Zero vector.
     pub const ZERO: Self   = Self { x: 0.0, y: 0.0 };
 
+    /// Vector from its components.
     pub const fn new(x: f64, y: f64) -> Self { Self { x, y } }
 
 
+    /// Same as `length`.
     #[inline]
     pub fn magnitude(self) -> f64 { self.length() }
 
+    /// Euclidean length.
     #[inline]
     pub fn length(self) -> f64 { self.x.hypot(self.y) } // more stable than sqrt(x^2+y^2)
 
@@ -0,0 +1,99 @@
+// vector_3d.rs
+use core::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Neg};
+
+use crate::math_utils::vector_2d::Vector2D;
+
+/// Vector in 3D: x downrange, y up, z to the right.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Vector3D {
+    /// Downrange component.
+    pub x: f64,
+    /// Vertical component.
+    pub y: f64,
+    /// Cross-range component.
+    pub z: f64,
+}
+
+impl Vector3D {
+    /// Zero vector.
+    pub const ZERO: Self   = Self { x: 0.0, y: 0.0, z: 0.0 };
+    /// Unit vector along x.
+    pub const UNIT_X: Self = Self { x: 1.0, y: 0.0, z: 0.0 };
+    /// Unit vector along y.
+    pub const UNIT_Y: Self = Self { x: 0.0, y: 1.0, z: 0.0 };
+    /// Unit vector along z.
+    pub const UNIT_Z: Self = Self { x: 0.0, y: 0.0, z: 1.0 };
+
+    /// Vector from its components.
+    pub const fn new(x: f64, y: f64, z: f64) -> Self { Self { x, y, z } }
+
+    /// Vector in the vertical x–y plane (z = 0).
+    pub const fn from_planar(v: Vector2D) -> Self { Self { x: v.x, y: v.y, z: 0.0 } }
+
+    /// Same as `length`.
+    #[inline]
+    pub fn magnitude(self) -> f64 { self.length() }
+
+    /// Euclidean length.
+    #[inline]
+    pub fn length(self) -> f64 { self.x.hypot(self.y).hypot(self.z) }
+
+    /// Dot product.
+    #[inline]
+    pub fn dot(self, rhs: Self) -> f64 { self.x * rhs.x + self.y * rhs.y + self.z * rhs.z }
+
+    /// Cross product, right-handed.
+    #[inline]
+    pub fn cross(self, rhs: Self) -> Self {
+        Self::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+
+    /// Unit vector in the same direction, or ZERO for a zero vector.
+    #[inline]
+    pub fn normalized(self) -> Self {
+        let len = self.length();
+        if len > 0.0 { self / len } else { Self::ZERO }
+    }
+}
+
+// Operator overloads (ergonomic and fast)
+impl Add for Vector3D {
+    type Output = Self;
+    #[inline] fn add(self, rhs: Self) -> Self { Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z) }
+}
+impl AddAssign for Vector3D {
+    #[inline] fn add_assign(&mut self, rhs: Self) { self.x += rhs.x; self.y += rhs.y; self.z += rhs.z; }
+}
+impl Sub for Vector3D {
+    type Output = Self;
+    #[inline] fn sub(self, rhs: Self) -> Self { Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z) }
+}
+impl SubAssign for Vector3D {
+    #[inline] fn sub_assign(&mut self, rhs: Self) { self.x -= rhs.x; self.y -= rhs.y; self.z -= rhs.z; }
+}
+impl Mul<f64> for Vector3D {
+    type Output = Self;
+    #[inline] fn mul(self, s: f64) -> Self { Self::new(self.x * s, self.y * s, self.z * s) }
+}
+impl MulAssign<f64> for Vector3D {
+    #[inline] fn mul_assign(&mut self, s: f64) { self.x *= s; self.y *= s; self.z *= s; }
+}
+impl Div<f64> for Vector3D {
+    type Output = Self;
+    #[inline] fn div(self, s: f64) -> Self { Self::new(self.x / s, self.y / s, self.z / s) }
+}
+impl DivAssign<f64> for Vector3D {
+    #[inline] fn div_assign(&mut self, s: f64) { self.x /= s; self.y /= s; self.z /= s; }
+}
+impl Neg for Vector3D {
+    type Output = Self;
+    #[inline] fn neg(self) -> Self { Self::new(-self.x, -self.y, -self.z) }
+}
+
+impl From<(f64, f64, f64)> for Vector3D {
+    fn from(t: (f64, f64, f64)) -> Self { Self::new(t.0, t.1, t.2) }
+}
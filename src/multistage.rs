@@ -0,0 +1,463 @@
+// multistage.rs
+use std::io;
+use std::path::Path;
+
+use crate::{
+    cluster::{ClusterBottle, Coupling},
+    config::SimulationConfig,
+    error::{check, SimulationError},
+    math_utils::vector_2d::Vector2D,
+    physics::{bottle::BottleSpec, nozzle::Nozzle, stability::StabilityModel},
+    simulation::{self, Simulation},
+    state::SimulationState,
+};
+
+/// Condición para soltar la etapa activa y encender la siguiente.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StagingTrigger {
+    /// Al agotarse el agua de la etapa.
+    Burnout,
+    /// Tantos segundos después del encendido de la etapa.
+    Timer(f64),
+    /// Cuando la presión manométrica de la etapa cae por debajo de este valor [Pa].
+    PressureBelow(f64),
+}
+
+/// Una etapa: su botella, tobera, carga de agua y masa en seco.
+#[derive(Clone, Debug)]
+pub struct StageConfig {
+    /// Nombre para logs e informes.
+    pub name: String,
+    /// Botella principal de la etapa.
+    pub bottle: BottleSpec,
+    /// Tobera de la botella principal.
+    pub nozzle: Nozzle,
+    /// Carga de agua de la botella principal [kg].
+    pub water_mass: f64,
+    /// Masa en seco de la etapa sola [kg].
+    pub dry_mass: f64,
+    /// Presión manométrica al encender [psi].
+    pub initial_air_pressure_psi: f64,
+    /// Botellas además de la principal.
+    pub cluster: Vec<ClusterBottle>,
+    /// Cómo se conectan las botellas del cúmulo.
+    pub coupling: Coupling,
+    /// Se ignora en la última etapa.
+    pub trigger: StagingTrigger,
+}
+
+impl StageConfig {
+    fn full_mass(&self) -> f64 {
+        self.dry_mass + self.water_mass + self.cluster.iter().map(|c| c.water_mass).sum::<f64>()
+    }
+}
+
+/// Separación de una etapa.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StagingEvent {
+    /// Instante de la separación [s].
+    pub time: f64,
+    /// Índice de la etapa soltada.
+    pub spent_stage: usize,
+    /// Posición al separarse [m].
+    pub position: Vector2D,
+    /// Velocidad al separarse [m/s].
+    pub velocity: Vector2D,
+}
+
+/// Trayectoria de una etapa desde su encendido. Mientras está activa lleva
+/// encima las etapas superiores; tras la separación vuela sola.
+pub struct StageFlight {
+    /// Nombre de la etapa.
+    pub name: String,
+    /// Instante del encendido [s].
+    pub ignition_time: f64,
+    /// Instante de la separación; None para la última etapa o si aún no se ha soltado [s].
+    pub separation_time: Option<f64>,
+    /// Simulación de la etapa, con el mismo reloj que el conjunto.
+    pub sim: Simulation,
+}
+
+/// Pila de etapas. `stages[0]` es el propulsor inferior, que se enciende en el suelo.
+pub struct MultiStageSimulation {
+    step: f64,
+    time: f64,
+    base: SimulationConfig,
+    stages: Vec<StageConfig>,
+    active: usize,
+    flights: Vec<StageFlight>,
+    events: Vec<StagingEvent>,
+}
+
+impl MultiStageSimulation {
+    /// `base` aporta las constantes físicas y las condiciones iniciales;
+    /// sus datos de botella, tobera y masas se sustituyen por los de cada etapa. De
+    /// `geometry` solo se toma el modelo de estabilidad (CP, CG e inercia fijos).
+    /// Cada etapa se valida ya aquí (rangos y `safety`), con la carga útil de las superiores.
+    pub fn new(
+        time_steps: f64,
+        base: SimulationConfig,
+        stages: Vec<StageConfig>,
+    ) -> Result<Self, SimulationError> {
+        if stages.is_empty() {
+            let (name, expected) = ("stages".to_string(), "al menos una etapa");
+            return Err(SimulationError::InvalidParameter { name, value: 0.0, expected });
+        }
+        let mut multi = Self {
+            step: time_steps,
+            time: base.initial_time,
+            base,
+            stages,
+            active: 0,
+            flights: Vec::new(),
+            events: Vec::new(),
+        };
+        let (position, velocity) = (multi.base.initial_position, multi.base.initial_velocity);
+        for index in 1..multi.stages.len() {
+            let config = multi.stage_config(index, position, velocity);
+            config.validate()?;
+            simulation::check_safety(&config)?;
+        }
+        let first = multi.stage_config(0, position, velocity);
+        multi.ignite(0, first)?;
+        Ok(multi)
+    }
+
+    // configuración de la etapa `index` con todas las superiores como carga útil
+    fn stage_config(
+        &self,
+        index: usize,
+        position: Vector2D,
+        velocity: Vector2D,
+    ) -> SimulationConfig {
+        let stage = &self.stages[index];
+        let payload: f64 = self.stages[index + 1..].iter().map(StageConfig::full_mass).sum();
+
+        // las etapas superiores empujan en la dirección de vuelo al separarse
+        let launching_angle_deg = if index == 0 || velocity.magnitude() <= 0.0 {
+            self.base.launching_angle_deg
+        } else {
+            velocity.y.atan2(velocity.x).to_degrees()
+        };
+        // la geometría de `base` lleva su propia masa en seco, que no es la de cada etapa:
+        // solo se usa su modelo de estabilidad, fijo, con el agua de la etapa
+        let stability = match &self.base.geometry {
+            Some(geometry) => Some(geometry.stability_model(
+                stage.water_mass,
+                self.base.water_density,
+                &self.base.stability.clone().unwrap_or_default(),
+            )),
+            None => self.base.stability.clone(),
+        };
+        // y las superiores se encienden en el aire: sin rampa, giran desde el primer paso
+        let stability = match stability {
+            Some(model) if index > 0 => Some(StabilityModel { launch_rail_length: 0.0, ..model }),
+            stability => stability,
+        };
+
+        SimulationConfig {
+            initial_time: self.time,
+            initial_position: position,
+            initial_velocity: velocity,
+            launching_angle_deg,
+            initial_water_mass: stage.water_mass,
+            initial_air_pressure_psi: stage.initial_air_pressure_psi,
+            dry_mass: stage.dry_mass + payload,
+            bottle: stage.bottle.clone(),
+            nozzle: stage.nozzle.clone(),
+            cluster: stage.cluster.clone(),
+            coupling: stage.coupling,
+            stability,
+            geometry: None,
+            ..self.base.clone()
+        }
+    }
+
+    fn ignite(&mut self, index: usize, config: SimulationConfig) -> Result<(), SimulationError> {
+        let sim = Simulation::with_config(self.step, config)?;
+        self.light(index, sim);
+        Ok(())
+    }
+
+    // añade la etapa `index`, ya construida, como la activa
+    fn light(&mut self, index: usize, sim: Simulation) {
+        self.flights.push(StageFlight {
+            name: self.stages[index].name.clone(),
+            ignition_time: self.time,
+            separation_time: None,
+            sim,
+        });
+        self.active = index;
+    }
+
+    fn should_stage(&self) -> bool {
+        if self.active + 1 >= self.stages.len() {
+            return false;
+        }
+        let flight = &self.flights[self.active];
+        let sim = &flight.sim;
+        match self.stages[self.active].trigger {
+            StagingTrigger::Burnout => sim.water_mass() <= 0.0,
+            StagingTrigger::Timer(delay) => self.time - flight.ignition_time >= delay,
+            StagingTrigger::PressureBelow(gauge_pa) => {
+                sim.pressure() - sim.atmospheric_pressure() <= gauge_pa
+            }
+        }
+    }
+
+    // construye la etapa siguiente desde el estado actual de la activa, sin tocar nada
+    fn next_stage(&self) -> Result<Simulation, SimulationError> {
+        let sim = &self.flights[self.active].sim;
+        let next = self.stage_config(self.active + 1, sim.position(), sim.velocity());
+        Simulation::with_config(self.step, next)
+    }
+
+    fn separate(&mut self, upper: Simulation) {
+        let spent = self.active;
+        let (position, velocity) = {
+            let flight = &mut self.flights[spent];
+            flight.separation_time = Some(self.time);
+
+            // la etapa gastada sigue sola, sin la carga útil y abierta: con un disparo por
+            // tiempo o presión le queda agua y aire a presión, pero ya no empuja
+            let sim = &mut flight.sim;
+            sim.set_dry_mass(self.stages[spent].dry_mass);
+            sim.vent();
+            (sim.position(), sim.velocity())
+        };
+
+        self.events.push(StagingEvent { time: self.time, spent_stage: spent, position, velocity });
+        self.light(spent + 1, upper);
+    }
+
+    // =========================
+    // bucle principal
+    // =========================
+    /// Avanza hasta `t_max` o hasta que todas las etapas hayan acabado.
+    pub fn run(&mut self, t_max: f64) -> Result<(), SimulationError> {
+        check("t_max", t_max, true, "finito")?;
+        let mut result = Ok(());
+        while self.time < t_max {
+            result = self.step_once();
+            if result.is_err() || self.flights.iter().all(|f| f.sim.is_finished()) {
+                break;
+            }
+        }
+        // tras un paso enfermo no se registra el estado final
+        if result.is_ok() {
+            for flight in &mut self.flights {
+                flight.sim.flush_logs();
+            }
+        }
+        result
+    }
+
+    /// Avanza un paso todas las etapas que siguen en el aire.
+    /// Falla si alguna etapa queda en un estado enfermo o si la siguiente no puede
+    /// encenderse desde el estado de la separación; entonces el paso no se aplica a
+    /// ninguna etapa y el conjunto sigue como estaba.
+    pub fn step_once(&mut self) -> Result<(), SimulationError> {
+        // primero se integran todas; nada se registra hasta saber que el paso vale
+        let mut pending = Vec::new();
+        for index in 0..self.flights.len() {
+            let sim = &mut self.flights[index].sim;
+            if sim.is_finished() {
+                continue;
+            }
+            let before = sim.snapshot();
+            match sim.advance() {
+                Ok(first_new_event) => pending.push((index, before, first_new_event)),
+                Err(e) => {
+                    sim.rewind(&before);
+                    self.rewind(&pending);
+                    return Err(e);
+                }
+            }
+        }
+        let previous_time = self.time;
+        self.time += self.step;
+
+        let upper = if self.should_stage() {
+            match self.next_stage() {
+                Ok(sim) => Some(sim),
+                Err(e) => {
+                    self.time = previous_time;
+                    self.rewind(&pending);
+                    return Err(e);
+                }
+            }
+        } else {
+            None
+        };
+
+        for (index, _, first_new_event) in pending {
+            self.flights[index].sim.commit_step(first_new_event);
+        }
+        if let Some(upper) = upper {
+            self.separate(upper);
+        }
+        Ok(())
+    }
+
+    // deshace los pasos integrados pero aún no registrados
+    fn rewind(&mut self, pending: &[(usize, SimulationState, usize)]) {
+        for (index, before, _) in pending {
+            self.flights[*index].sim.rewind(before);
+        }
+    }
+
+    /// Tiempo actual [s].
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Índice de la etapa que empuja ahora (0 = la inferior).
+    pub fn active_stage(&self) -> usize {
+        self.active
+    }
+
+    /// Cada etapa encendida, de la inferior a la superior.
+    pub fn flights(&self) -> &[StageFlight] {
+        &self.flights
+    }
+
+    /// Separaciones, en orden.
+    pub fn events(&self) -> &[StagingEvent] {
+        &self.events
+    }
+
+    /// Escribe un CSV por etapa: `<prefijo>_etapa<N>.csv` (N desde 1).
+    pub fn export_stage_logs_to_csv<P: AsRef<Path>>(
+        &self,
+        prefix: P,
+        delimiter: char,
+    ) -> io::Result<()> {
+        let prefix = prefix.as_ref().to_string_lossy().into_owned();
+        for (i, flight) in self.flights.iter().enumerate() {
+            flight
+                .sim
+                .export_logs_to_csv(format!("{}_etapa{}.csv", prefix, i + 1), delimiter)?;
+        }
+        Ok(())
+    }
+}
+
+/// Dos etapas a quemado: la superior llega más alto y el propulsor cae por su cuenta.
+#[test]
+fn two_stage_separates_on_burnout() {
+    let stage = |name: &str, dry_mass: f64| StageConfig {
+        name: name.to_string(),
+        bottle: BottleSpec::pet_1_5l(),
+        nozzle: Nozzle::full_bore(),
+        water_mass: 0.5,
+        dry_mass,
+        initial_air_pressure_psi: 60.0,
+        cluster: Vec::new(),
+        coupling: Coupling::Independent,
+        trigger: StagingTrigger::Burnout,
+    };
+    let base = SimulationConfig { launching_angle_deg: 80.0, ..Default::default() };
+    let stages = vec![stage("propulsor", 0.25), stage("superior", 0.15)];
+    let mut multi = MultiStageSimulation::new(1e-3, base, stages).unwrap();
+    multi.run(60.0).unwrap();
+
+    assert_eq!(multi.events().len(), 1);
+    let [booster, upper] = multi.flights() else { panic!("se esperaban dos etapas") };
+    assert_eq!(booster.separation_time, Some(upper.ignition_time));
+
+    let apogee = |f: &StageFlight| {
+        f.sim.records().iter().map(|r| r.position.y).fold(f64::NEG_INFINITY, f64::max)
+    };
+    assert!(
+        apogee(upper) > apogee(booster),
+        "superior {} vs propulsor {}",
+        apogee(upper),
+        apogee(booster)
+    );
+    assert!(booster.sim.has_landed() && upper.sim.has_landed());
+}
+
+/// Con disparo por tiempo la etapa soltada conserva agua pero no empuja, y la superior
+/// no arranca sobre la rampa.
+#[test]
+fn timer_staging_vents_spent_stage() {
+    let stage = |name: &str, trigger| StageConfig {
+        name: name.to_string(),
+        bottle: BottleSpec::pet_1_5l(),
+        nozzle: Nozzle::full_bore(),
+        water_mass: 0.5,
+        dry_mass: 0.2,
+        initial_air_pressure_psi: 60.0,
+        cluster: Vec::new(),
+        coupling: Coupling::Independent,
+        trigger,
+    };
+    let base = SimulationConfig {
+        launching_angle_deg: 80.0,
+        stability: Some(StabilityModel::default()),
+        ..Default::default()
+    };
+    let stages = vec![
+        stage("propulsor", StagingTrigger::Timer(0.05)),
+        stage("superior", StagingTrigger::Burnout),
+    ];
+    let mut multi = MultiStageSimulation::new(1e-3, base, stages).unwrap();
+    while multi.events().is_empty() {
+        multi.step_once().unwrap();
+    }
+    multi.step_once().unwrap();
+    let [booster, upper] = multi.flights() else { panic!("se esperaban dos etapas") };
+    let spent = &booster.sim;
+    assert!(spent.water_mass() > 0.0);
+    assert_eq!(spent.pressure(), spent.atmospheric_pressure());
+    assert_eq!(spent.thrust(), 0.0);
+    assert!(upper.sim.thrust() > 0.0 && !upper.sim.is_on_launch_rail());
+
+    let water = spent.water_mass();
+    multi.run(60.0).unwrap();
+    assert_eq!(multi.flights()[0].sim.water_mass(), water);
+}
+
+/// Si una etapa enferma en un paso, ninguna avanza: la que ya se había integrado vuelve
+/// atrás y no registra nada.
+#[test]
+fn failed_step_leaves_every_stage_untouched() {
+    let stage = |name: &str, trigger| StageConfig {
+        name: name.to_string(),
+        bottle: BottleSpec::pet_1_5l(),
+        nozzle: Nozzle::full_bore(),
+        water_mass: 0.5,
+        dry_mass: 0.2,
+        initial_air_pressure_psi: 60.0,
+        cluster: Vec::new(),
+        coupling: Coupling::Independent,
+        trigger,
+    };
+    let base = SimulationConfig { launching_angle_deg: 80.0, ..Default::default() };
+    let stages = vec![
+        stage("propulsor", StagingTrigger::Timer(0.05)),
+        stage("superior", StagingTrigger::Burnout),
+    ];
+    let mut multi = MultiStageSimulation::new(1e-3, base, stages).unwrap();
+    while multi.events().is_empty() {
+        multi.step_once().unwrap();
+    }
+    // la superior sigue desde un estado imposible
+    let upper = &multi.flights[1].sim;
+    let mut broken = upper.snapshot();
+    broken.velocity.x = f64::NAN;
+    multi.flights[1].sim = Simulation::restore(upper.config().clone(), &broken).unwrap();
+
+    let booster = multi.flights[0].sim.snapshot();
+    let records = multi.flights[0].sim.records().len();
+    let time = multi.time();
+    assert!(matches!(multi.step_once(), Err(SimulationError::UnhealthyState { .. })));
+    assert_eq!(multi.time(), time);
+    assert_eq!(multi.flights[0].sim.snapshot(), booster);
+    assert_eq!(multi.flights[0].sim.records().len(), records);
+    // (NaN != NaN: se compara el resto)
+    let upper = multi.flights[1].sim.snapshot();
+    assert_eq!(upper.time, broken.time);
+    assert_eq!(upper.position, broken.position);
+    assert_eq!(upper.energy, broken.energy);
+}
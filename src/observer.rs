@@ -0,0 +1,179 @@
+// observer.rs
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{
+    events::{FlightEvent, FlightEventKind},
+    simulation::Simulation,
+    six_dof::{SixDofEvent, SixDofSimulation},
+};
+
+/// Lo que `Termination` y los observadores necesitan de un vuelo, plano o en 3D.
+pub trait Flight {
+    /// Cómo se anotan sus eventos.
+    type Event;
+
+    /// Tiempo actual [s].
+    fn time(&self) -> f64;
+    /// Sobre la vertical del modelo [m].
+    fn altitude(&self) -> f64;
+    /// Si ya se ha registrado un evento de ese tipo.
+    fn has_event(&self, kind: FlightEventKind) -> bool;
+    /// Si ha vuelto al suelo (la regla de `Termination::Landing`).
+    fn has_landed(&self) -> bool;
+}
+
+impl Flight for Simulation {
+    type Event = FlightEvent;
+
+    fn time(&self) -> f64 {
+        Simulation::time(self)
+    }
+
+    fn altitude(&self) -> f64 {
+        self.position().y
+    }
+
+    fn has_event(&self, kind: FlightEventKind) -> bool {
+        self.events().iter().any(|e| e.kind == kind)
+    }
+
+    fn has_landed(&self) -> bool {
+        Simulation::has_landed(self)
+    }
+}
+
+impl Flight for SixDofSimulation {
+    type Event = SixDofEvent;
+
+    fn time(&self) -> f64 {
+        SixDofSimulation::time(self)
+    }
+
+    fn altitude(&self) -> f64 {
+        self.position().y
+    }
+
+    fn has_event(&self, kind: FlightEventKind) -> bool {
+        SixDofSimulation::has_event(self, kind)
+    }
+
+    fn has_landed(&self) -> bool {
+        SixDofSimulation::has_landed(self)
+    }
+}
+
+/// Lógica propia en cada paso (telemetría, paradas a medida, ...) sin tocar `step_once`.
+/// Se registra con `add_observer` (en `Simulation` o en `SixDofSimulation`, que es `S`);
+/// todos los métodos son opcionales.
+pub trait Observer<S: Flight = Simulation> {
+    /// Tras cada paso, con el estado ya actualizado.
+    fn on_step(&mut self, _sim: &S) {}
+
+    /// Cada evento del vuelo en el paso en que se detecta (antes que `on_step`).
+    fn on_event(&mut self, _sim: &S, _event: &S::Event) {}
+
+    /// `true` para que `run` pare tras este paso.
+    fn should_stop(&self, _sim: &S) -> bool {
+        false
+    }
+}
+
+/// Compartido, para poder leer el observador al acabar la corrida.
+impl<S: Flight, T: Observer<S>> Observer<S> for Rc<RefCell<T>> {
+    fn on_step(&mut self, sim: &S) {
+        self.borrow_mut().on_step(sim);
+    }
+
+    fn on_event(&mut self, sim: &S, event: &S::Event) {
+        self.borrow_mut().on_event(sim, event);
+    }
+
+    fn should_stop(&self, sim: &S) -> bool {
+        self.borrow().should_stop(sim)
+    }
+}
+
+/// Cuándo para `Simulation::run`, además de `t_max`. Basta con que se cumpla una.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Termination {
+    /// Baja de y = 0 pasado un instante desde el lanzamiento (la regla de siempre).
+    Landing,
+    /// En cuanto se registra el evento (p. ej. `Apogee` para estudiar solo el ascenso).
+    Event(FlightEventKind),
+    /// Ya pasado el apogeo, por debajo de esa altura [m].
+    AltitudeBelow(f64),
+    /// Pasado ese instante [s].
+    Time(f64),
+}
+
+impl Termination {
+    /// Por defecto se vuela hasta el suelo.
+    pub fn default_set() -> Vec<Self> {
+        vec![Self::Landing]
+    }
+
+    /// Si el vuelo `sim` ya cumple la condición.
+    pub fn is_met<S: Flight>(self, sim: &S) -> bool {
+        match self {
+            Self::Landing => sim.has_landed(),
+            Self::Event(kind) => sim.has_event(kind),
+            Self::AltitudeBelow(altitude) => {
+                sim.has_event(FlightEventKind::Apogee) && sim.altitude() < altitude
+            }
+            Self::Time(time) => sim.time() >= time,
+        }
+    }
+}
+
+/// Los observadores ven cada paso y cada evento, y pueden parar la corrida;
+/// las condiciones de parada de la configuración sustituyen al impacto.
+#[test]
+fn observers_and_termination() {
+    use crate::config::SimulationConfig;
+
+    #[derive(Default)]
+    struct Counter {
+        steps: usize,
+        events: Vec<FlightEventKind>,
+        stop_after: Option<usize>,
+    }
+    impl Observer for Counter {
+        fn on_step(&mut self, _sim: &Simulation) {
+            self.steps += 1;
+        }
+        fn on_event(&mut self, _sim: &Simulation, event: &FlightEvent) {
+            self.events.push(event.kind);
+        }
+        fn should_stop(&self, _sim: &Simulation) -> bool {
+            self.stop_after.is_some_and(|n| self.steps >= n)
+        }
+    }
+
+    let counter = Rc::new(RefCell::new(Counter::default()));
+    let mut sim = Simulation::with_config(0.001, SimulationConfig::default()).unwrap();
+    sim.add_observer(Box::new(counter.clone()));
+    sim.run(30.0).unwrap();
+    let seen = counter.borrow();
+    let expected = [FlightEventKind::Burnout, FlightEventKind::Apogee, FlightEventKind::Landing];
+    assert_eq!(seen.events, expected);
+    assert_eq!(seen.steps, sim.records().len() - 1);
+    drop(seen);
+
+    // parar en el apogeo, por configuración
+    let config = SimulationConfig {
+        termination: vec![Termination::Event(FlightEventKind::Apogee)],
+        ..SimulationConfig::default()
+    };
+    let mut ascent = Simulation::with_config(0.001, config).unwrap();
+    ascent.run(30.0).unwrap();
+    assert!(ascent.velocity().y <= 0.0 && ascent.position().y > 5.0);
+    assert!(!ascent.events().iter().any(|e| e.kind == FlightEventKind::Landing));
+
+    // o desde un observador
+    let stopper = Counter { stop_after: Some(100), ..Counter::default() };
+    let mut short = Simulation::with_config(0.001, SimulationConfig::default()).unwrap();
+    short.add_observer(Box::new(stopper));
+    short.run(30.0).unwrap();
+    assert!((short.time() - 0.1).abs() < 1e-9);
+}
@@ -0,0 +1,167 @@
+// bottle.rs
+
+/// Illustrative swelling curve of a 1.5 L PET bottle: (gauge pressure [Pa], volume [m³]).
+/// Not measured data: a smooth curve of plausible size (about 5 % growth at 7 bar) for
+/// examples and defaults. Use `BottleCompliance::Table` with your own measurements when
+/// the swelling matters.
+pub const PET_1_5L_TABLE: &[(f64, f64)] = &[
+    (0.0, 1.500e-3),
+    (1.0e5, 1.518e-3),
+    (2.0e5, 1.533e-3),
+    (3.0e5, 1.546e-3),
+    (4.0e5, 1.557e-3),
+    (5.0e5, 1.567e-3),
+    (6.0e5, 1.576e-3),
+    (7.0e5, 1.584e-3),
+];
+
+/// Illustrative swelling curve of a 2 L PET bottle: (gauge pressure [Pa], volume [m³]).
+/// Not measured data either; see `PET_1_5L_TABLE`.
+pub const PET_2L_TABLE: &[(f64, f64)] = &[
+    (0.0, 2.000e-3),
+    (1.0e5, 2.026e-3),
+    (2.0e5, 2.048e-3),
+    (3.0e5, 2.066e-3),
+    (4.0e5, 2.082e-3),
+    (5.0e5, 2.096e-3),
+    (6.0e5, 2.109e-3),
+    (7.0e5, 2.120e-3),
+];
+
+/// Internal volume of the bottle as a function of gauge pressure.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BottleCompliance {
+    /// Volume does not change with pressure.
+    Rigid {
+        /// Internal volume [m³].
+        volume: f64,
+    },
+    /// V(p) = V0 · (1 + k · p_gauge), with k in 1/Pa.
+    Linear {
+        /// V0 [m³].
+        nominal_volume: f64,
+        /// k [1/Pa].
+        expansion_per_pa: f64,
+    },
+    /// Piecewise-linear interpolation of (p_gauge [Pa], V [m³]) points, e.g. from a
+    /// pressure test, sorted by pressure. Held constant outside the tabulated range.
+    Table {
+        /// (p_gauge [Pa], V [m³]) pairs.
+        points: Vec<(f64, f64)>,
+    },
+}
+
+impl BottleCompliance {
+    /// 1.5 L PET soda bottle with the illustrative `PET_1_5L_TABLE` curve.
+    pub fn pet_1_5l() -> Self {
+        Self::Table { points: PET_1_5L_TABLE.to_vec() }
+    }
+
+    /// 2 L PET soda bottle with the illustrative `PET_2L_TABLE` curve.
+    pub fn pet_2l() -> Self {
+        Self::Table { points: PET_2L_TABLE.to_vec() }
+    }
+
+    /// Volume at zero gauge pressure [m³].
+    pub fn nominal_volume(&self) -> f64 {
+        self.volume_at(0.0)
+    }
+
+    /// Internal volume [m³] at the given gauge pressure [Pa].
+    /// Negative gauge pressures are treated as zero (the bottle does not shrink).
+    pub fn volume_at(&self, gauge_pressure: f64) -> f64 {
+        let p = gauge_pressure.max(0.0);
+        match self {
+            Self::Rigid { volume } => *volume,
+            Self::Linear { nominal_volume, expansion_per_pa } => {
+                nominal_volume * (1.0 + expansion_per_pa * p)
+            }
+            Self::Table { points } => interpolate(points, p),
+        }
+    }
+}
+
+/// A bottle definition: how it swells and how much it can take.
+/// Pressures are gauge [Pa]; the ratings hold up to `rated_temperature_c`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BottleSpec {
+    /// Name for reports.
+    pub name: String,
+    /// How the volume grows with pressure.
+    pub compliance: BottleCompliance,
+    /// Highest working pressure [Pa].
+    pub rated_pressure_pa: f64,
+    /// Pressure at which it bursts [Pa].
+    pub burst_pressure_pa: f64,
+    /// Temperature up to which the ratings hold [°C].
+    pub rated_temperature_c: f64,
+}
+
+impl BottleSpec {
+    /// PET loses roughly 1 % of its strength per °C above the rating temperature.
+    pub const STRENGTH_LOSS_PER_C: f64 = 0.01;
+
+    /// 1.5 L PET soda bottle with typical ratings (≈ 90 psi working, ≈ 160 psi burst) and
+    /// the illustrative swelling curve; not a measured bottle.
+    pub fn pet_1_5l() -> Self {
+        Self {
+            name: "PET 1.5 L".to_string(),
+            compliance: BottleCompliance::pet_1_5l(),
+            rated_pressure_pa: 6.2e5,
+            burst_pressure_pa: 1.10e6,
+            rated_temperature_c: 23.0,
+        }
+    }
+
+    /// 2 L PET soda bottle with typical ratings (≈ 90 psi working, ≈ 150 psi burst) and
+    /// the illustrative swelling curve; not a measured bottle.
+    pub fn pet_2l() -> Self {
+        Self {
+            name: "PET 2 L".to_string(),
+            compliance: BottleCompliance::pet_2l(),
+            rated_pressure_pa: 6.2e5,
+            burst_pressure_pa: 1.03e6,
+            rated_temperature_c: 23.0,
+        }
+    }
+
+    /// Fraction of the rated strength left at `temperature_c` (1 at or below the rating).
+    pub fn strength_factor(&self, temperature_c: f64) -> f64 {
+        let excess = (temperature_c - self.rated_temperature_c).max(0.0);
+        (1.0 - Self::STRENGTH_LOSS_PER_C * excess).max(0.0)
+    }
+}
+
+fn interpolate(points: &[(f64, f64)], p: f64) -> f64 {
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(f), Some(l)) => (*f, *l),
+        _ => return 0.0,
+    };
+    if p <= first.0 {
+        return first.1;
+    }
+    if p >= last.0 {
+        return last.1;
+    }
+    for w in points.windows(2) {
+        let (p0, v0) = w[0];
+        let (p1, v1) = w[1];
+        if p <= p1 {
+            if p1 <= p0 {
+                return v1;
+            }
+            return v0 + (v1 - v0) * (p - p0) / (p1 - p0);
+        }
+    }
+    last.1
+}
+
+#[test]
+fn table_interpolates_between_points() {
+    let bottle = BottleCompliance::pet_2l();
+    assert_eq!(bottle.nominal_volume(), 2.000e-3);
+    let v = bottle.volume_at(1.5e5);
+    assert!((v - 2.037e-3).abs() < 1e-9, "v = {}", v);
+    // fuera de rango: se mantiene el último punto de la tabla
+    assert_eq!(bottle.volume_at(9.0e5), 2.120e-3);
+}
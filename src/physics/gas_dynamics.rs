@@ -1,9 +1,10 @@
+/// Incompressible water jet driven by the air pressure.
+#[allow(clippy::module_inception)]
 pub mod gas_dynamics {
     // gas_dynamics.rs
 
     /// Ideal (Bernoulli-style) exit velocity for an orifice into atmosphere.
     /// Returns 0 if Δp <= 0 or rho <= 0.
-    #[allow(dead_code)]
     #[inline]
     pub fn exit_velocity(p_air: f64, p_atm: f64, rho: f64) -> f64 {
         let dp = p_air - p_atm;
@@ -38,7 +39,6 @@ pub mod gas_dynamics {
 
     /// Exit velocity with discharge coefficient:
     /// v = C_d * sqrt(2 Δp / ρ). Returns 0 if c_d ≤ 0, Δp ≤ 0, or ρ ≤ 0.
-    #[allow(dead_code)]
     #[inline]
     pub fn exit_velocity_with_cd(c_d: f64, p_air: f64, p_atm: f64, rho: f64) -> f64 {
         let dp = p_air - p_atm;
@@ -48,4 +48,17 @@ pub mod gas_dynamics {
             c_d * (2.0 * dp / rho).sqrt()
         }
     }
+
+    /// Exit velocity through a converging section with loss coefficient `k_loss`
+    /// (referred to the exit velocity head) and discharge coefficient at the exit:
+    /// v = C_d * sqrt(2 Δp / (ρ (1 + K))). Returns 0 if c_d ≤ 0, Δp ≤ 0, ρ ≤ 0 or K < 0.
+    #[inline]
+    pub fn exit_velocity_with_loss(c_d: f64, k_loss: f64, p_air: f64, p_atm: f64, rho: f64) -> f64 {
+        let dp = p_air - p_atm;
+        if c_d <= 0.0 || k_loss < 0.0 || dp <= 0.0 || rho <= 0.0 {
+            0.0
+        } else {
+            c_d * (2.0 * dp / (rho * (1.0 + k_loss))).sqrt()
+        }
+    }
 }
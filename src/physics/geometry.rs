@@ -0,0 +1,287 @@
+// geometry.rs
+use std::fmt;
+
+use crate::physics::stability::StabilityModel;
+
+/// Nose cone profile (Barrowman CP location as a fraction of the nose length).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoseShape {
+    /// Straight cone, CP at 2/3.
+    Cone,
+    /// Tangent ogive, CP at 0.466.
+    Ogive,
+    /// Parabola, CP at 1/2.
+    Parabolic,
+}
+
+impl NoseShape {
+    fn cp_fraction(self) -> f64 {
+        match self {
+            Self::Cone => 2.0 / 3.0,
+            Self::Ogive => 0.466,
+            Self::Parabolic => 0.5,
+        }
+    }
+}
+
+/// Trapezoidal fin set. Lengths in metres; `position` is the root leading edge
+/// measured from the nose tip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinSet {
+    /// Number of fins.
+    pub count: u32,
+    /// Chord at the body.
+    pub root_chord: f64,
+    /// Chord at the tip.
+    pub tip_chord: f64,
+    /// Height of one fin from the body.
+    pub span: f64,
+    /// Axial distance from the root leading edge to the tip leading edge.
+    pub sweep: f64,
+    /// Root leading edge from the nose tip.
+    pub position: f64,
+}
+
+/// A point mass of the dry rocket (nose weight, bottle, fins, payload...).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MassComponent {
+    /// Name for reports.
+    pub name: String,
+    /// [kg].
+    pub mass: f64,
+    /// CG of the component from the nose tip [m].
+    pub position: f64,
+}
+
+/// Outer shape and mass layout of a single-body rocket, nose up, nozzle at the tail.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RocketGeometry {
+    /// Nose cone profile.
+    pub nose_shape: NoseShape,
+    /// [m].
+    pub nose_length: f64,
+    /// [m].
+    pub body_diameter: f64,
+    /// From the nose base to the tail [m].
+    pub body_length: f64,
+    /// None for a finless rocket.
+    pub fins: Option<FinSet>,
+    /// Dry masses; they add up to the dry mass.
+    pub components: Vec<MassComponent>,
+    /// Position of the nozzle end of the water bottle from the nose tip [m].
+    /// Under thrust the water sits against it as a cylinder of `body_diameter`.
+    pub water_tank_end: f64,
+}
+
+/// Barrowman normal-force slope and CP of each part, and the combined result.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Barrowman {
+    /// C_Nα of the nose [1/rad].
+    pub nose_cn_alpha: f64,
+    /// CP of the nose from the nose tip [m].
+    pub nose_cp: f64,
+    /// C_Nα of the fins, with body interference [1/rad].
+    pub fins_cn_alpha: f64,
+    /// CP of the fins from the nose tip [m].
+    pub fins_cp: f64,
+    /// Total C_Nα [1/rad].
+    pub cn_alpha: f64,
+    /// Total CP from the nose tip [m].
+    pub cp: f64,
+}
+
+impl RocketGeometry {
+    /// Finned 2 L bottle rocket with an ogive nose and nose ballast; fins reach past the nozzle.
+    pub fn two_liter_finned() -> Self {
+        Self {
+            nose_shape: NoseShape::Ogive,
+            nose_length: 0.15,
+            body_diameter: 0.105,
+            body_length: 0.45,
+            fins: Some(FinSet {
+                count: 3,
+                root_chord: 0.16,
+                tip_chord: 0.07,
+                span: 0.15,
+                sweep: 0.08,
+                position: 0.50,
+            }),
+            components: vec![
+                MassComponent { name: "cono + lastre".to_string(), mass: 0.35, position: 0.07 },
+                MassComponent { name: "botella".to_string(), mass: 0.15, position: 0.38 },
+                MassComponent { name: "aletas".to_string(), mass: 0.10, position: 0.56 },
+            ],
+            water_tank_end: 0.60,
+        }
+    }
+
+    /// Nose tip to tail [m].
+    pub fn total_length(&self) -> f64 {
+        self.nose_length + self.body_length
+    }
+
+    /// Sum of the components [kg].
+    pub fn dry_mass(&self) -> f64 {
+        self.components.iter().map(|c| c.mass).sum()
+    }
+
+    fn tank_area(&self) -> f64 {
+        std::f64::consts::PI * (self.body_diameter / 2.0).powi(2)
+    }
+
+    // (masa, CG, longitud) de la columna de agua
+    fn water_column(&self, water_mass: f64, water_density: f64) -> (f64, f64, f64) {
+        let height = (water_mass / water_density) / self.tank_area();
+        (water_mass, self.water_tank_end - height / 2.0, height)
+    }
+
+    /// Center of gravity from the nose tip [m] with `water_mass` kg left in the bottle.
+    pub fn center_of_gravity(&self, water_mass: f64, water_density: f64) -> f64 {
+        let (mw, xw, _) = self.water_column(water_mass.max(0.0), water_density);
+        let dry_moment: f64 = self.components.iter().map(|c| c.mass * c.position).sum();
+        let moment = dry_moment + mw * xw;
+        let mass = self.dry_mass() + mw;
+        if mass > 0.0 { moment / mass } else { 0.0 }
+    }
+
+    /// Pitch moment of inertia about the current CG [kg·m²]: components as point
+    /// masses, water as a uniform rod.
+    pub fn pitch_inertia(&self, water_mass: f64, water_density: f64) -> f64 {
+        let cg = self.center_of_gravity(water_mass, water_density);
+        let (mw, xw, h) = self.water_column(water_mass.max(0.0), water_density);
+        let dry: f64 = self.components.iter().map(|c| c.mass * (c.position - cg).powi(2)).sum();
+        dry + mw * (h * h / 12.0 + (xw - cg).powi(2))
+    }
+
+    /// Barrowman center of pressure (subsonic, small angle of attack).
+    pub fn barrowman(&self) -> Barrowman {
+        let nose_cn_alpha = 2.0;
+        let nose_cp = self.nose_shape.cp_fraction() * self.nose_length;
+
+        let (fins_cn_alpha, fins_cp) = match &self.fins {
+            Some(f) if f.count > 0 && f.span > 0.0 => {
+                let d = self.body_diameter;
+                let r = d / 2.0;
+                let (cr, ct, s, xr) = (f.root_chord, f.tip_chord, f.span, f.sweep);
+                let mid_chord = (s * s + (xr + ct / 2.0 - cr / 2.0).powi(2)).sqrt();
+                let interference = 1.0 + r / (s + r);
+                let cn = interference * (4.0 * f.count as f64 * (s / d).powi(2))
+                    / (1.0 + (1.0 + (2.0 * mid_chord / (cr + ct)).powi(2)).sqrt());
+                let xf = f.position
+                    + xr * (cr + 2.0 * ct) / (3.0 * (cr + ct))
+                    + ((cr + ct) - cr * ct / (cr + ct)) / 6.0;
+                (cn, xf)
+            }
+            _ => (0.0, 0.0),
+        };
+
+        let cn_alpha = nose_cn_alpha + fins_cn_alpha;
+        let cp = (nose_cn_alpha * nose_cp + fins_cn_alpha * fins_cp) / cn_alpha;
+        Barrowman { nose_cn_alpha, nose_cp, fins_cn_alpha, fins_cp, cn_alpha, cp }
+    }
+
+    /// Stability model for the given water load; air density, wind and rail come from `base`.
+    pub fn stability_model(
+        &self,
+        water_mass: f64,
+        water_density: f64,
+        base: &StabilityModel,
+    ) -> StabilityModel {
+        let aero = self.barrowman();
+        StabilityModel {
+            reference_diameter: self.body_diameter,
+            normal_force_slope: aero.cn_alpha,
+            center_of_pressure: aero.cp,
+            center_of_gravity: self.center_of_gravity(water_mass, water_density),
+            pitch_inertia: self.pitch_inertia(water_mass, water_density),
+            ..base.clone()
+        }
+    }
+
+    /// Printable CG/CP summary, loaded with `water_mass` and empty.
+    pub fn report(&self, water_mass: f64, water_density: f64) -> DesignReport {
+        DesignReport {
+            geometry: self.clone(),
+            aero: self.barrowman(),
+            water_mass,
+            cg_loaded: self.center_of_gravity(water_mass, water_density),
+            cg_empty: self.center_of_gravity(0.0, water_density),
+        }
+    }
+}
+
+/// CG and CP of a design, as printed before flight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DesignReport {
+    /// The design.
+    pub geometry: RocketGeometry,
+    /// Its CP.
+    pub aero: Barrowman,
+    /// Water load at launch [kg].
+    pub water_mass: f64,
+    /// CG with the water [m].
+    pub cg_loaded: f64,
+    /// CG once empty [m].
+    pub cg_empty: f64,
+}
+
+impl DesignReport {
+    /// Static margin at launch [calibres].
+    pub fn margin_loaded(&self) -> f64 {
+        (self.aero.cp - self.cg_loaded) / self.geometry.body_diameter
+    }
+
+    /// Static margin at burnout [calibres].
+    pub fn margin_empty(&self) -> f64 {
+        (self.aero.cp - self.cg_empty) / self.geometry.body_diameter
+    }
+}
+
+impl fmt::Display for DesignReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let g = &self.geometry;
+        writeln!(f, "Diseño ({:.3} m, Ø {:.1} mm)", g.total_length(), g.body_diameter * 1e3)?;
+        writeln!(f, "  cono {:?} {:.3} m", g.nose_shape, g.nose_length)?;
+        for c in &g.components {
+            writeln!(f, "  {:<16} {:>7.3} kg @ {:.3} m", c.name, c.mass, c.position)?;
+        }
+        writeln!(f, "  masa en seco: {:.3} kg", g.dry_mass())?;
+        writeln!(
+            f,
+            "  CP (Barrowman): {:.3} m  [cono C_Nα {:.2} @ {:.3} m, aletas C_Nα {:.2} @ {:.3} m]",
+            self.aero.cp,
+            self.aero.nose_cn_alpha,
+            self.aero.nose_cp,
+            self.aero.fins_cn_alpha,
+            self.aero.fins_cp
+        )?;
+        writeln!(
+            f,
+            "  CG con {:.3} kg de agua: {:.3} m -> margen {:.2} calibres",
+            self.water_mass,
+            self.cg_loaded,
+            self.margin_loaded()
+        )?;
+        write!(
+            f,
+            "  CG vacío: {:.3} m -> margen {:.2} calibres",
+            self.cg_empty,
+            self.margin_empty()
+        )
+    }
+}
+
+#[test]
+fn barrowman_matches_hand_calculation() {
+    let geometry = RocketGeometry::two_liter_finned();
+    let aero = geometry.barrowman();
+    // 3 aletas, d = 0.105: C_Nα ≈ 11.55 a mano; el CP queda entre el cono y las aletas
+    assert!((aero.fins_cn_alpha - 11.55).abs() < 0.05, "fins C_Nα = {}", aero.fins_cn_alpha);
+    assert!(aero.cp > aero.nose_cp && aero.cp < aero.fins_cp);
+
+    // el agua está abajo: al vaciarse el CG sube hacia el morro y el margen crece
+    let report = geometry.report(1.0, 1000.0);
+    assert!(report.margin_loaded() > 0.0);
+    assert!(report.cg_empty < report.cg_loaded);
+    assert!(report.margin_empty() > report.margin_loaded());
+}
@@ -1 +1,10 @@
+/// Bottle volume under pressure and its ratings.
+pub mod bottle;
+/// Water jet: exit velocity, mass flow and thrust.
 pub mod gas_dynamics;
+/// Rocket shape, mass layout and Barrowman CP.
+pub mod geometry;
+/// Nozzle geometry, losses and discharge coefficient.
+pub mod nozzle;
+/// Pitch-plane aerodynamics and attitude.
+pub mod stability;
\ No newline at end of file
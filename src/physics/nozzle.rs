@@ -0,0 +1,161 @@
+// nozzle.rs
+
+/// How the discharge coefficient depends on the jet Reynolds number.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DischargeModel {
+    /// No correction at the exit lip: C_d = 1 (the contraction loss K still applies).
+    Ideal,
+    /// Same C_d at every Reynolds number, in (0, 1].
+    Constant(f64),
+    /// Short-tube orifice correlation (Lichtarowicz et al.):
+    /// 1/C_d = 1/C_d,max + 20/Re · (1 + 2.25 · L/D).
+    ShortTube {
+        /// C_d at high Reynolds number.
+        cd_max: f64,
+        /// Length of the exit tube over its diameter.
+        length_over_diameter: f64,
+    },
+}
+
+/// Exit geometry of the rocket: area, losses in the converging section
+/// upstream of the exit, and the discharge coefficient at the exit lip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Nozzle {
+    /// Name for reports.
+    pub name: String,
+    /// Exit diameter [m].
+    pub exit_diameter: f64,
+    /// Loss coefficient K of the contraction, referred to the exit velocity head.
+    pub contraction_loss: f64,
+    /// C_d at the exit lip.
+    pub discharge: DischargeModel,
+}
+
+impl Nozzle {
+    /// Inner diameter of a standard PET bottle neck [m].
+    pub const PET_NECK_DIAMETER: f64 = 0.0215;
+
+    /// Fixed diameter and constant C_d, with no contraction loss.
+    pub fn custom(exit_diameter: f64, discharge_coefficient: f64) -> Self {
+        Self {
+            name: "personalizada".to_string(),
+            exit_diameter,
+            contraction_loss: 0.0,
+            discharge: DischargeModel::Constant(discharge_coefficient),
+        }
+    }
+
+    /// Ideal jet through `exit_diameter`: no discharge or contraction losses.
+    pub fn ideal(exit_diameter: f64) -> Self {
+        Self {
+            name: "ideal".to_string(),
+            exit_diameter,
+            contraction_loss: 0.0,
+            discharge: DischargeModel::Ideal,
+        }
+    }
+
+    /// Gardena-style quick connector used as launch tube seal (≈ 9.5 mm bore).
+    pub fn gardena() -> Self {
+        let exit_diameter = 0.0095;
+        Self {
+            name: "Gardena".to_string(),
+            exit_diameter,
+            contraction_loss: sharp_contraction_loss(exit_diameter / Self::PET_NECK_DIAMETER),
+            discharge: DischargeModel::ShortTube { cd_max: 0.82, length_over_diameter: 2.0 },
+        }
+    }
+
+    /// Open bottle neck, no restriction.
+    pub fn full_bore() -> Self {
+        Self {
+            name: "boca completa 21.5 mm".to_string(),
+            exit_diameter: Self::PET_NECK_DIAMETER,
+            contraction_loss: 0.04,
+            discharge: DischargeModel::ShortTube { cd_max: 0.98, length_over_diameter: 0.8 },
+        }
+    }
+
+    /// Thin orifice plate restricting the neck to 9 mm.
+    pub fn restricted_9mm() -> Self {
+        let exit_diameter = 0.009;
+        Self {
+            name: "restringida 9 mm".to_string(),
+            exit_diameter,
+            contraction_loss: sharp_contraction_loss(exit_diameter / Self::PET_NECK_DIAMETER),
+            discharge: DischargeModel::ShortTube { cd_max: 0.61, length_over_diameter: 0.2 },
+        }
+    }
+
+    /// Conical converging nozzle from the bottle neck down to `exit_diameter`.
+    /// `half_angle_deg` is the cone half angle (90° = sharp step).
+    pub fn converging_cone(exit_diameter: f64, half_angle_deg: f64, cd_max: f64) -> Self {
+        let beta = exit_diameter / Self::PET_NECK_DIAMETER;
+        Self {
+            name: format!("cono {:.1} mm / {:.0}°", exit_diameter * 1e3, half_angle_deg),
+            exit_diameter,
+            contraction_loss: cone_contraction_loss(beta, 2.0 * half_angle_deg),
+            discharge: DischargeModel::ShortTube { cd_max, length_over_diameter: 1.0 },
+        }
+    }
+
+    /// Exit area [m²].
+    pub fn area(&self) -> f64 {
+        std::f64::consts::PI * (self.exit_diameter / 2.0).powi(2)
+    }
+
+    /// Re = ρ v D / μ of the jet at the exit.
+    pub fn reynolds(&self, velocity: f64, rho: f64, viscosity: f64) -> f64 {
+        if viscosity <= 0.0 {
+            return f64::INFINITY;
+        }
+        rho * velocity.abs() * self.exit_diameter / viscosity
+    }
+
+    /// Discharge coefficient at the given Reynolds number.
+    pub fn discharge_coefficient(&self, reynolds: f64) -> f64 {
+        match self.discharge {
+            DischargeModel::Ideal => 1.0,
+            DischargeModel::Constant(c_d) => c_d,
+            DischargeModel::ShortTube { cd_max, length_over_diameter } => {
+                if reynolds <= 0.0 || cd_max <= 0.0 {
+                    return 0.0;
+                }
+                1.0 / (1.0 / cd_max + 20.0 / reynolds * (1.0 + 2.25 * length_over_diameter))
+            }
+        }
+    }
+}
+
+/// Sudden contraction: K = 0.5 (1 - β²), β = d_exit / d_inlet.
+fn sharp_contraction_loss(beta: f64) -> f64 {
+    0.5 * (1.0 - beta.powi(2)).max(0.0)
+}
+
+/// Conical contraction (Crane TP-410), θ = included angle in degrees:
+/// K = 0.8 sin(θ/2)(1 - β²) for θ ≤ 45°, 0.5 √sin(θ/2) (1 - β²) above.
+fn cone_contraction_loss(beta: f64, included_angle_deg: f64) -> f64 {
+    let half = (included_angle_deg.clamp(0.0, 180.0) / 2.0).to_radians();
+    let area_term = (1.0 - beta.powi(2)).max(0.0);
+    if included_angle_deg <= 45.0 {
+        0.8 * half.sin() * area_term
+    } else {
+        0.5 * half.sin().sqrt() * area_term
+    }
+}
+
+#[test]
+fn presets_are_ordered_by_losses() {
+    let full = Nozzle::full_bore();
+    let orifice = Nozzle::restricted_9mm();
+    let cone = Nozzle::converging_cone(0.009, 10.0, 0.95);
+    assert!(full.area() > orifice.area());
+    // un cono suave pierde menos que una placa de orificio del mismo diámetro
+    assert!(cone.contraction_loss < orifice.contraction_loss);
+
+    // C_d crece con Re y tiende a C_d,max
+    let low = orifice.discharge_coefficient(1.0e3);
+    let high = orifice.discharge_coefficient(1.0e6);
+    assert!(low < high && high < 0.61 && high > 0.60);
+    assert_eq!(Nozzle::custom(0.021, 0.0).discharge_coefficient(1.0e5), 0.0);
+}
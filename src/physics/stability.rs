@@ -0,0 +1,128 @@
+// stability.rs
+
+/// Pitch-plane aerodynamics of the rocket body: normal force from angle of attack
+/// acting at the center of pressure, plus the aerodynamic damping of pitch rate.
+/// Distances are measured from the nose tip, positive towards the tail.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StabilityModel {
+    /// Body diameter [m].
+    pub reference_diameter: f64,
+    /// C_Nα [1/rad], referred to the body cross-section.
+    pub normal_force_slope: f64,
+    /// From the nose tip [m].
+    pub center_of_pressure: f64,
+    /// From the nose tip [m].
+    pub center_of_gravity: f64,
+    /// About the CG [kg·m²].
+    pub pitch_inertia: f64,
+    /// [kg/m³].
+    pub air_density: f64,
+    /// Horizontal, +x = blowing downrange [m/s].
+    pub wind_speed: f64,
+    /// Pitch is locked until the rocket has traveled this far from the pad [m].
+    pub launch_rail_length: f64,
+}
+
+impl StabilityModel {
+    /// Reference area [m²].
+    pub fn reference_area(&self) -> f64 {
+        std::f64::consts::PI * (self.reference_diameter / 2.0).powi(2)
+    }
+
+    // ½ ρ A C_Nα, common to every aerodynamic term
+    fn aero_factor(&self) -> f64 {
+        0.5 * self.air_density * self.reference_area() * self.normal_force_slope
+    }
+
+    /// (CP − CG) / d. Positive means statically stable; 1–2 calibres is the usual target.
+    pub fn static_margin_calibers(&self) -> f64 {
+        (self.center_of_pressure - self.center_of_gravity) / self.reference_diameter
+    }
+
+    /// Aerodynamic normal force [N] at airspeed `speed` and angle of attack `alpha` [rad].
+    pub fn normal_force(&self, speed: f64, alpha: f64) -> f64 {
+        self.aero_factor() * speed * speed * alpha.sin()
+    }
+
+    /// Pitching moment about the CG [N·m]: restoring term from the normal force at the CP
+    /// and damping term C₂ = ½ ρ V A C_Nα (CP − CG)² opposing the pitch rate.
+    pub fn pitching_moment(&self, speed: f64, alpha: f64, pitch_rate: f64) -> f64 {
+        let arm = self.center_of_pressure - self.center_of_gravity;
+        let restoring = -arm * self.normal_force(speed, alpha);
+        restoring - self.damping_coefficient(speed) * pitch_rate
+    }
+
+    /// Aerodynamic damping C₂ [N·m·s/rad] at airspeed `speed`.
+    pub fn damping_coefficient(&self, speed: f64) -> f64 {
+        let arm = self.center_of_pressure - self.center_of_gravity;
+        self.aero_factor() * speed * arm * arm
+    }
+
+    /// Small-angle natural frequency of the weathercock oscillation [rad/s]
+    /// (0 if the rocket is not statically stable).
+    pub fn natural_frequency(&self, speed: f64) -> f64 {
+        let arm = self.center_of_pressure - self.center_of_gravity;
+        let c1 = self.aero_factor() * speed * speed * arm;
+        if c1 <= 0.0 || self.pitch_inertia <= 0.0 {
+            0.0
+        } else {
+            (c1 / self.pitch_inertia).sqrt()
+        }
+    }
+
+    /// Damping ratio ζ of the weathercock oscillation.
+    pub fn damping_ratio(&self, speed: f64) -> f64 {
+        let omega = self.natural_frequency(speed);
+        if omega <= 0.0 {
+            return 0.0;
+        }
+        self.damping_coefficient(speed) / (2.0 * self.pitch_inertia * omega)
+    }
+}
+
+impl Default for StabilityModel {
+    /// Finned 2 L bottle rocket, about 1.5 calibres stable, on a 1 m rail.
+    fn default() -> Self {
+        Self {
+            reference_diameter: 0.105,
+            normal_force_slope: 9.0,
+            center_of_pressure: 0.52,
+            center_of_gravity: 0.36,
+            pitch_inertia: 0.035,
+            air_density: crate::constants::AIR_DENSITY,
+            wind_speed: 0.0,
+            launch_rail_length: 1.0,
+        }
+    }
+}
+
+/// Wraps an angle to (−π, π].
+pub fn wrap_angle(angle: f64) -> f64 {
+    let two_pi = std::f64::consts::TAU;
+    let a = angle.rem_euclid(two_pi);
+    if a > std::f64::consts::PI { a - two_pi } else { a }
+}
+
+#[test]
+fn stable_rocket_restores_angle_of_attack() {
+    let model = StabilityModel {
+        reference_diameter: 0.1,
+        normal_force_slope: 10.0,
+        center_of_pressure: 0.5,
+        center_of_gravity: 0.35,
+        pitch_inertia: 0.03,
+        air_density: 1.225,
+        wind_speed: 0.0,
+        launch_rail_length: 1.0,
+    };
+    assert!((model.static_margin_calibers() - 1.5).abs() < 1e-12);
+    // morro por encima del viento relativo => momento de picado
+    assert!(model.pitching_moment(20.0, 0.1, 0.0) < 0.0);
+    // el amortiguamiento se opone al giro
+    assert!(model.pitching_moment(20.0, 0.0, 1.0) < 0.0);
+    assert!(model.natural_frequency(20.0) > 0.0);
+
+    let unstable = StabilityModel { center_of_gravity: 0.6, ..model };
+    assert!(unstable.pitching_moment(20.0, 0.1, 0.0) > 0.0);
+    assert_eq!(unstable.natural_frequency(20.0), 0.0);
+}
@@ -0,0 +1,931 @@
+// plotting.rs
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    csv_export,
+    events::{self, FlightEvent, FlightEventKind},
+    logging::LogRecord,
+    map_export::escape_xml,
+};
+
+/// Puntos por serie, más o menos (como `MAX_POINTS_EXTRA` del antiguo script de Python):
+/// al aclarar quedan dos por tramo y los extremos.
+const MAX_PLOT_POINTS: usize = 5000;
+
+/// Ancho por defecto de las gráficas [px].
+pub const DEFAULT_WIDTH: usize = 640;
+/// Alto por defecto de las gráficas [px].
+pub const DEFAULT_HEIGHT: usize = 420;
+
+// márgenes del área de trazado [px]
+const MARGIN_LEFT: f64 = 72.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 36.0;
+const MARGIN_BOTTOM: f64 = 48.0;
+
+// índices en `COLORS`
+const BACKGROUND: usize = 0;
+const INK: usize = 1;
+const GRID: usize = 2;
+const EVENT: usize = 3;
+const FIRST_SERIES: usize = 4;
+
+// fondo, ejes y texto, rejilla, eventos y la paleta de matplotlib para las series
+const COLORS: [(u8, u8, u8); 10] = [
+    (255, 255, 255),
+    (0, 0, 0),
+    (221, 221, 221),
+    (120, 120, 120),
+    (31, 119, 180),
+    (255, 127, 14),
+    (44, 160, 44),
+    (214, 39, 40),
+    (148, 103, 189),
+    (140, 86, 75),
+];
+
+/// Formato de archivo de una gráfica.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlotFormat {
+    /// Vectorial, con el texto tal cual.
+    Svg,
+    /// PNG con paleta; el texto sale en mayúsculas y sin tildes (fuente de mapa de bits 5×7).
+    Png,
+}
+
+impl PlotFormat {
+    /// Extensión del archivo, sin punto.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// Una curva; los puntos no finitos la cortan.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    /// Nombre en la leyenda.
+    pub label: String,
+    /// Puntos (x, y), en orden.
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Un evento en la gráfica: línea vertical en `x` o, si hay `y`, un punto.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    /// Texto junto a la marca.
+    pub label: String,
+    /// Abscisa.
+    pub x: f64,
+    /// Ordenada del punto; None = línea vertical.
+    pub y: Option<f64>,
+}
+
+/// Gráfica de líneas con ejes, rejilla, leyenda y marcas de eventos.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Chart {
+    /// Título, arriba.
+    pub title: String,
+    /// Rótulo del eje x.
+    pub x_label: String,
+    /// Rótulo del eje y.
+    pub y_label: String,
+    /// Curvas, en el orden de la paleta.
+    pub series: Vec<Series>,
+    /// Eventos marcados.
+    pub markers: Vec<Marker>,
+    /// Misma escala en ambos ejes (para la trayectoria).
+    pub equal_aspect: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+// primitivas en píxeles, comunes a SVG y PNG
+#[derive(Clone, Debug, PartialEq)]
+enum Shape {
+    Rect { min: (f64, f64), max: (f64, f64), fill: usize },
+    Line { from: (f64, f64), to: (f64, f64), color: usize, dashed: bool },
+    Polyline { points: Vec<(f64, f64)>, color: usize },
+    Dot { center: (f64, f64), color: usize },
+    Text { at: (f64, f64), text: String, size: f64, anchor: Anchor, vertical: bool },
+}
+
+// rango de un eje y su rejilla
+struct Axis {
+    min: f64,
+    max: f64,
+    step: f64,
+}
+
+impl Axis {
+    fn fit(mut min: f64, mut max: f64) -> Self {
+        if !(min.is_finite() && max.is_finite()) {
+            (min, max) = (0.0, 1.0);
+        }
+        if max - min < 1e-12 * max.abs().max(1.0) {
+            let pad = 0.5 * min.abs().max(1.0);
+            (min, max) = (min - pad, max + pad);
+        }
+        let step = nice_step((max - min) / 5.0);
+        Self { min: (min / step).floor() * step, max: (max / step).ceil() * step, step }
+    }
+
+    // amplía el rango hasta `span` alrededor del centro
+    fn widen(&mut self, span: f64) {
+        let center = 0.5 * (self.min + self.max);
+        self.min = center - 0.5 * span;
+        self.max = center + 0.5 * span;
+        self.step = nice_step(span / 5.0);
+    }
+
+    fn ticks(&self) -> Vec<f64> {
+        let first = (self.min / self.step - 1e-9).ceil() as i64;
+        let last = (self.max / self.step + 1e-9).floor() as i64;
+        (first..=last).map(|k| k as f64 * self.step).collect()
+    }
+
+    fn label(&self, value: f64) -> String {
+        let decimals = (-self.step.log10().floor()).max(0.0) as usize;
+        let text = format!("{:.*}", decimals, value);
+        // evita "-0"
+        if text.trim_start_matches('-').chars().all(|c| c == '0' || c == '.') {
+            text.trim_start_matches('-').to_string()
+        } else {
+            text
+        }
+    }
+}
+
+// borde de un rectángulo
+fn outline(min: (f64, f64), max: (f64, f64), color: usize) -> impl Iterator<Item = Shape> {
+    let corners = [min, (max.0, min.1), max, (min.0, max.1)];
+    (0..4).map(move |i| Shape::Line {
+        from: corners[i],
+        to: corners[(i + 1) % 4],
+        color,
+        dashed: false,
+    })
+}
+
+// 1, 2 o 5 × 10^n
+fn nice_step(raw: f64) -> f64 {
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let fraction = raw / magnitude;
+    let nice = if fraction <= 1.0 {
+        1.0
+    } else if fraction <= 2.0 {
+        2.0
+    } else if fraction <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+// Parte la serie en `max_points / 2` tramos seguidos y de cada uno guarda su mínimo y su
+// máximo en y, en el orden en que aparecen: un pico de un solo paso (el empuje al lanzar)
+// sobrevive, cosa que no pasa tomando un punto de cada N. El primer y el último punto se
+// guardan siempre.
+fn decimate(points: Vec<(f64, f64)>, max_points: usize) -> Vec<(f64, f64)> {
+    if points.len() <= max_points {
+        return points;
+    }
+    let bucket = points.len().div_ceil((max_points / 2).max(1));
+    let mut kept = vec![0];
+    for (start, chunk) in (0..).step_by(bucket).zip(points.chunks(bucket)) {
+        let by_y = |&i: &usize, &j: &usize| chunk[i].1.total_cmp(&chunk[j].1);
+        let low = start + (0..chunk.len()).min_by(by_y).unwrap_or(0);
+        let high = start + (0..chunk.len()).max_by(by_y).unwrap_or(0);
+        kept.extend([low.min(high), low.max(high)]);
+    }
+    kept.push(points.len() - 1);
+    kept.dedup();
+    kept.into_iter().map(|i| points[i]).collect()
+}
+
+impl Chart {
+    /// Gráfica vacía con título y rótulos.
+    pub fn new(title: &str, x_label: &str, y_label: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            x_label: x_label.to_string(),
+            y_label: y_label.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Añade una serie, aclarada a unos `MAX_PLOT_POINTS` sin perder picos (ver `decimate`).
+    pub fn with_series(mut self, label: &str, points: Vec<(f64, f64)>) -> Self {
+        let points = decimate(points, MAX_PLOT_POINTS);
+        self.series.push(Series { label: label.to_string(), points });
+        self
+    }
+
+    /// Añade una marca de evento.
+    pub fn with_marker(mut self, label: &str, x: f64, y: Option<f64>) -> Self {
+        self.markers.push(Marker { label: label.to_string(), x, y });
+        self
+    }
+
+    fn scene(&self, width: usize, height: usize) -> Vec<Shape> {
+        let (width, height) = (width as f64, height as f64);
+        let left = MARGIN_LEFT;
+        let right = (width - MARGIN_RIGHT).max(left + 1.0);
+        let top = MARGIN_TOP;
+        let bottom = (height - MARGIN_BOTTOM).max(top + 1.0);
+
+        // rangos a partir de los datos y de los eventos con posición
+        let mut x_range = (f64::INFINITY, f64::NEG_INFINITY);
+        let mut y_range = (f64::INFINITY, f64::NEG_INFINITY);
+        let mut include = |x: f64, y: Option<f64>| {
+            if x.is_finite() {
+                x_range = (x_range.0.min(x), x_range.1.max(x));
+            }
+            if let Some(y) = y.filter(|y| y.is_finite()) {
+                y_range = (y_range.0.min(y), y_range.1.max(y));
+            }
+        };
+        for series in &self.series {
+            for &(x, y) in &series.points {
+                if x.is_finite() && y.is_finite() {
+                    include(x, Some(y));
+                }
+            }
+        }
+        for marker in &self.markers {
+            include(marker.x, marker.y);
+        }
+        let mut x_axis = Axis::fit(x_range.0, x_range.1);
+        let mut y_axis = Axis::fit(y_range.0, y_range.1);
+        if self.equal_aspect {
+            let x_scale = (x_axis.max - x_axis.min) / (right - left);
+            let y_scale = (y_axis.max - y_axis.min) / (bottom - top);
+            if x_scale > y_scale {
+                y_axis.widen(x_scale * (bottom - top));
+            } else {
+                x_axis.widen(y_scale * (right - left));
+            }
+        }
+        let px = |x: f64| left + (x - x_axis.min) / (x_axis.max - x_axis.min) * (right - left);
+        let py = |y: f64| bottom - (y - y_axis.min) / (y_axis.max - y_axis.min) * (bottom - top);
+        let text = |at, text: &str, size, anchor, vertical| Shape::Text {
+            at,
+            text: text.to_string(),
+            size,
+            anchor,
+            vertical,
+        };
+
+        let background = Shape::Rect { min: (0.0, 0.0), max: (width, height), fill: BACKGROUND };
+        let mut shapes = vec![background];
+
+        // rejilla y marcas de los ejes
+        for x in x_axis.ticks() {
+            let p = px(x);
+            let (from, to) = ((p, top), (p, bottom));
+            shapes.push(Shape::Line { from, to, color: GRID, dashed: false });
+            shapes.push(text((p, bottom + 16.0), &x_axis.label(x), 11.0, Anchor::Middle, false));
+        }
+        for y in y_axis.ticks() {
+            let p = py(y);
+            let (from, to) = ((left, p), (right, p));
+            shapes.push(Shape::Line { from, to, color: GRID, dashed: false });
+            shapes.push(text((left - 6.0, p + 4.0), &y_axis.label(y), 11.0, Anchor::End, false));
+        }
+
+        // eventos
+        for (i, marker) in self.markers.iter().enumerate() {
+            let x = px(marker.x);
+            match marker.y {
+                Some(y) => {
+                    let center = (x, py(y));
+                    shapes.push(Shape::Dot { center, color: EVENT });
+                    let at = (center.0 + 6.0, center.1 - 6.0);
+                    shapes.push(text(at, &marker.label, 11.0, Anchor::Start, false));
+                }
+                None => {
+                    let (from, to) = ((x, top), (x, bottom));
+                    shapes.push(Shape::Line { from, to, color: EVENT, dashed: true });
+                    // escalonadas para que no se pisen; junto al borde derecho, a la izquierda
+                    let y = top + 12.0 + 12.0 * (i % 3) as f64;
+                    let fits = x + 3.0 + 7.0 * marker.label.chars().count() as f64 <= right;
+                    let (at, anchor) = if fits {
+                        ((x + 3.0, y), Anchor::Start)
+                    } else {
+                        ((x - 3.0, y), Anchor::End)
+                    };
+                    shapes.push(text(at, &marker.label, 11.0, anchor, false));
+                }
+            }
+        }
+
+        // series, cortadas en los puntos no finitos
+        for (i, series) in self.series.iter().enumerate() {
+            let color = FIRST_SERIES + i % (COLORS.len() - FIRST_SERIES);
+            for run in series.points.split(|(x, y)| !(x.is_finite() && y.is_finite())) {
+                if !run.is_empty() {
+                    let points = run.iter().map(|&(x, y)| (px(x), py(y))).collect();
+                    shapes.push(Shape::Polyline { points, color });
+                }
+            }
+        }
+
+        // marco, títulos y leyenda
+        shapes.extend(outline((left, top), (right, bottom), INK));
+        shapes.push(text((0.5 * width, 22.0), &self.title, 15.0, Anchor::Middle, false));
+        let center = 0.5 * (left + right);
+        shapes.push(text((center, height - 10.0), &self.x_label, 12.0, Anchor::Middle, false));
+        let at = (18.0, 0.5 * (top + bottom));
+        shapes.push(text(at, &self.y_label, 12.0, Anchor::Middle, true));
+
+        if self.series.len() > 1 {
+            let longest = self.series.iter().map(|s| s.label.chars().count()).max().unwrap_or(0);
+            let box_width = 34.0 + 7.0 * longest as f64;
+            let (x0, y0) = (right - box_width - 8.0, top + 8.0);
+            let y1 = y0 + 8.0 + 16.0 * self.series.len() as f64;
+            let x1 = right - 8.0;
+            shapes.push(Shape::Rect { min: (x0, y0), max: (x1, y1), fill: BACKGROUND });
+            shapes.extend(outline((x0, y0), (x1, y1), GRID));
+            for (i, series) in self.series.iter().enumerate() {
+                let color = FIRST_SERIES + i % (COLORS.len() - FIRST_SERIES);
+                let y = y0 + 12.0 + 16.0 * i as f64;
+                let points = vec![(x0 + 6.0, y), (x0 + 26.0, y)];
+                shapes.push(Shape::Polyline { points, color });
+                shapes.push(text((x0 + 30.0, y + 4.0), &series.label, 11.0, Anchor::Start, false));
+            }
+        }
+        shapes
+    }
+
+    /// Documento SVG de `width` × `height` px.
+    pub fn to_svg(&self, width: usize, height: usize) -> String {
+        let hex = |c: usize| {
+            let (r, g, b) = COLORS[c];
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        };
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif">"#,
+            w = width,
+            h = height
+        );
+        svg.push('\n');
+        for shape in self.scene(width, height) {
+            let element = match shape {
+                Shape::Rect { min, max, fill } => format!(
+                    r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+                    min.0,
+                    min.1,
+                    max.0 - min.0,
+                    max.1 - min.1,
+                    hex(fill)
+                ),
+                Shape::Line { from, to, color, dashed } => format!(
+                    r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}"{}/>"#,
+                    from.0,
+                    from.1,
+                    to.0,
+                    to.1,
+                    hex(color),
+                    if dashed { r#" stroke-dasharray="4 3""# } else { "" }
+                ),
+                Shape::Polyline { points, color } => {
+                    let coords: Vec<String> =
+                        points.iter().map(|(x, y)| format!("{:.1},{:.1}", x, y)).collect();
+                    format!(
+                        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+                        coords.join(" "),
+                        hex(color)
+                    )
+                }
+                Shape::Dot { center, color } => format!(
+                    r#"<circle cx="{:.1}" cy="{:.1}" r="3.5" fill="{}"/>"#,
+                    center.0,
+                    center.1,
+                    hex(color)
+                ),
+                Shape::Text { at, text, size, anchor, vertical } => {
+                    let anchor = match anchor {
+                        Anchor::Start => "start",
+                        Anchor::Middle => "middle",
+                        Anchor::End => "end",
+                    };
+                    let rotate = if vertical {
+                        format!(r#" transform="rotate(-90 {:.1} {:.1})""#, at.0, at.1)
+                    } else {
+                        String::new()
+                    };
+                    format!(
+                        r#"<text x="{:.1}" y="{:.1}" font-size="{}" text-anchor="{}"{}>{}</text>"#,
+                        at.0,
+                        at.1,
+                        size,
+                        anchor,
+                        rotate,
+                        escape_xml(&text)
+                    )
+                }
+            };
+            svg.push_str(&element);
+            svg.push('\n');
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Imagen PNG de `width` × `height` px.
+    pub fn to_png(&self, width: usize, height: usize) -> Vec<u8> {
+        let mut canvas = Canvas::new(width, height);
+        for shape in self.scene(width, height) {
+            match shape {
+                Shape::Rect { min, max, fill } => canvas.fill_rect(min, max, fill),
+                Shape::Line { from, to, color, dashed } => canvas.line(from, to, color, dashed),
+                Shape::Polyline { points, color } => {
+                    for pair in points.windows(2) {
+                        canvas.line(pair[0], pair[1], color, false);
+                        // 2 px de grosor
+                        let shift = |(x, y): (f64, f64)| (x, y + 1.0);
+                        canvas.line(shift(pair[0]), shift(pair[1]), color, false);
+                    }
+                }
+                Shape::Dot { center, color } => canvas.dot(center, 3.5, color),
+                Shape::Text { at, text, size, anchor, vertical } => {
+                    canvas.text(at, &text, if size >= 14.0 { 2 } else { 1 }, anchor, vertical)
+                }
+            }
+        }
+        canvas.encode_png()
+    }
+
+    /// Escribe la gráfica en `path` con ese formato y tamaño [px].
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: PlotFormat,
+        width: usize,
+        height: usize,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        match format {
+            PlotFormat::Svg => writer.write_all(self.to_svg(width, height).as_bytes())?,
+            PlotFormat::Png => writer.write_all(&self.to_png(width, height))?,
+        }
+        writer.flush()
+    }
+}
+
+/// Las gráficas de siempre: trayectoria y magnitudes frente al tiempo, con los eventos.
+/// Devuelve (nombre de archivo sin extensión, gráfica).
+pub fn flight_charts(records: &[LogRecord], events: &[FlightEvent]) -> Vec<(&'static str, Chart)> {
+    let over_time = |pick: fn(&LogRecord) -> f64| -> Vec<(f64, f64)> {
+        records.iter().map(|r| (r.time, pick(r))).collect()
+    };
+    let with_events = |mut chart: Chart| {
+        for event in events.iter().filter(|e| e.kind != FlightEventKind::Launch) {
+            chart = chart.with_marker(&event.kind.to_string(), event.time, None);
+        }
+        chart
+    };
+
+    let mut trajectory = Chart::new("Trayectoria (Y vs X)", "Posición X (m)", "Posición Y (m)")
+        .with_series("trayectoria", records.iter().map(|r| (r.position.x, r.position.y)).collect());
+    trajectory.equal_aspect = true;
+    for event in events.iter().filter(|e| e.kind != FlightEventKind::Launch) {
+        let label = event.kind.to_string();
+        trajectory = trajectory.with_marker(&label, event.position.x, Some(event.position.y));
+    }
+
+    vec![
+        ("trayectoria_y_vs_x", trajectory),
+        (
+            "altitud_vs_tiempo",
+            with_events(
+                Chart::new("Altitud vs Tiempo", "Tiempo (s)", "Altitud (m)")
+                    .with_series("altitud", over_time(|r| r.position.y)),
+            ),
+        ),
+        (
+            "velocidades_vs_tiempo",
+            with_events(
+                Chart::new("Velocidades vs Tiempo", "Tiempo (s)", "Velocidad (m/s)")
+                    .with_series("vx", over_time(|r| r.velocity.x))
+                    .with_series("vy", over_time(|r| r.velocity.y))
+                    .with_series("módulo", over_time(|r| r.velocity.magnitude())),
+            ),
+        ),
+        (
+            "aceleraciones_vs_tiempo",
+            with_events(
+                Chart::new("Aceleraciones vs Tiempo", "Tiempo (s)", "Aceleración (m/s²)")
+                    .with_series("ax", over_time(|r| r.acceleration.x))
+                    .with_series("ay", over_time(|r| r.acceleration.y))
+                    .with_series("módulo", over_time(|r| r.acceleration.magnitude())),
+            ),
+        ),
+        (
+            "thrust_vs_tiempo",
+            with_events(
+                Chart::new("Empuje vs Tiempo", "Tiempo (s)", "Empuje (N)")
+                    .with_series("empuje", over_time(|r| r.thrust)),
+            ),
+        ),
+        (
+            "presion_vs_tiempo",
+            with_events(
+                Chart::new("Presión Absoluta vs Tiempo", "Tiempo (s)", "Presión absoluta (kPa)")
+                    .with_series("presión", over_time(|r| r.pressure / 1000.0)),
+            ),
+        ),
+        (
+            "masas_vs_tiempo",
+            with_events(
+                Chart::new("Masas vs Tiempo", "Tiempo (s)", "Masa (kg)")
+                    .with_series("agua", over_time(|r| r.water_mass))
+                    .with_series("total", over_time(|r| r.total_mass)),
+            ),
+        ),
+    ]
+}
+
+/// Guarda `flight_charts` en `dir` (lo crea si no existe) y devuelve las rutas escritas.
+pub fn export_flight_charts<P: AsRef<Path>>(
+    dir: P,
+    records: &[LogRecord],
+    events: &[FlightEvent],
+    format: PlotFormat,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(&dir)?;
+    let mut written = Vec::new();
+    for (name, chart) in flight_charts(records, events) {
+        let path = dir.as_ref().join(format!("{}.{}", name, format.extension()));
+        chart.save(&path, format, DEFAULT_WIDTH, DEFAULT_HEIGHT)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Lo mismo a partir de un CSV con el formato por defecto; los eventos se deducen de los datos.
+pub fn plot_csv<P: AsRef<Path>, Q: AsRef<Path>>(
+    csv_path: P,
+    dir: Q,
+    format: PlotFormat,
+) -> io::Result<Vec<PathBuf>> {
+    let records = csv_export::read_records(csv_path)?;
+    export_flight_charts(dir, &records, &events::detect_events(&records), format)
+}
+
+// =========================
+// PNG
+// =========================
+
+// lienzo con índices de `COLORS`
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self { width, height, pixels: vec![BACKGROUND as u8; width * height] }
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: usize) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.pixels[y as usize * self.width + x as usize] = color as u8;
+        }
+    }
+
+    fn fill_rect(&mut self, min: (f64, f64), max: (f64, f64), color: usize) {
+        for y in min.1.round() as i64..max.1.round() as i64 {
+            for x in min.0.round() as i64..max.0.round() as i64 {
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    // Bresenham, sin antialiasing
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), color: usize, dashed: bool) {
+        let (mut x, mut y) = (from.0.round() as i64, from.1.round() as i64);
+        let (x1, y1) = (to.0.round() as i64, to.1.round() as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut error = dx + dy;
+        let mut count = 0;
+        loop {
+            if !dashed || count % 7 < 4 {
+                self.set(x, y, color);
+            }
+            count += 1;
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * error;
+            if e2 >= dy {
+                error += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    fn dot(&mut self, center: (f64, f64), radius: f64, color: usize) {
+        let r = radius.ceil() as i64;
+        let (cx, cy) = (center.0.round() as i64, center.1.round() as i64);
+        for dy in -r..=r {
+            for dx in -r..=r {
+                if ((dx * dx + dy * dy) as f64) <= radius * radius {
+                    self.set(cx + dx, cy + dy, color);
+                }
+            }
+        }
+    }
+
+    // `at` es la línea base, como en SVG; en vertical el texto se lee de abajo arriba
+    fn text(&mut self, at: (f64, f64), text: &str, scale: i64, anchor: Anchor, vertical: bool) {
+        let advance = 6 * scale;
+        let length = text.chars().count() as i64 * advance - scale;
+        let shift = match anchor {
+            Anchor::Start => 0,
+            Anchor::Middle => length / 2,
+            Anchor::End => length,
+        };
+        let (x0, y0) = (at.0.round() as i64, at.1.round() as i64);
+        for (i, c) in text.chars().enumerate() {
+            let rows = glyph(c);
+            for (row, bits) in rows.iter().enumerate() {
+                for column in 0..5 {
+                    if bits & (0x10 >> column) == 0 {
+                        continue;
+                    }
+                    for sy in 0..scale {
+                        for sx in 0..scale {
+                            // posición dentro del texto: a lo largo y hacia arriba desde la base
+                            let along = i as i64 * advance + column * scale + sx - shift;
+                            let up = (7 - row as i64) * scale - sy;
+                            if vertical {
+                                self.set(x0 - up, y0 - along, INK);
+                            } else {
+                                self.set(x0 + along, y0 - up, INK);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// PNG de 8 bits con paleta, filtro "Up" por fila y deflate con Huffman fijo.
+    fn encode_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            raw.push(2); // filtro Up
+            let row = &self.pixels[y * self.width..(y + 1) * self.width];
+            if y == 0 {
+                raw.extend_from_slice(row);
+            } else {
+                let up = &self.pixels[(y - 1) * self.width..y * self.width];
+                raw.extend(row.iter().zip(up).map(|(a, b)| a.wrapping_sub(*b)));
+            }
+        }
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 3, 0, 0, 0]); // 8 bits, paleta
+        let palette: Vec<u8> = COLORS.iter().flat_map(|&(r, g, b)| [r, g, b]).collect();
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"PLTE", &palette);
+        png_chunk(&mut png, b"IDAT", &zlib_compress(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+// Un bloque deflate con los códigos fijos; solo busca repeticiones del byte anterior
+// (distancia 1), que es lo que abunda en una gráfica filtrada con "Up".
+fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    const LENGTH_BASE: [usize; 29] = [
+        3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+        131, 163, 195, 227, 258,
+    ];
+    const LENGTH_EXTRA: [u32; 29] = [
+        0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+    ];
+
+    let mut bits = BitWriter::default();
+    bits.write(1, 1); // último bloque
+    bits.write(1, 2); // Huffman fijo
+    let literal = |bits: &mut BitWriter, symbol: usize| match symbol {
+        0..=143 => bits.write_code(0x30 + symbol as u32, 8),
+        144..=255 => bits.write_code(0x190 + (symbol - 144) as u32, 9),
+        256..=279 => bits.write_code((symbol - 256) as u32, 7),
+        _ => bits.write_code(0xC0 + (symbol - 280) as u32, 8),
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let run = if i == 0 {
+            0
+        } else {
+            data[i..].iter().take(258).take_while(|&&b| b == data[i - 1]).count()
+        };
+        if run >= 3 {
+            let code = LENGTH_BASE.iter().rposition(|&base| base <= run).unwrap_or(0);
+            literal(&mut bits, 257 + code);
+            bits.write((run - LENGTH_BASE[code]) as u32, LENGTH_EXTRA[code]);
+            bits.write_code(0, 5); // distancia 1
+            i += run;
+        } else {
+            literal(&mut bits, data[i] as usize);
+            i += 1;
+        }
+    }
+    literal(&mut bits, 256); // fin de bloque
+
+    let mut out = vec![0x78, 0x01];
+    out.extend(bits.finish());
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    out.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    out
+}
+
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    // bits sueltos, el menos significativo primero
+    fn write(&mut self, value: u32, count: u32) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // los códigos de Huffman van con el más significativo primero
+    fn write_code(&mut self, code: u32, length: u32) {
+        let reversed = code.reverse_bits() >> (32 - length);
+        self.write(reversed, length);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.buffer as u8);
+        }
+        self.out
+    }
+}
+
+// fuente 5×7; minúsculas y tildes se dibujan como su mayúscula sin tilde
+fn glyph(c: char) -> [u8; 7] {
+    let c = match c.to_ascii_uppercase() {
+        'á' | 'Á' => 'A',
+        'é' | 'É' => 'E',
+        'í' | 'Í' => 'I',
+        'ó' | 'Ó' => 'O',
+        'ú' | 'Ú' | 'ü' | 'Ü' => 'U',
+        'ñ' | 'Ñ' => 'N',
+        '²' => '2',
+        c => c,
+    };
+    match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '|' => [0x04; 7],
+        _ => [0x00; 7],
+    }
+}
+
+/// SVG con todas las piezas y PNG válido (firma, CRC de los bloques y flujo zlib).
+#[test]
+fn charts_render_svg_and_png() {
+    let points: Vec<(f64, f64)> =
+        (0..200).map(|i| (i as f64 * 0.01, (i as f64 * 0.05).sin())).collect();
+    let chart = Chart::new("Prueba <1>", "Tiempo (s)", "Valor")
+        .with_series("seno", points.clone())
+        .with_series("doble", points.iter().map(|&(t, y)| (t, 2.0 * y)).collect())
+        .with_marker("apogeo", 0.5, None);
+
+    let svg = chart.to_svg(DEFAULT_WIDTH, DEFAULT_HEIGHT);
+    assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches("<polyline").count(), 2 + 2); // series + leyenda
+    assert!(svg.contains("Prueba &lt;1&gt;") && svg.contains("stroke-dasharray"));
+    assert!(svg.contains(">-2<") && svg.contains(">2<"));
+
+    let png = chart.to_png(DEFAULT_WIDTH, DEFAULT_HEIGHT);
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let mut offset = 8;
+    let mut kinds = Vec::new();
+    while offset < png.len() {
+        let len = u32::from_be_bytes(png[offset..offset + 4].try_into().unwrap()) as usize;
+        let body = &png[offset + 4..offset + 8 + len];
+        let crc = u32::from_be_bytes(png[offset + 8 + len..offset + 12 + len].try_into().unwrap());
+        assert_eq!(crc, crc32(body));
+        kinds.push(String::from_utf8_lossy(&body[..4]).to_string());
+        offset += 12 + len;
+    }
+    assert_eq!(kinds, ["IHDR", "PLTE", "IDAT", "IEND"]);
+    assert!(png.len() < DEFAULT_WIDTH * DEFAULT_HEIGHT / 10, "{} bytes", png.len());
+}
+
+/// Al aclarar una serie larga se conservan los picos de un solo punto, el orden y los extremos.
+#[test]
+fn decimation_keeps_peaks() {
+    let mut points: Vec<(f64, f64)> = (0..100_003).map(|i| (i as f64 * 1e-4, 1.0)).collect();
+    points[12_345].1 = 250.0;
+    points[87_654].1 = -3.0;
+    let kept = decimate(points.clone(), MAX_PLOT_POINTS);
+    assert!(kept.len() <= MAX_PLOT_POINTS + 2, "{}", kept.len());
+    assert!(kept.contains(&points[12_345]) && kept.contains(&points[87_654]));
+    assert_eq!((kept[0], kept[kept.len() - 1]), (points[0], points[100_002]));
+    assert!(kept.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(decimate(points[..10].to_vec(), MAX_PLOT_POINTS), points[..10]);
+}
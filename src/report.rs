@@ -0,0 +1,289 @@
+// report.rs
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::{
+    config::SimulationConfig,
+    events::{FlightEvent, FlightSummary},
+    geodesy::GeodeticPoint,
+    json_export::{self, Json},
+    logging::LogRecord,
+    map_export::escape_xml,
+    plotting::{self, Chart, DEFAULT_HEIGHT, DEFAULT_WIDTH},
+};
+
+/// Lo que el informe necesita de una corrida.
+#[derive(Clone, Debug)]
+pub struct RunReport {
+    /// Nombre de la corrida en el informe.
+    pub name: String,
+    /// Configuración, para la tabla de parámetros.
+    pub config: SimulationConfig,
+    /// Cifras clave.
+    pub summary: FlightSummary,
+    /// Eventos, marcados en las gráficas.
+    pub events: Vec<FlightEvent>,
+    /// Registros para las gráficas.
+    pub records: Vec<LogRecord>,
+    /// Impacto geográfico, si hay lugar de lanzamiento.
+    pub landing_point: Option<GeodeticPoint>,
+}
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:1000px;margin:2em auto;color:#222}\
+h1{border-bottom:2px solid #1f77b4}table{border-collapse:collapse;margin:0.5em 0 1.5em}\
+th,td{border:1px solid #ccc;padding:0.25em 0.6em;text-align:left}\
+td.num{text-align:right;font-variant-numeric:tabular-nums}th{background:#f0f0f0}\
+.charts svg{max-width:100%;height:auto;margin:0.5em 0}";
+
+/// Documento HTML autocontenido (sin recursos externos): una corrida con todo su detalle o,
+/// con varias, una comparación de cifras, de los parámetros que cambian y de las curvas.
+pub fn html_report(title: &str, runs: &[RunReport]) -> String {
+    let mut html = String::new();
+    let _ = writeln!(html, "<!DOCTYPE html>");
+    let _ = writeln!(html, r#"<html lang="es"><head><meta charset="utf-8">"#);
+    let _ = writeln!(
+        html,
+        "<title>{}</title><style>{}</style></head><body>",
+        escape_xml(title),
+        STYLE
+    );
+    let _ = writeln!(html, "<h1>{}</h1>", escape_xml(title));
+    match runs {
+        [] => html.push_str("<p>No hay corridas.</p>\n"),
+        [run] => single_run(&mut html, run),
+        _ => comparison(&mut html, runs),
+    }
+    let _ = writeln!(
+        html,
+        "<footer><small>Generado por physics_calculations {}</small></footer>",
+        env!("CARGO_PKG_VERSION")
+    );
+    html.push_str("</body></html>\n");
+    html
+}
+
+/// Escribe el informe HTML de `runs` en `path`.
+pub fn export_html<P: AsRef<Path>>(path: P, title: &str, runs: &[RunReport]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(html_report(title, runs).as_bytes())?;
+    writer.flush()
+}
+
+fn single_run(html: &mut String, run: &RunReport) {
+    html.push_str("<h2>Cifras clave</h2>\n");
+    let rows: Vec<(String, Vec<String>)> =
+        metrics(run).into_iter().map(|(k, v)| (k.to_string(), vec![v])).collect();
+    table(html, &["Magnitud", "Valor"], &rows);
+
+    html.push_str("<h2>Eventos</h2>\n");
+    events_table(html, &run.events);
+
+    html.push_str(r#"<h2>Gráficas</h2><div class="charts">"#);
+    html.push('\n');
+    for (_, chart) in plotting::flight_charts(&run.records, &run.events) {
+        html.push_str(&chart.to_svg(DEFAULT_WIDTH, DEFAULT_HEIGHT));
+    }
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Configuración</h2>\n");
+    let rows: Vec<(String, Vec<String>)> =
+        config_entries(&run.config).into_iter().map(|(k, v)| (k, vec![v])).collect();
+    table(html, &["Parámetro", "Valor"], &rows);
+}
+
+fn comparison(html: &mut String, runs: &[RunReport]) {
+    let mut headers = vec!["Magnitud"];
+    headers.extend(runs.iter().map(|r| r.name.as_str()));
+
+    html.push_str("<h2>Cifras clave</h2>\n");
+    let per_run: Vec<Vec<(&str, String)>> = runs.iter().map(metrics).collect();
+    let rows: Vec<(String, Vec<String>)> = (0..per_run[0].len())
+        .map(|i| (per_run[0][i].0.to_string(), per_run.iter().map(|m| m[i].1.clone()).collect()))
+        .collect();
+    table(html, &headers, &rows);
+
+    // solo los parámetros que cambian entre corridas
+    html.push_str("<h2>Parámetros que cambian</h2>\n");
+    let configs: Vec<Vec<(String, String)>> =
+        runs.iter().map(|r| config_entries(&r.config)).collect();
+    let value_of = |entries: &[(String, String)], key: &str| {
+        entries.iter().find(|(k, _)| k == key).map_or("—".to_string(), |(_, v)| v.clone())
+    };
+    // claves de todas las corridas (un racimo o una geometría solo salen en algunas), en el
+    // orden en que aparecen
+    let mut keys: Vec<&String> = Vec::new();
+    for (key, _) in configs.iter().flatten() {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    let rows: Vec<(String, Vec<String>)> = keys
+        .into_iter()
+        .map(|key| (key.clone(), configs.iter().map(|c| value_of(c, key)).collect::<Vec<_>>()))
+        .filter(|(_, values)| values.iter().any(|v| v != &values[0]))
+        .collect();
+    if rows.is_empty() {
+        html.push_str("<p>Todas las corridas usan la misma configuración.</p>\n");
+    } else {
+        let mut headers = vec!["Parámetro"];
+        headers.extend(runs.iter().map(|r| r.name.as_str()));
+        table(html, &headers, &rows);
+    }
+
+    html.push_str(r#"<h2>Gráficas</h2><div class="charts">"#);
+    html.push('\n');
+    let overlay = |title: &str, x_label: &str, y_label: &str, pick: fn(&LogRecord) -> (f64, f64)| {
+        let mut chart = Chart::new(title, x_label, y_label);
+        for run in runs {
+            chart = chart.with_series(&run.name, run.records.iter().map(pick).collect());
+        }
+        chart
+    };
+    let time = "Tiempo (s)";
+    let (x_label, y_label) = ("Posición X (m)", "Posición Y (m)");
+    let mut trajectory =
+        overlay("Trayectorias (Y vs X)", x_label, y_label, |r| (r.position.x, r.position.y));
+    trajectory.equal_aspect = true;
+    for chart in [
+        trajectory,
+        overlay("Altitud vs Tiempo", time, "Altitud (m)", |r| (r.time, r.position.y)),
+        overlay("Empuje vs Tiempo", time, "Empuje (N)", |r| (r.time, r.thrust)),
+        overlay("Rapidez vs Tiempo", time, "Velocidad (m/s)", |r| (r.time, r.velocity.magnitude())),
+    ] {
+        html.push_str(&chart.to_svg(DEFAULT_WIDTH, DEFAULT_HEIGHT));
+    }
+    html.push_str("</div>\n");
+
+    for run in runs {
+        let _ = writeln!(html, "<h2>Eventos: {}</h2>", escape_xml(&run.name));
+        events_table(html, &run.events);
+    }
+}
+
+// (etiqueta, valor ya formateado)
+fn metrics(run: &RunReport) -> Vec<(&'static str, String)> {
+    let s = &run.summary;
+    vec![
+        ("Apogeo", format!("{:.2} m", s.apogee)),
+        ("Tiempo al apogeo", format!("{:.3} s", s.apogee_time)),
+        ("Alcance", format!("{:.2} m", s.range)),
+        ("Tiempo de vuelo", format!("{:.3} s", s.flight_time)),
+        ("Fin de propulsión", s.burnout_time.map_or("—".to_string(), |t| format!("{:.4} s", t))),
+        ("Empuje máximo", format!("{:.1} N", s.peaks.max_thrust)),
+        ("Velocidad máxima", format!("{:.2} m/s", s.peaks.max_speed)),
+        ("Aceleración máxima", format!("{:.1} m/s²", s.peaks.max_acceleration)),
+        ("Presión máxima (abs.)", format!("{:.1} kPa", s.peaks.max_pressure / 1000.0)),
+        ("Punto de impacto", run.landing_point.map_or("—".to_string(), |p| p.to_string())),
+    ]
+}
+
+fn events_table(html: &mut String, events: &[FlightEvent]) {
+    let rows: Vec<(String, Vec<String>)> = events
+        .iter()
+        .map(|e| {
+            (
+                e.kind.to_string(),
+                vec![
+                    format!("{:.4}", e.time),
+                    format!("{:.2}", e.position.x),
+                    format!("{:.2}", e.position.y),
+                    format!("{:.2}", e.velocity.magnitude()),
+                ],
+            )
+        })
+        .collect();
+    table(html, &["Evento", "t (s)", "x (m)", "y (m)", "|v| (m/s)"], &rows);
+}
+
+fn table(html: &mut String, headers: &[&str], rows: &[(String, Vec<String>)]) {
+    html.push_str("<table><tr>");
+    for header in headers {
+        let _ = write!(html, "<th>{}</th>", escape_xml(header));
+    }
+    html.push_str("</tr>\n");
+    for (label, values) in rows {
+        let _ = write!(html, "<tr><th>{}</th>", escape_xml(label));
+        for value in values {
+            let numeric = value.starts_with(|c: char| c.is_ascii_digit() || c == '-');
+            let class = if numeric { r#" class="num""# } else { "" };
+            let _ = write!(html, "<td{}>{}</td>", class, escape_xml(value));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+}
+
+/// La configuración de `config_json` aplanada: `bottle.name`, `cluster[0].water_mass_kg`, ...
+fn config_entries(config: &SimulationConfig) -> Vec<(String, String)> {
+    fn flatten(prefix: String, value: &Json, out: &mut Vec<(String, String)>) {
+        match value {
+            Json::Object(fields) => {
+                for (key, value) in fields {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    flatten(key, value, out);
+                }
+            }
+            Json::Array(items) if items.iter().any(|i| matches!(i, Json::Object(_))) => {
+                for (i, item) in items.iter().enumerate() {
+                    flatten(format!("{}[{}]", prefix, i), item, out);
+                }
+            }
+            Json::Array(items) if items.is_empty() => out.push((prefix, "—".to_string())),
+            Json::String(text) => out.push((prefix, text.clone())),
+            Json::Null => out.push((prefix, "—".to_string())),
+            value => out.push((prefix, value.to_string())),
+        }
+    }
+    let mut out = Vec::new();
+    flatten(String::new(), &json_export::config_json(config), &mut out);
+    out
+}
+
+/// Una corrida trae cifras, eventos, gráficas y configuración; varias, la comparación.
+#[test]
+fn report_single_and_comparison() {
+    use crate::simulation::Simulation;
+
+    let run = |name: &str, psi: f64| {
+        let config =
+            SimulationConfig { initial_air_pressure_psi: psi, ..SimulationConfig::default() };
+        let mut sim = Simulation::with_config(0.001, config).unwrap();
+        sim.run(30.0).unwrap();
+        sim.report(name)
+    };
+    let low = run("40 psi", 40.0);
+    let high = run("60 psi", 60.0);
+
+    let single = html_report("Lanzamiento", std::slice::from_ref(&low));
+    assert!(single.starts_with("<!DOCTYPE html>") && single.ends_with("</html>\n"));
+    assert!(single.contains("<th>Apogeo</th>") && single.contains("<th>apogeo</th>"));
+    assert_eq!(single.matches("<svg").count(), 7);
+    assert!(single.contains("<th>initial_air_pressure_psi</th><td class=\"num\">40</td>"));
+    assert!(!single.contains("src=") && !single.contains("href="));
+
+    let changed = |html: &str| {
+        let start = html.find("Parámetros que cambian").unwrap();
+        html[start..html.find("Gráficas").unwrap()].to_string()
+    };
+    let both = html_report("Comparación", &[low.clone(), high]);
+    assert!(both.contains("<th>40 psi</th><th>60 psi</th>"));
+    // encabezado + la presión, lo único distinto
+    let table = changed(&both);
+    assert_eq!(table.matches("</tr>").count(), 2, "{}", table);
+
+    // los parámetros que solo tiene la segunda corrida también salen
+    let config = SimulationConfig {
+        stability: Some(crate::physics::stability::StabilityModel::default()),
+        ..low.config.clone()
+    };
+    let mut sim = Simulation::with_config(0.001, config).unwrap();
+    sim.run(30.0).unwrap();
+    let table = changed(&html_report("Comparación", &[low, sim.report("estable")]));
+    assert!(table.contains("<th>stability.launch_rail_length_m</th><td>—</td>"), "{}", table);
+}
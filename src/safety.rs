@@ -0,0 +1,253 @@
+// safety.rs
+use std::fmt;
+
+use crate::{config::SimulationConfig, constants::PSI_TO_PA, physics::bottle::BottleSpec};
+
+/// Qué hacer cuando la presión de lanzamiento no respeta el factor de seguridad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SafetyMode {
+    /// No construir la simulación.
+    Refuse,
+    /// Simular de todas formas y avisar.
+    Warn,
+}
+
+/// Qué margen se exige a la botella y qué hacer si no lo tiene.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SafetyPolicy {
+    /// Presión de reventón / presión de lanzamiento mínima aceptable.
+    pub safety_factor: f64,
+    /// Rechazar o avisar.
+    pub mode: SafetyMode,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self { safety_factor: 1.5, mode: SafetyMode::Refuse }
+    }
+}
+
+/// Gravedad del margen, de menos a más.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SafetyLevel {
+    /// Por debajo de la presión nominal.
+    Ok,
+    /// Por encima de la presión nominal pero dentro del factor de seguridad.
+    AboveRated,
+    /// Dentro del límite de reventón pero sin el factor de seguridad pedido.
+    InsufficientMargin,
+    /// Igual o por encima de la presión de reventón (corregida por temperatura).
+    Burst,
+}
+
+/// Margen de la botella en el instante del lanzamiento.
+/// Todas las presiones son manométricas [Pa].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SafetyReport {
+    /// Botella revisada.
+    pub bottle_name: String,
+    /// Presión de lanzamiento.
+    pub launch_pressure_pa: f64,
+    /// Temperatura de la botella al lanzar [°C].
+    pub launch_temperature_c: f64,
+    /// Presión nominal, ya corregida por temperatura.
+    pub rated_pressure_pa: f64,
+    /// Presión de reventón, ya corregida por temperatura.
+    pub burst_pressure_pa: f64,
+    /// El factor de la política.
+    pub required_safety_factor: f64,
+    /// Reventón / lanzamiento (infinito si no hay presión).
+    pub actual_safety_factor: f64,
+    /// Presión que aún se podría añadir sin pasar la nominal (negativa si ya se pasó).
+    pub margin_to_rated_pa: f64,
+    /// Veredicto.
+    pub level: SafetyLevel,
+    /// Avisos para el usuario, uno por línea.
+    pub warnings: Vec<String>,
+}
+
+impl SafetyReport {
+    /// Si la política deja lanzar con este margen.
+    pub fn is_acceptable(&self, policy: &SafetyPolicy) -> bool {
+        match self.level {
+            SafetyLevel::Ok | SafetyLevel::AboveRated => true,
+            SafetyLevel::InsufficientMargin => policy.mode == SafetyMode::Warn,
+            SafetyLevel::Burst => false,
+        }
+    }
+}
+
+/// Compara la presión de lanzamiento de `config` con los límites de sus botellas.
+/// En un cúmulo manda la botella con menos margen.
+pub fn assess_launch(config: &SimulationConfig, policy: &SafetyPolicy) -> SafetyReport {
+    std::iter::once(&config.bottle)
+        .chain(config.cluster.iter().map(|c| &c.bottle))
+        .map(|bottle| assess_bottle(bottle, config, policy))
+        .min_by(|a, b| a.actual_safety_factor.total_cmp(&b.actual_safety_factor))
+        .expect("siempre hay una botella principal")
+}
+
+fn assess_bottle(
+    bottle: &BottleSpec,
+    config: &SimulationConfig,
+    policy: &SafetyPolicy,
+) -> SafetyReport {
+    let launch_pressure_pa = config.initial_air_pressure_psi * PSI_TO_PA;
+    let strength = bottle.strength_factor(config.launch_temperature_c);
+    let rated_pressure_pa = bottle.rated_pressure_pa * strength;
+    let burst_pressure_pa = bottle.burst_pressure_pa * strength;
+
+    let actual_safety_factor = if launch_pressure_pa > 0.0 {
+        burst_pressure_pa / launch_pressure_pa
+    } else {
+        f64::INFINITY
+    };
+
+    let mut warnings = Vec::new();
+    if strength < 1.0 {
+        warnings.push(format!(
+            "{:.1} °C supera la temperatura nominal de {:.1} °C: resistencia al {:.0} %",
+            config.launch_temperature_c,
+            bottle.rated_temperature_c,
+            strength * 100.0
+        ));
+    }
+
+    let level = if launch_pressure_pa >= burst_pressure_pa {
+        warnings.push(format!(
+            "presión de lanzamiento {:.1} psi >= reventón {:.1} psi",
+            launch_pressure_pa / PSI_TO_PA,
+            burst_pressure_pa / PSI_TO_PA
+        ));
+        SafetyLevel::Burst
+    } else if actual_safety_factor < policy.safety_factor {
+        warnings.push(format!(
+            "factor de seguridad {:.2} menor que el requerido {:.2}",
+            actual_safety_factor, policy.safety_factor
+        ));
+        SafetyLevel::InsufficientMargin
+    } else if launch_pressure_pa > rated_pressure_pa {
+        warnings.push(format!(
+            "presión de lanzamiento {:.1} psi por encima de la nominal {:.1} psi",
+            launch_pressure_pa / PSI_TO_PA,
+            rated_pressure_pa / PSI_TO_PA
+        ));
+        SafetyLevel::AboveRated
+    } else {
+        SafetyLevel::Ok
+    };
+
+    SafetyReport {
+        bottle_name: bottle.name.clone(),
+        launch_pressure_pa,
+        launch_temperature_c: config.launch_temperature_c,
+        rated_pressure_pa,
+        burst_pressure_pa,
+        required_safety_factor: policy.safety_factor,
+        actual_safety_factor,
+        margin_to_rated_pa: rated_pressure_pa - launch_pressure_pa,
+        level,
+        warnings,
+    }
+}
+
+impl fmt::Display for SafetyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Botella: {} ({:?})", self.bottle_name, self.level)?;
+        writeln!(
+            f,
+            "  lanzamiento: {:.1} psi a {:.1} °C",
+            self.launch_pressure_pa / PSI_TO_PA,
+            self.launch_temperature_c
+        )?;
+        writeln!(
+            f,
+            "  nominal: {:.1} psi (margen {:.1} psi), reventón: {:.1} psi",
+            self.rated_pressure_pa / PSI_TO_PA,
+            self.margin_to_rated_pa / PSI_TO_PA,
+            self.burst_pressure_pa / PSI_TO_PA
+        )?;
+        write!(
+            f,
+            "  factor de seguridad: {:.2} (requerido {:.2})",
+            self.actual_safety_factor, self.required_safety_factor
+        )?;
+        for w in &self.warnings {
+            write!(f, "\n  aviso: {}", w)?;
+        }
+        Ok(())
+    }
+}
+
+/// Las presiones del barrido (hasta 60 psi) caben en una 2 L; 150 psi no.
+#[test]
+fn launch_pressure_levels() {
+    let policy = SafetyPolicy::default();
+    let mut config = SimulationConfig { initial_air_pressure_psi: 60.0, ..Default::default() };
+    let report = assess_launch(&config, &policy);
+    assert_eq!(report.level, SafetyLevel::Ok);
+    assert!(report.margin_to_rated_pa > 0.0);
+
+    config.initial_air_pressure_psi = 120.0;
+    let report = assess_launch(&config, &policy);
+    assert_eq!(report.level, SafetyLevel::InsufficientMargin);
+    assert!(!report.is_acceptable(&policy));
+    let warn = SafetyPolicy { mode: SafetyMode::Warn, ..policy };
+    assert!(report.is_acceptable(&warn));
+
+    config.initial_air_pressure_psi = 150.0;
+    assert_eq!(assess_launch(&config, &warn).level, SafetyLevel::Burst);
+
+    // en un día caluroso la misma presión deja menos margen
+    config.initial_air_pressure_psi = 60.0;
+    config.launch_temperature_c = 45.0;
+    assert!(assess_launch(&config, &policy).actual_safety_factor < 2.0);
+}
+
+/// La revisión va dentro de la construcción: ninguna entrada la salta. `Warn` deja volar
+/// sin margen, pero nunca por encima del reventón.
+#[test]
+fn every_entry_point_checks_the_bottle() {
+    use crate::{
+        convergence::ConvergenceStudy,
+        error::SimulationError,
+        multistage::{MultiStageSimulation, StageConfig, StagingTrigger},
+        simulation::Simulation,
+        six_dof::{SixDofConfig, SixDofSimulation},
+        sweep::Sweep,
+    };
+
+    let unsafe_error = |result: Result<(), SimulationError>| {
+        matches!(result, Err(SimulationError::Unsafe(report)) if report.level == SafetyLevel::Burst)
+    };
+    let base = SimulationConfig::default();
+    let burst = SimulationConfig { initial_air_pressure_psi: 250.0, ..base.clone() };
+    assert!(unsafe_error(Simulation::with_config(1e-3, burst.clone()).map(drop)));
+    assert!(unsafe_error(Sweep::over_pressure(&base, &[60.0, 250.0], 1e-3).map(drop)));
+    assert!(unsafe_error(ConvergenceStudy::run(&burst, 1e-3, 3, 1e-3).map(drop)));
+    let six_dof = SixDofSimulation::new(1e-3, burst.clone(), SixDofConfig::default());
+    assert!(unsafe_error(six_dof.map(drop)));
+    let stage = |psi: f64| StageConfig {
+        name: format!("{} psi", psi),
+        bottle: base.bottle.clone(),
+        nozzle: base.nozzle.clone(),
+        water_mass: 0.5,
+        dry_mass: 0.2,
+        initial_air_pressure_psi: psi,
+        cluster: Vec::new(),
+        coupling: base.coupling,
+        trigger: StagingTrigger::Burnout,
+    };
+    let stages = vec![stage(60.0), stage(250.0)];
+    assert!(unsafe_error(MultiStageSimulation::new(1e-3, base.clone(), stages).map(drop)));
+
+    let warn = SafetyPolicy { mode: SafetyMode::Warn, ..SafetyPolicy::default() };
+    let tight = SimulationConfig { initial_air_pressure_psi: 120.0, safety: warn, ..base };
+    let sim = Simulation::with_config(1e-3, tight.clone()).unwrap();
+    assert_eq!(sim.safety_report().level, SafetyLevel::InsufficientMargin);
+    assert!(!sim.safety_report().warnings.is_empty());
+    let refused = SimulationConfig { safety: SafetyPolicy::default(), ..tight.clone() };
+    assert!(Simulation::with_config(1e-3, refused).is_err());
+    let burst = SimulationConfig { initial_air_pressure_psi: 250.0, ..tight };
+    assert!(unsafe_error(Simulation::with_config(1e-3, burst).map(drop)));
+}
@@ -1,212 +1,723 @@
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::{
-    constants::{
-        ADIABATIC_INDEX_AIR,
-        ATMOSFERIC_PRESSURE,
-        BOTTLE_VOLUME,
-        DISCHARGE_COEFFICIENT,
-        DRY_MASS,
-        GRAVITY,
-        INITIAL_ACCELERATION,
-        INITIAL_AIR_PRESSURE_PSI, // asumida manométrica (gauge)
-        INITIAL_POSITION,
-        INITIAL_VELOCITY,
-        INITIAL_WATER_MASS,
-        LAUNCHING_ANGLE_DEG,
-        NOZZLE_DIAMETER,
-        WATER_DENSITY,
+    cluster::{BottleState, Coupling, NozzleFlow, PressureVessel},
+    columnar::{ColumnData, ColumnarTable},
+    config::SimulationConfig,
+    constants::{MAX_TIME_STEP, PSI_TO_PA},
+    csv_export::{CsvColumn, CsvOptions},
+    energy::{self, EnergyBalance, MomentumBalance},
+    error::{check, SimulationError},
+    events::{FlightEvent, FlightEventKind, FlightPeaks, FlightSummary},
+    json_export::{self, Json, NdjsonStreamLogger},
+    geodesy::{GeodeticPoint, LaunchSite},
+    logging::{
+        self, BottleSample, CsvStreamLogger, LogRecord, LogScheduler, Logger, MemoryLogger,
     },
-    math_utils::vector_2d::Vector2D,
-    physics::gas_dynamics::gas_dynamics,
+    map_export::{self, GeoTrack},
+    observer::Observer,
+    math_utils::{vector_2d::Vector2D, vector_3d::Vector3D},
+    plotting::{self, PlotFormat},
+    report::RunReport,
+    physics::{
+        gas_dynamics::gas_dynamics,
+        geometry::{DesignReport, RocketGeometry},
+        nozzle::Nozzle,
+        stability::{self, StabilityModel},
+    },
+    safety::{self, SafetyReport},
+    state::SimulationState,
 };
 
+// un paso mayor se come la fase de propulsión (décimas de segundo) en pocos pasos
+fn check_step(step: f64) -> Result<(), SimulationError> {
+    let expected = if !step.is_finite() || step <= 0.0 {
+        "finito y > 0"
+    } else if step > MAX_TIME_STEP {
+        "<= 0.01 s (MAX_TIME_STEP)"
+    } else {
+        return Ok(());
+    };
+    Err(SimulationError::InvalidStep { step, expected })
+}
+
+// la política de `config.safety` decide si las botellas aguantan la presión de lanzamiento
+pub(crate) fn check_safety(config: &SimulationConfig) -> Result<SafetyReport, SimulationError> {
+    let report = safety::assess_launch(config, &config.safety);
+    if report.is_acceptable(&config.safety) {
+        Ok(report)
+    } else {
+        Err(SimulationError::Unsafe(report))
+    }
+}
+
+// energía mecánica admitida, en veces la disponible al lanzar; el margen cubre lo que
+// el viento pueda aportar a través de las aletas (una integración inestable crece sin tope)
+const ENERGY_GROWTH_LIMIT: f64 = 10.0;
+
+/// Vuelo plano (x horizontal, y vertical) de un cohete de agua, integrado paso a paso
+/// desde un `SimulationConfig`: propulsión, actitud, eventos, logs y balances.
 pub struct Simulation {
     // --- tiempo ---
-    pub(crate) step: f64, // paso de simulación [s]
-    pub(crate) time: f64, // tiempo actual [s]
+    step: f64, // paso de simulación [s]
+    time: f64, // tiempo actual [s]
 
     // --- parámetros físicos ---
-    pub(crate) gravity: f64,              // [m/s²]
-    pub(crate) water_density: f64,        // [kg/m³]
-    pub(crate) atmospheric_pressure: f64, // [Pa]
-    pub(crate) adiabatic_index: f64,      // índice adiabático
+    gravity: f64,              // [m/s²]
+    water_density: f64,        // [kg/m³]
+    water_viscosity: f64,      // [Pa·s]
+    atmospheric_pressure: f64, // [Pa]
+    adiabatic_index: f64,      // índice adiabático
 
     // --- estado de traslación ---
-    pub(crate) position: Vector2D,     // [m]
-    pub(crate) velocity: Vector2D,     // [m/s]
-    pub(crate) acceleration: Vector2D, // [m/s²]
+    position: Vector2D,     // [m]
+    velocity: Vector2D,     // [m/s]
+    acceleration: Vector2D, // [m/s²]
+    launch_position: Vector2D, // inicio de la rampa [m]
+    launch_site: Option<LaunchSite>, // origen geodésico y acimut de +x
 
-    // --- estado de masa/volúmenes/presión ---
-    pub(crate) water_mass: f64, // masa de agua actual [kg]
-    pub(crate) dry_mass: f64,   // masa en seco [kg]
-    pub(crate) total_mass: f64, // masa total actual [kg]
+    // --- estado de rotación (cabeceo) ---
+    stability: Option<StabilityModel>, // None => actitud fija
+    geometry: Option<RocketGeometry>,  // CG/CP/inercia a partir del diseño
+    pitch: f64,              // ángulo del eje del cohete sobre la horizontal [rad]
+    pitch_rate: f64,         // [rad/s]
+    angle_of_attack: f64,    // [rad]
+    aero_normal_force: f64,  // fuerza normal al eje [N]
 
-    pub(crate) bottle_volume: f64,       // volumen interno botella [m³]
-    pub(crate) nozzle_area: f64,         // área tobera [m²]
-    pub(crate) launching_angle_rad: f64, // ángulo lanzamiento [rad]
+    // --- depósitos de aire y botellas (una sola botella = un depósito) ---
+    vessels: Vec<PressureVessel>,
+    nozzle_flows: Vec<NozzleFlow>, // chorros del paso actual
+
+    // --- estado de masa/volúmenes/presión (sumado sobre todas las botellas) ---
+    water_mass: f64, // masa de agua actual [kg]
+    dry_mass: f64,   // masa en seco [kg]
+    total_mass: f64, // masa total actual [kg]
+
+    bottle_volume: f64,       // volumen interno botellas actual [m³]
+    launching_angle_rad: f64, // ángulo lanzamiento [rad]
 
     // presión inicial ABSOLUTA (gauge convertida a Pa + atm)
-    #[allow(dead_code)]
-    pub(crate) initial_air_pressure_pa: f64,
+    initial_air_pressure_pa: f64,
 
     // NUEVO: estado termodinámico dinámico
-    pub(crate) polytropic_constant: f64, // K = P * V^gamma
-    pub(crate) current_pressure_pa: f64, // presión interna absoluta actual [Pa]
-    pub(crate) current_air_volume: f64,  // volumen de aire actual [m³]
+    current_pressure_pa: f64, // presión absoluta del depósito más cargado [Pa]
+    current_air_volume: f64,  // volumen de aire actual [m³]
 
     // para compatibilidad si quieres leerlo externamente
-    pub(crate) water_volume: f64, // volumen de agua (se actualiza opcionalmente)
-    pub(crate) air_volume: f64,   // volumen de aire (se espelha de current_air_volume)
-
-    // coeficiente de descarga (0..1)
-    pub(crate) discharge_coefficient: f64,
+    water_volume: f64, // volumen de agua (se actualiza opcionalmente)
+    air_volume: f64,   // volumen de aire (se espelha de current_air_volume)
 
     // --- fuerzas ---
-    pub(crate) thrust_force: f64, // empuje instantáneo [N]
+    thrust_force: f64, // empuje instantáneo [N]
 
     // --- logs ---
-    pub(crate) x_log: Vec<f64>,
-    pub(crate) y_log: Vec<f64>,
-    pub(crate) x_velocity_log: Vec<f64>,
-    pub(crate) y_velocity_log: Vec<f64>,
-    pub(crate) velocity_magnitur_log: Vec<f64>,
-    pub(crate) x_acceleration_log: Vec<f64>,
-    pub(crate) y_acceleration_log: Vec<f64>,
-    pub(crate) acceleration_magnitur_log: Vec<f64>,
-    pub(crate) time_log: Vec<f64>,
-    pub(crate) water_mass_log: Vec<f64>,
-    pub(crate) mass_log: Vec<f64>,
-    pub(crate) thrust_log: Vec<f64>,
-    pub(crate) pressure_log: Vec<f64>, // ahora guarda presión ACTUAL absoluta
+    initial_water_mass: f64, // carga de agua al lanzar [kg]
+    bottle_count: usize,
+    logger: Box<dyn Logger>,
+    log_error: Option<std::io::Error>, // primer fallo del logger
+    log_scheduler: LogScheduler,
+    previous_log: Option<LogRecord>, // estado del paso anterior, para interpolar
+    last_logged_time: Option<f64>,
+
+    // --- eventos y resumen ---
+    config: SimulationConfig, // configuración de entrada, tal cual
+    events: Vec<FlightEvent>,
+    peaks: FlightPeaks,
+    observers: Vec<Box<dyn Observer>>,
+    energy: EnergyBalance,
+    momentum: MomentumBalance,
+    safety_report: SafetyReport,
 }
 
 impl Simulation {
     // =========================
     // construcción
     // =========================
-    pub fn new(time_steps: f64) -> Self {
-        let launching_angle_rad: f64 = LAUNCHING_ANGLE_DEG.to_radians();
-        let nozzle_area: f64 = std::f64::consts::PI * (NOZZLE_DIAMETER / 2.0).powi(2);
+    /// Configuración por defecto con paso `time_steps` [s].
+    pub fn new(time_steps: f64) -> Result<Self, SimulationError> {
+        Self::with_config(time_steps, SimulationConfig::default())
+    }
 
-        // presión inicial ABSOLUTA = (psi gauge -> Pa) + atm
-        let initial_air_pressure_pa_abs: f64 =
-            INITIAL_AIR_PRESSURE_PSI * 6894.757293168361 + ATMOSFERIC_PRESSURE;
+    /// Valida paso y configuración (`SimulationConfig::validate`) antes de construir, y
+    /// revisa los límites de las botellas con `config.safety`: si la política rechaza la
+    /// presión de lanzamiento devuelve `SimulationError::Unsafe` con el informe.
+    pub fn with_config(time_steps: f64, config: SimulationConfig) -> Result<Self, SimulationError> {
+        check_step(time_steps)?;
+        config.validate()?;
+        let safety_report = check_safety(&config)?;
+        let mut sim = Self::build(time_steps, config, safety_report);
+        sim.record_event(FlightEventKind::Launch);
+        sim.push_logs(); // primer muestreo
+        Ok(sim)
+    }
+
+    /// Sigue desde `state` con `config`: la de la corrida original para reanudarla u otra
+    /// para ramificar (las constantes físicas y la actitud salen de `config`, el estado
+    /// dinámico de `state`). Las botellas de `config` deben coincidir con las del estado.
+    pub fn restore(
+        config: SimulationConfig,
+        state: &SimulationState,
+    ) -> Result<Self, SimulationError> {
+        check_step(state.step)?;
+        config.validate()?;
+        let safety_report = check_safety(&config)?;
+        let mut sim = Self::build(state.step, config, safety_report);
+        if !sim.load_state(state) {
+            return Err(SimulationError::IncompatibleState);
+        }
+        sim.log_scheduler =
+            LogScheduler::resume(sim.config.logging, state.steps_since_log, state.last_log_time);
+        // el log de la rama empieza en el estado restaurado
+        let current = sim.log_record();
+        sim.write_log(&current);
+        sim.previous_log = Some(current);
+        Ok(sim)
+    }
+
+    // estado dinámico de `state` (sin la cuenta de los logs); false si las botellas no encajan
+    fn load_state(&mut self, state: &SimulationState) -> bool {
+        let fits = self.vessels.len() == state.vessels.len()
+            && self.vessels.iter_mut().zip(&state.vessels).all(|(v, s)| v.restore(s));
+        if !fits {
+            return false;
+        }
+        self.time = state.time;
+        self.position = state.position;
+        self.velocity = state.velocity;
+        self.acceleration = state.acceleration;
+        self.launch_position = state.launch_position;
+        self.pitch = state.pitch;
+        self.pitch_rate = state.pitch_rate;
+        self.angle_of_attack = state.angle_of_attack;
+        self.aero_normal_force = state.normal_force;
+        self.thrust_force = state.thrust;
+        self.events = state.events.clone();
+        self.peaks = state.peaks;
+
+        self.update_vessel_totals();
+        self.set_dry_mass(state.dry_mass);
+        self.update_mass_properties();
+        self.energy = state.energy;
+        self.momentum = state.momentum;
+        true
+    }
 
-        // volúmenes iniciales
-        let water_volume: f64 = INITIAL_WATER_MASS / WATER_DENSITY;
-        let air_volume: f64 = (BOTTLE_VOLUME - water_volume).max(1e-12);
+    /// Vuelve a un `snapshot` de esta misma simulación tomado antes de `advance`, para
+    /// deshacer un paso que no se llegó a registrar.
+    pub(crate) fn rewind(&mut self, state: &SimulationState) {
+        let fits = self.load_state(state);
+        debug_assert!(fits, "snapshot de otra simulación");
+    }
+
+    // todo menos el evento de lanzamiento y el primer registro
+    fn build(time_steps: f64, config: SimulationConfig, safety_report: SafetyReport) -> Self {
+        let stored_config = config.clone();
+        let launching_angle_rad: f64 = config.launching_angle_deg.to_radians();
 
-        // constante politrópica K = P0 * Va0^gamma
-        let polytropic_constant: f64 =
-            initial_air_pressure_pa_abs * air_volume.powf(ADIABATIC_INDEX_AIR);
+        // presión inicial ABSOLUTA = (psi gauge -> Pa) + atm
+        let initial_gauge_pa: f64 = config.initial_air_pressure_psi * PSI_TO_PA;
+        let initial_air_pressure_pa_abs: f64 = initial_gauge_pa + config.atmospheric_pressure;
 
-        let total_mass: f64 = INITIAL_WATER_MASS + DRY_MASS;
+        // botella principal + cúmulo, todas infladas a la presión de lanzamiento
+        let mut bottles = vec![BottleState::new(
+            &config.bottle,
+            &config.nozzle,
+            config.initial_water_mass,
+        )];
+        bottles.extend(
+            config
+                .cluster
+                .iter()
+                .map(|c| BottleState::new(&c.bottle, &c.nozzle, c.water_mass)),
+        );
+        let bottle_count = bottles.len();
+        let vessel = |bottles: Vec<BottleState>, series: bool| {
+            PressureVessel::new(
+                bottles,
+                series,
+                initial_air_pressure_pa_abs,
+                config.atmospheric_pressure,
+                config.adiabatic_index,
+                config.water_density,
+            )
+        };
+        let vessels: Vec<PressureVessel> = match config.coupling {
+            Coupling::Independent => bottles.into_iter().map(|b| vessel(vec![b], false)).collect(),
+            Coupling::Parallel => vec![vessel(bottles, false)],
+            Coupling::Series => vec![vessel(bottles, true)],
+        };
 
         let mut sim = Self {
             step: time_steps,
-            time: 0.0,
+            time: config.initial_time,
 
-            gravity: GRAVITY,
-            water_density: WATER_DENSITY,
-            atmospheric_pressure: ATMOSFERIC_PRESSURE,
-            adiabatic_index: ADIABATIC_INDEX_AIR,
+            gravity: config.gravity,
+            water_density: config.water_density,
+            water_viscosity: config.water_viscosity,
+            atmospheric_pressure: config.atmospheric_pressure,
+            adiabatic_index: config.adiabatic_index,
 
-            position: INITIAL_POSITION,
-            velocity: INITIAL_VELOCITY,
-            acceleration: INITIAL_ACCELERATION,
+            position: config.initial_position,
+            velocity: config.initial_velocity,
+            acceleration: config.initial_acceleration,
+            launch_position: config.initial_position,
+            launch_site: config.launch_site,
 
-            water_mass: INITIAL_WATER_MASS,
-            dry_mass: DRY_MASS,
-            total_mass,
+            stability: match &config.geometry {
+                Some(geometry) => Some(geometry.stability_model(
+                    config.initial_water_mass,
+                    config.water_density,
+                    &config.stability.unwrap_or_default(),
+                )),
+                None => config.stability,
+            },
+            geometry: config.geometry,
+            pitch: launching_angle_rad,
+            pitch_rate: 0.0,
+            angle_of_attack: 0.0,
+            aero_normal_force: 0.0,
 
-            bottle_volume: BOTTLE_VOLUME,
-            nozzle_area,
+            vessels,
+            nozzle_flows: Vec::new(),
+
+            water_mass: 0.0,
+            dry_mass: config.dry_mass,
+            total_mass: config.dry_mass,
+
+            bottle_volume: 0.0,
             launching_angle_rad,
 
             initial_air_pressure_pa: initial_air_pressure_pa_abs,
 
-            polytropic_constant,
             current_pressure_pa: initial_air_pressure_pa_abs,
-            current_air_volume: air_volume,
-
-            water_volume,
-            air_volume, // espejo del actual para mantener compatibilidad
+            current_air_volume: 0.0,
 
-            discharge_coefficient: DISCHARGE_COEFFICIENT, // realista; cambia si quieres
+            water_volume: 0.0,
+            air_volume: 0.0, // espejo del actual para mantener compatibilidad
 
             thrust_force: 0.0,
 
-            x_log: Vec::new(),
-            y_log: Vec::new(),
-            x_velocity_log: Vec::new(),
-            y_velocity_log: Vec::new(),
-            velocity_magnitur_log: Vec::new(),
-            x_acceleration_log: Vec::new(),
-            y_acceleration_log: Vec::new(),
-            acceleration_magnitur_log: Vec::new(),
-            time_log: Vec::new(),
-            water_mass_log: Vec::new(),
-            mass_log: Vec::new(),
-            thrust_log: Vec::new(),
-            pressure_log: Vec::new(),
+            initial_water_mass: config.initial_water_mass
+                + config.cluster.iter().map(|c| c.water_mass).sum::<f64>(),
+            bottle_count,
+            logger: Box::new(MemoryLogger::default()),
+            log_error: None,
+            log_scheduler: LogScheduler::new(config.logging, config.initial_time),
+            previous_log: None,
+            last_logged_time: None,
+
+            config: stored_config,
+            events: Vec::new(),
+            peaks: FlightPeaks::default(),
+            observers: Vec::new(),
+            energy: EnergyBalance::default(),
+            momentum: MomentumBalance::default(),
+            safety_report,
         };
 
-        sim.push_logs(); // primer muestreo
+        sim.update_vessel_totals();
+        sim.update_total_mass(sim.water_mass);
+        let gas = sim.vessels.iter().map(|v| sim.gas_energy(v)).sum();
+        let (kinetic, potential) = sim.rocket_energy();
+        sim.energy = EnergyBalance::at_launch(gas, kinetic, potential);
+        sim.momentum = MomentumBalance::at_launch(sim.velocity * sim.total_mass);
         sim
     }
 
+    // =========================
+    // lectura del estado
+    // =========================
+    /// Paso de integración [s].
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// Tiempo actual [s].
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Gravedad [m/s²].
+    pub fn gravity(&self) -> f64 {
+        self.gravity
+    }
+
+    /// Presión atmosférica [Pa].
+    pub fn atmospheric_pressure(&self) -> f64 {
+        self.atmospheric_pressure
+    }
+
+    /// Posición [m].
+    pub fn position(&self) -> Vector2D {
+        self.position
+    }
+
+    /// Velocidad [m/s].
+    pub fn velocity(&self) -> Vector2D {
+        self.velocity
+    }
+
+    /// Aceleración del último paso [m/s²].
+    pub fn acceleration(&self) -> Vector2D {
+        self.acceleration
+    }
+
+    /// Inicio de la rampa [m].
+    pub fn launch_position(&self) -> Vector2D {
+        self.launch_position
+    }
+
+    /// Lugar de lanzamiento, si se configuró.
+    pub fn launch_site(&self) -> Option<LaunchSite> {
+        self.launch_site
+    }
+
+    /// Ángulo de lanzamiento [rad].
+    pub fn launch_angle(&self) -> f64 {
+        self.launching_angle_rad
+    }
+
+    /// Modelo de actitud, con CG e inercia al día si hay geometría.
+    pub fn stability(&self) -> Option<&StabilityModel> {
+        self.stability.as_ref()
+    }
+
+    /// Geometría del cohete, si se configuró.
+    pub fn geometry(&self) -> Option<&RocketGeometry> {
+        self.geometry.as_ref()
+    }
+
+    /// Ángulo del eje del cohete sobre la horizontal [rad].
+    pub fn pitch(&self) -> f64 {
+        self.pitch
+    }
+
+    /// Velocidad de cabeceo [rad/s].
+    pub fn pitch_rate(&self) -> f64 {
+        self.pitch_rate
+    }
+
+    /// Ángulo de ataque [rad].
+    pub fn angle_of_attack(&self) -> f64 {
+        self.angle_of_attack
+    }
+
+    /// Fuerza aerodinámica normal al eje [N].
+    pub fn normal_force(&self) -> f64 {
+        self.aero_normal_force
+    }
+
+    /// Depósitos a presión (uno por botella, o uno compartido si están conectadas).
+    pub fn vessels(&self) -> &[PressureVessel] {
+        &self.vessels
+    }
+
+    /// Chorros del paso actual.
+    pub fn nozzle_flows(&self) -> &[NozzleFlow] {
+        &self.nozzle_flows
+    }
+
+    /// Masa de agua actual [kg].
+    pub fn water_mass(&self) -> f64 {
+        self.water_mass
+    }
+
+    /// Carga de agua al lanzar [kg].
+    pub fn initial_water_mass(&self) -> f64 {
+        self.initial_water_mass
+    }
+
+    /// Masa en seco [kg].
+    pub fn dry_mass(&self) -> f64 {
+        self.dry_mass
+    }
+
+    /// Masa en seco más el agua [kg].
+    pub fn total_mass(&self) -> f64 {
+        self.total_mass
+    }
+
+    /// Volumen interno de las botellas [m³].
+    pub fn bottle_volume(&self) -> f64 {
+        self.bottle_volume
+    }
+
+    /// Volumen de agua [m³].
+    pub fn water_volume(&self) -> f64 {
+        self.water_volume
+    }
+
+    /// Volumen de aire [m³].
+    pub fn air_volume(&self) -> f64 {
+        self.air_volume
+    }
+
+    /// Presión absoluta inicial [Pa].
+    pub fn initial_pressure(&self) -> f64 {
+        self.initial_air_pressure_pa
+    }
+
+    /// Presión absoluta del depósito más cargado [Pa].
+    pub fn pressure(&self) -> f64 {
+        self.current_pressure_pa
+    }
+
+    /// Empuje instantáneo [N].
+    pub fn thrust(&self) -> f64 {
+        self.thrust_force
+    }
+
+    /// Configuración de entrada, tal cual.
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    /// Máximos del vuelo hasta ahora.
+    pub fn peaks(&self) -> FlightPeaks {
+        self.peaks
+    }
+
+    /// Estado dinámico actual, para seguir más tarde con `restore`.
+    pub fn snapshot(&self) -> SimulationState {
+        SimulationState {
+            step: self.step,
+            time: self.time,
+            position: self.position,
+            velocity: self.velocity,
+            acceleration: self.acceleration,
+            launch_position: self.launch_position,
+            pitch: self.pitch,
+            pitch_rate: self.pitch_rate,
+            angle_of_attack: self.angle_of_attack,
+            normal_force: self.aero_normal_force,
+            thrust: self.thrust_force,
+            dry_mass: self.dry_mass,
+            vessels: self.vessels.iter().map(PressureVessel::state).collect(),
+            events: self.events.clone(),
+            peaks: self.peaks,
+            energy: self.energy,
+            momentum: self.momentum,
+            steps_since_log: self.log_scheduler.steps_since_log(),
+            last_log_time: self.log_scheduler.last_log_time(),
+        }
+    }
+
+    /// Abre todos los depósitos a la atmósfera (etapa soltada): sin empuje desde ya; el agua
+    /// que quede cae con la etapa. La energía del aire liberado va a la atmósfera.
+    pub(crate) fn vent(&mut self) {
+        let before: f64 = self.vessels.iter().map(|v| self.gas_energy(v)).sum();
+        for vessel in &mut self.vessels {
+            vessel.vent(self.atmospheric_pressure, self.adiabatic_index, self.water_density);
+        }
+        self.update_vessel_totals();
+        self.nozzle_flows.clear();
+        self.thrust_force = 0.0;
+
+        let after: f64 = self.vessels.iter().map(|v| self.gas_energy(v)).sum();
+        self.energy.atmosphere_work += before - after;
+        self.energy.gas = after;
+    }
+
+    /// Cambia la masa en seco (p. ej. al soltar una etapa) y recalcula la total.
+    pub(crate) fn set_dry_mass(&mut self, dry_mass: f64) {
+        self.dry_mass = dry_mass;
+        self.update_total_mass(self.water_mass);
+    }
+
     // =========================
     // bucle principal
     // =========================
-    pub fn run(&mut self, t_max: f64) {
+    /// Integra hasta `t_max` o hasta que `is_finished`. Si un paso deja el estado enfermo
+    /// (ver `step_once`) para con `UnhealthyState`; en los logs queda lo registrado hasta
+    /// el último paso sano.
+    pub fn run(&mut self, t_max: f64) -> Result<(), SimulationError> {
+        check("t_max", t_max, true, "finito")?;
+        let mut result = Ok(());
         while self.time < t_max {
-            self.step_once();
-
-            // cortar si toca suelo tras un instante
-            if self.time > 0.02 && self.position.y < 0.0 {
+            result = self.step_once();
+            if result.is_err() || self.is_finished() {
                 break;
             }
         }
+        if result.is_ok() {
+            self.flush_logs();
+        }
+        result
     }
 
-    pub fn step_once(&mut self) {
-        self.update_current_air_volume();
-        self.update_current_internal_pressure();
+    /// Número de pasos desde `initial_time` (también tras restaurar).
+    pub fn iteration(&self) -> usize {
+        ((self.time - self.config.initial_time) / self.step).round() as usize
+    }
 
-        let delta_p = self.current_pressure_pa - self.atmospheric_pressure;
-        if self.water_mass <= 0.0 || delta_p <= 0.0 {
-            // sin empuje
-            self.thrust_force = 0.0;
-            self.update_total_mass(self.water_mass);
-            self.update_current_acceleration();
-            self.update_current_velocity();
-            self.update_current_position();
-            self.update_time();
-            self.push_logs();
-            return;
+    // energía interna del aire de un depósito [J]
+    fn gas_energy(&self, vessel: &PressureVessel) -> f64 {
+        energy::gas_energy(vessel.pressure_pa(), vessel.air_volume(), self.adiabatic_index)
+    }
+
+    // (cinética, potencial sobre el inicio de la rampa) del cohete [J]
+    fn rocket_energy(&self) -> (f64, f64) {
+        let height = self.position.y - self.launch_position.y;
+        let kinetic = 0.5 * self.total_mass * self.velocity.length().powi(2);
+        (kinetic, self.total_mass * self.gravity * height)
+    }
+
+    /// Tras un paso: estado finito, masas no negativas, presión absoluta no negativa y
+    /// energía mecánica acotada por la disponible al lanzar. Si no, `UnhealthyState`
+    /// con el paso, el instante y la primera magnitud que falla.
+    pub(crate) fn check_health(&self) -> Result<(), SimulationError> {
+        self.check_finite(&[
+            ("position.x", self.position.x),
+            ("position.y", self.position.y),
+            ("velocity.x", self.velocity.x),
+            ("velocity.y", self.velocity.y),
+            ("acceleration.x", self.acceleration.x),
+            ("acceleration.y", self.acceleration.y),
+            ("pitch", self.pitch),
+            ("pitch_rate", self.pitch_rate),
+        ])?;
+        self.check_propulsion_health()?;
+        let (kinetic, potential) = self.rocket_energy();
+        self.check_energy(kinetic + potential)
+    }
+
+    // lo que no depende de la cinemática (también para el 6-DOF): tiempo, masas y aire
+    pub(crate) fn check_propulsion_health(&self) -> Result<(), SimulationError> {
+        let mut finite = vec![
+            ("time", self.time),
+            ("water_mass", self.water_mass),
+            ("total_mass", self.total_mass),
+            ("thrust", self.thrust_force),
+        ];
+        // la presión total es un máximo, que se salta los NaN: cada depósito por separado
+        for vessel in &self.vessels {
+            finite.push(("vessel.pressure", vessel.pressure_pa()));
+            finite.push(("vessel.air_volume", vessel.air_volume()));
         }
+        self.check_finite(&finite)?;
 
-        let exit_velocity = self.compute_exit_velocity();
-        let mass_flow = self.compute_mass_flow(exit_velocity);
+        let mut masses = vec![("dry_mass", self.dry_mass)];
+        masses.extend(self.vessels.iter().map(|v| ("vessel.water_mass", v.water_mass())));
+        if let Some(&(quantity, value)) = masses.iter().find(|&&(_, m)| m < 0.0) {
+            return Err(self.unhealthy(quantity, value, "es negativa"));
+        }
+        if let Some(vessel) = self.vessels.iter().find(|v| v.pressure_pa() < 0.0) {
+            let value = vessel.pressure_pa();
+            return Err(self.unhealthy("vessel.pressure", value, "está por debajo del vacío"));
+        }
+        Ok(())
+    }
 
-        // Si por algún motivo no hay flujo, no “inventes” empuje
-        if mass_flow <= 0.0 || exit_velocity <= 0.0 {
-            self.thrust_force = 0.0;
-        } else {
-            self.update_current_thrust(exit_velocity, mass_flow);
+    // la primera magnitud no finita, si la hay
+    pub(crate) fn check_finite(
+        &self,
+        quantities: &[(&'static str, f64)],
+    ) -> Result<(), SimulationError> {
+        match quantities.iter().find(|(_, v)| !v.is_finite()) {
+            Some(&(quantity, value)) => Err(self.unhealthy(quantity, value, "no es finito")),
+            None => Ok(()),
         }
+    }
 
-        self.update_total_mass(self.water_mass);
+    // energía mecánica del cohete (cinética + potencial) frente a la disponible al lanzar
+    pub(crate) fn check_energy(&self, mechanical: f64) -> Result<(), SimulationError> {
+        if mechanical > ENERGY_GROWTH_LIMIT * self.energy.initial {
+            let problem = "supera con mucho la energía disponible (integración inestable)";
+            return Err(self.unhealthy("mechanical_energy", mechanical, problem));
+        }
+        Ok(())
+    }
+
+    fn unhealthy(
+        &self,
+        quantity: &'static str,
+        value: f64,
+        problem: &'static str,
+    ) -> SimulationError {
+        SimulationError::UnhealthyState {
+            iteration: self.iteration(),
+            time: self.time,
+            quantity,
+            value,
+            problem,
+        }
+    }
+
+    /// Ha bajado de y = 0 pasado un instante desde el lanzamiento.
+    pub fn has_landed(&self) -> bool {
+        self.time > 0.02 && self.position.y < 0.0
+    }
+
+    /// Se cumple alguna condición de `config.termination` o algún observador pide parar.
+    pub fn is_finished(&self) -> bool {
+        self.config.termination.iter().any(|t| t.is_met(self))
+            || self.observers.iter().any(|o| o.should_stop(self))
+    }
+
+    /// Registra un observador; se le llama al final de cada `step_once`.
+    pub fn add_observer(&mut self, observer: Box<dyn Observer>) {
+        self.observers.push(observer);
+    }
+
+    // eventos nuevos desde `first_new_event` y luego el paso, a cada observador
+    fn notify_observers(&mut self, first_new_event: usize) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let mut observers = std::mem::take(&mut self.observers);
+        for observer in &mut observers {
+            for event in &self.events[first_new_event..] {
+                observer.on_event(self, event);
+            }
+            observer.on_step(self);
+        }
+        self.observers = observers;
+    }
+
+    /// Avanza un paso. Si el estado queda enfermo (ver `check_health`) devuelve el
+    /// diagnóstico sin registrar ese paso, ni en los logs ni ante los observadores.
+    pub fn step_once(&mut self) -> Result<(), SimulationError> {
+        let first_new_event = self.advance()?;
+        self.commit_step(first_new_event);
+        Ok(())
+    }
+
+    /// Integra un paso y comprueba la salud, sin eventos, logs ni observadores (eso lo
+    /// hace `commit_step`). Devuelve el índice del primer evento nuevo para `commit_step`.
+    pub(crate) fn advance(&mut self) -> Result<usize, SimulationError> {
+        let first_new_event = self.events.len();
+        let air_volume = self.current_air_volume;
+        self.update_current_air_volume();
+        self.update_current_internal_pressure();
+        self.update_gas_energy(air_volume);
+
+        // chorro de cada tobera con agua y sobrepresión (si no hay, sin empuje)
+        self.update_nozzle_flows();
+
+        self.update_mass_properties();
+        self.update_current_aerodynamics();
         self.update_current_acceleration();
+        // actitud y masa con las que se ha acelerado, para los balances
+        let (pitch, mass) = (self.pitch, self.total_mass);
         self.update_current_velocity();
         self.update_current_position();
-        self.update_current_water_flow_with(mass_flow);
+        self.update_current_pitch();
+        self.update_current_water_flow();
+        self.update_balances(pitch, mass);
+        self.blow_out_empty_bottles();
 
         self.update_time();
+        self.check_health()?;
+        Ok(first_new_event)
+    }
+
+    /// Da por bueno el paso de `advance`: eventos, logs y observadores.
+    pub(crate) fn commit_step(&mut self, first_new_event: usize) {
+        self.update_flight_events();
         self.push_logs();
+        self.notify_observers(first_new_event);
     }
 
     // =========================
@@ -214,132 +725,648 @@ impl Simulation {
     // =========================
 
     // masa total = seco + agua (con argumento por compatibilidad)
-    pub fn update_total_mass(&mut self, new_water_mass: f64) {
+    pub(crate) fn update_total_mass(&mut self, new_water_mass: f64) {
         self.total_mass = self.dry_mass + new_water_mass;
     }
 
-    // volumen de aire actual = volumen botella - volumen de agua
-    pub fn update_current_air_volume(&mut self) {
-        self.current_air_volume =
-            (self.bottle_volume - (self.water_mass / self.water_density)).max(1e-12);
+    // volumen de aire de cada depósito = volumen botellas(P) - volumen de agua
+    pub(crate) fn update_current_air_volume(&mut self) {
+        for vessel in &mut self.vessels {
+            vessel.update_air_volume(
+                self.water_density,
+                self.atmospheric_pressure,
+                self.adiabatic_index,
+            );
+        }
+        self.update_vessel_totals();
+    }
+
+    // presión actual de cada depósito por politropía: P = K / Va^gamma
+    pub(crate) fn update_current_internal_pressure(&mut self) {
+        for vessel in &mut self.vessels {
+            vessel.update_pressure(self.adiabatic_index);
+        }
+        self.update_vessel_totals();
+    }
+
+    // campos escalares = suma (o máximo, para la presión) sobre los depósitos
+    fn update_vessel_totals(&mut self) {
+        self.water_mass = self.vessels.iter().map(PressureVessel::water_mass).sum();
+        self.bottle_volume = self.vessels.iter().map(PressureVessel::bottle_volume).sum();
+        self.current_air_volume = self.vessels.iter().map(PressureVessel::air_volume).sum();
+        self.current_pressure_pa = self
+            .vessels
+            .iter()
+            .map(PressureVessel::pressure_pa)
+            .fold(f64::NEG_INFINITY, f64::max);
         // mantener campos espejo si los usas en otro lado
         self.water_volume = self.water_mass / self.water_density;
         self.air_volume = self.current_air_volume;
     }
 
-    // presión actual por politropía: P = K / Va^gamma
-    pub fn update_current_internal_pressure(&mut self) {
-        self.current_pressure_pa =
-            self.polytropic_constant / self.current_air_volume.powf(self.adiabatic_index);
+    pub(crate) fn update_nozzle_flows(&mut self) {
+        self.nozzle_flows = self.compute_nozzle_flows();
     }
 
-    // velocidad de salida: con o sin coeficiente de descarga
-    pub fn compute_exit_velocity(&self) -> f64 {
-        if self.water_mass <= 0.0 {
+    // un chorro por cada tobera que esté descargando
+    pub(crate) fn compute_nozzle_flows(&self) -> Vec<NozzleFlow> {
+        let mut flows = Vec::new();
+        for (v, vessel) in self.vessels.iter().enumerate() {
+            for b in vessel.discharging_bottles() {
+                let nozzle = vessel.bottles()[b].nozzle();
+                let exit_velocity = self.compute_exit_velocity(vessel, nozzle);
+                let mass_flow = self.compute_mass_flow(nozzle, exit_velocity);
+
+                // Si por algún motivo no hay flujo, no “inventes” empuje
+                if mass_flow <= 0.0 || exit_velocity <= 0.0 {
+                    continue;
+                }
+                flows.push(NozzleFlow {
+                    vessel: v,
+                    bottle: b,
+                    exit_velocity,
+                    mass_flow,
+                    thrust: self.compute_thrust(vessel, nozzle, exit_velocity, mass_flow),
+                });
+            }
+        }
+        flows
+    }
+
+    // velocidad de salida: pérdidas de la contracción y C_d(Re) de la tobera
+    pub(crate) fn compute_exit_velocity(&self, vessel: &PressureVessel, nozzle: &Nozzle) -> f64 {
+        if vessel.water_mass() <= 0.0 {
             return 0.0;
         }
-        let dp = self.current_pressure_pa - self.atmospheric_pressure;
+        let dp = vessel.pressure_pa() - self.atmospheric_pressure;
         if dp <= 0.0 {
             return 0.0;
         }
 
-        if self.discharge_coefficient > 0.0 {
-            gas_dynamics::exit_velocity_with_cd(
-                self.discharge_coefficient,
-                self.current_pressure_pa,
-                self.atmospheric_pressure,
-                self.water_density,
-            )
-        } else {
-            gas_dynamics::exit_velocity(
-                self.current_pressure_pa,
-                self.atmospheric_pressure,
-                self.water_density,
-            )
-        }
+        // Re con la velocidad ideal del chorro; la pérdida K de la contracción vale también
+        // con `DischargeModel::Ideal` (C_d = 1)
+        let ideal_velocity = gas_dynamics::exit_velocity(
+            vessel.pressure_pa(),
+            self.atmospheric_pressure,
+            self.water_density,
+        );
+        let reynolds = nozzle.reynolds(ideal_velocity, self.water_density, self.water_viscosity);
+        gas_dynamics::exit_velocity_with_loss(
+            nozzle.discharge_coefficient(reynolds),
+            nozzle.contraction_loss,
+            vessel.pressure_pa(),
+            self.atmospheric_pressure,
+            self.water_density,
+        )
     }
 
-    // flujo másico = densidad * área * velocidad_salida
-    pub fn compute_mass_flow(&self, exit_velocity: f64) -> f64 {
-        if self.water_mass <= 0.0 || exit_velocity <= 0.0 {
+    // flujo másico = densidad * área de la tobera * velocidad_salida
+    pub(crate) fn compute_mass_flow(&self, nozzle: &Nozzle, exit_velocity: f64) -> f64 {
+        if exit_velocity <= 0.0 {
             return 0.0;
         }
-        gas_dynamics::mass_flow_rate(exit_velocity, self.nozzle_area, self.water_density)
+        gas_dynamics::mass_flow_rate(exit_velocity, nozzle.area(), self.water_density)
     }
 
     // empuje = ṁ * v + (P_interna - P_atm) * área
-    pub fn update_current_thrust(&mut self, exit_velocity: f64, mass_flow: f64) {
-        if self.water_mass <= 0.0 || exit_velocity <= 0.0 || mass_flow <= 0.0 {
-            self.thrust_force = 0.0;
-            return;
+    pub(crate) fn compute_thrust(
+        &self,
+        vessel: &PressureVessel,
+        nozzle: &Nozzle,
+        exit_velocity: f64,
+        mass_flow: f64,
+    ) -> f64 {
+        if exit_velocity <= 0.0 || mass_flow <= 0.0 {
+            return 0.0;
         }
-        self.thrust_force = gas_dynamics::thrust(
+        gas_dynamics::thrust(
             mass_flow,
             exit_velocity,
-            self.current_pressure_pa,
+            vessel.pressure_pa(),
             self.atmospheric_pressure,
-            self.nozzle_area,
-        );
+            nozzle.area(),
+        )
     }
 
-    // aceleración a partir de empuje y masa total (proyección por ángulo)
-    pub fn update_current_acceleration(&mut self) {
-        let ax = (self.thrust_force * self.launching_angle_rad.cos()) / self.total_mass;
-        let ay =
-            (self.thrust_force * self.launching_angle_rad.sin()) / self.total_mass - self.gravity;
+    // empuje y masa sumados sobre todas las botellas
+    pub(crate) fn update_thrust_and_mass(&mut self) {
+        self.thrust_force = self.nozzle_flows.iter().fold(0.0, |acc, f| acc + f.thrust);
+        self.update_total_mass(self.vessels.iter().map(PressureVessel::water_mass).sum());
+    }
+
+    // aceleración a partir del empuje y la masa de todas las botellas
+    // (empuje a lo largo del eje; fuerza normal perpendicular si hay modelo de actitud)
+    pub(crate) fn update_current_acceleration(&mut self) {
+        self.update_thrust_and_mass();
+
+        let (sin, cos) = self.pitch.sin_cos();
+        let mut ax = (self.thrust_force * cos) / self.total_mass;
+        let mut ay = (self.thrust_force * sin) / self.total_mass - self.gravity;
+        if self.aero_normal_force != 0.0 {
+            ax -= self.aero_normal_force * sin / self.total_mass;
+            ay += self.aero_normal_force * cos / self.total_mass;
+        }
         self.acceleration = Vector2D { x: ax, y: ay };
     }
 
+    /// Sigue en la rampa, donde no puede girar (siempre, sin modelo de actitud).
+    pub fn is_on_launch_rail(&self) -> bool {
+        match &self.stability {
+            Some(model) => {
+                (self.position - self.launch_position).magnitude() < model.launch_rail_length
+            }
+            None => true,
+        }
+    }
+
+    /// Velocidad respecto al aire; el viento sopla en +x [m/s].
+    pub fn relative_air_velocity(&self) -> Vector2D {
+        let wind = self.stability.as_ref().map_or(0.0, |m| m.wind_speed);
+        self.velocity - Vector2D::new(wind, 0.0)
+    }
+
+    // CG e inercia con el agua que queda (solo si hay geometría)
+    pub(crate) fn update_mass_properties(&mut self) {
+        if let (Some(geometry), Some(model)) = (&self.geometry, &mut self.stability) {
+            let (water, rho) = (self.water_mass, self.water_density);
+            model.center_of_gravity = geometry.center_of_gravity(water, rho);
+            model.pitch_inertia = geometry.pitch_inertia(water, rho);
+        }
+    }
+
+    /// Margen de las botellas al lanzar, revisado al construir con `config.safety`.
+    pub fn safety_report(&self) -> &SafetyReport {
+        &self.safety_report
+    }
+
+    /// Informe de CG/CP del diseño con la carga inicial de agua, si hay geometría.
+    pub fn design_report(&self) -> Option<DesignReport> {
+        self.geometry
+            .as_ref()
+            .map(|g| g.report(self.initial_water_mass, self.water_density))
+    }
+
+    // ángulo de ataque y fuerza normal con la velocidad relativa al aire
+    pub(crate) fn update_current_aerodynamics(&mut self) {
+        let Some(model) = &self.stability else {
+            return;
+        };
+        let air = self.relative_air_velocity();
+        let speed = air.magnitude();
+        if speed <= 0.0 {
+            self.angle_of_attack = 0.0;
+            self.aero_normal_force = 0.0;
+            return;
+        }
+        self.angle_of_attack = stability::wrap_angle(self.pitch - air.y.atan2(air.x));
+        self.aero_normal_force = if self.is_on_launch_rail() {
+            0.0 // la rampa absorbe la fuerza lateral
+        } else {
+            model.normal_force(speed, self.angle_of_attack)
+        };
+    }
+
+    // integra cabeceo con el momento restaurador (CP frente a CG) y el amortiguamiento
+    pub(crate) fn update_current_pitch(&mut self) {
+        let Some(model) = &self.stability else {
+            return;
+        };
+        if self.is_on_launch_rail() {
+            self.pitch_rate = 0.0;
+            return;
+        }
+        let speed = self.relative_air_velocity().magnitude();
+        let moment = model.pitching_moment(speed, self.angle_of_attack, self.pitch_rate);
+        self.pitch_rate += moment / model.pitch_inertia * self.step;
+        self.pitch = stability::wrap_angle(self.pitch + self.pitch_rate * self.step);
+    }
+
+    /// Margen estático en calibres, si hay modelo de actitud.
+    pub fn static_margin_calibers(&self) -> Option<f64> {
+        self.stability.as_ref().map(StabilityModel::static_margin_calibers)
+    }
+
     // integra velocidad (NO vuelve a calcular aceleración aquí)
-    pub fn update_current_velocity(&mut self) {
-        self.velocity = self.velocity + self.acceleration * self.step;
+    pub(crate) fn update_current_velocity(&mut self) {
+        self.velocity += self.acceleration * self.step;
     }
 
     // integra posición con v(t+dt)
-    pub fn update_current_position(&mut self) {
-        self.position = self.position + self.velocity * self.step;
+    pub(crate) fn update_current_position(&mut self) {
+        self.position += self.velocity * self.step;
     }
 
-    // restar agua con un flujo ya calculado en este paso
-    pub fn update_current_water_flow_with(&mut self, mass_flow: f64) {
-        let dm = mass_flow * self.step;
-        self.water_mass = (self.water_mass - dm).max(0.0);
+    // restar el agua de los chorros ya calculados en este paso
+    pub(crate) fn update_current_water_flow(&mut self) {
+        for flow in &self.nozzle_flows {
+            self.vessels[flow.vessel].drain(flow.bottle, flow.mass_flow * self.step);
+        }
+        self.update_vessel_totals();
         self.update_total_mass(self.water_mass);
         if self.water_mass <= 0.0 {
             self.thrust_force = 0.0;
         }
     }
 
-    // si quieres mantener la vieja función, hazla delegar:
-    #[allow(dead_code)]
-    pub fn update_current_water_flow(&mut self) {
-        let v = self.compute_exit_velocity();
-        let m_dot = self.compute_mass_flow(v);
-        self.update_current_water_flow_with(m_dot);
+    // En paralelo, la botella que se queda sin agua abre el colector por su tobera: el aire,
+    // mucho más ligero que el agua, sale en milisegundos, así que el depósito se abre a la
+    // atmósfera al acabar el paso y el agua de las demás se queda dentro. El empuje de ese
+    // soplido no se modela; su energía va a la atmósfera, como al soltar una etapa.
+    pub(crate) fn blow_out_empty_bottles(&mut self) {
+        let (atmospheric, gamma, density) =
+            (self.atmospheric_pressure, self.adiabatic_index, self.water_density);
+        let mut vented = false;
+        for v in 0..self.vessels.len() {
+            if !self.vessels[v].is_blowing_air(atmospheric) {
+                continue;
+            }
+            let before = self.gas_energy(&self.vessels[v]);
+            self.vessels[v].vent(atmospheric, gamma, density);
+            self.energy.atmosphere_work += before - self.gas_energy(&self.vessels[v]);
+            vented = true;
+        }
+        if vented {
+            self.update_vessel_totals();
+            self.energy.gas = self.vessels.iter().map(|v| self.gas_energy(v)).sum();
+        }
+    }
+
+    // el aire recién asentado: su energía y lo que ha empujado a la atmósfera desde el paso
+    // anterior (el vaciado de agua lo expande)
+    fn update_gas_energy(&mut self, previous_air_volume: f64) {
+        let expansion = self.current_air_volume - previous_air_volume;
+        self.energy.atmosphere_work += self.atmospheric_pressure * expansion;
+        self.energy.gas = self.vessels.iter().map(|v| self.gas_energy(v)).sum();
+    }
+
+    // el chorro, los impulsos y los trabajos del paso; las fuerzas actúan con la actitud
+    // `pitch` sobre la masa `mass` de antes del vaciado, y trabajan a la velocidad media
+    fn update_balances(&mut self, pitch: f64, mass: f64) {
+        let (sin, cos) = pitch.sin_cos();
+        let axis = Vector2D::new(cos, sin);
+        let height = self.position.y - self.launch_position.y;
+        let mut pressure_thrust = 0.0;
+        for flow in &self.nozzle_flows {
+            let dm = flow.mass_flow * self.step;
+            let pressure = self.vessels[flow.vessel].pressure_pa();
+            let ideal =
+                gas_dynamics::exit_velocity(pressure, self.atmospheric_pressure, self.water_density);
+            let jet = self.velocity - axis * flow.exit_velocity;
+            self.energy.water_kinetic += 0.5 * dm * jet.length().powi(2);
+            self.energy.water_potential += dm * self.gravity * height;
+            self.energy.nozzle_loss += 0.5 * dm * (ideal.powi(2) - flow.exit_velocity.powi(2));
+            self.momentum.water += jet * dm;
+            pressure_thrust += flow.thrust - flow.mass_flow * flow.exit_velocity;
+        }
+        let mean_velocity = self.velocity - self.acceleration * (0.5 * self.step);
+        let work = |force: Vector2D| {
+            (force.x * mean_velocity.x + force.y * mean_velocity.y) * self.step
+        };
+        let pressure_force = axis * pressure_thrust;
+        let normal = Vector2D::new(-sin, cos) * self.aero_normal_force;
+        self.energy.pressure_thrust_work += work(pressure_force);
+        self.energy.drag_loss -= work(normal);
+        (self.energy.rocket_kinetic, self.energy.rocket_potential) = self.rocket_energy();
+
+        self.momentum.pressure_thrust_impulse += pressure_force * self.step;
+        self.momentum.aero_impulse += normal * self.step;
+        self.momentum.gravity_impulse += Vector2D::new(0.0, -mass * self.gravity * self.step);
+        self.momentum.rocket = self.velocity * self.total_mass;
     }
 
-    pub fn update_time(&mut self) {
-        self.time = self.time + self.step;
+    /// Balance de energía hasta el paso actual.
+    pub fn energy_balance(&self) -> EnergyBalance {
+        self.energy
+    }
+
+    /// Balance de cantidad de movimiento hasta el paso actual.
+    pub fn momentum_balance(&self) -> MomentumBalance {
+        self.momentum
+    }
+
+    pub(crate) fn update_time(&mut self) {
+        self.time += self.step;
     }
 
     // =========================
     // logs y export
     // =========================
-    pub fn push_logs(&mut self) {
-        self.x_log.push(self.position.x);
-        self.y_log.push(self.position.y);
-        self.x_velocity_log.push(self.velocity.x);
-        self.y_velocity_log.push(self.velocity.y);
-        self.velocity_magnitur_log.push(self.velocity.magnitude());
-        self.x_acceleration_log.push(self.acceleration.x);
-        self.y_acceleration_log.push(self.acceleration.y);
-        self.acceleration_magnitur_log
-            .push(self.acceleration.magnitude());
-        self.time_log.push(self.time);
-        self.water_mass_log.push(self.water_mass);
-        self.mass_log.push(self.total_mass);
-        self.thrust_log.push(self.thrust_force);
-        self.pressure_log.push(self.current_pressure_pa); // << presión ACTUAL absoluta
+    /// Punto de impacto: interpola entre los pasos que cruzan y = 0.
+    /// Si aún no ha caído, la posición actual.
+    pub fn landing_position(&self) -> Vector2D {
+        self.events
+            .iter()
+            .find(|e| e.kind == FlightEventKind::Landing)
+            .map_or(self.position, |e| e.position)
+    }
+
+    // =========================
+    // eventos
+    // =========================
+
+    fn record_event(&mut self, kind: FlightEventKind) {
+        self.events.push(FlightEvent {
+            kind,
+            time: self.time,
+            position: self.position,
+            velocity: self.velocity,
+        });
+    }
+
+    fn has_event(&self, kind: FlightEventKind) -> bool {
+        self.events.iter().any(|e| e.kind == kind)
+    }
+
+    // compara con el paso anterior; el impacto se interpola dentro del paso
+    fn update_flight_events(&mut self) {
+        self.peaks.update(
+            self.thrust_force,
+            self.velocity.magnitude(),
+            self.acceleration.magnitude(),
+            self.current_pressure_pa,
+        );
+        let Some(previous) = &self.previous_log else {
+            return;
+        };
+        let (t0, p0, v0) = (previous.time, previous.position, previous.velocity);
+        let crossing = |a: f64, b: f64| a / (a - b);
+        let mut found = Vec::new();
+
+        if let Some(model) = &self.stability {
+            let rail = model.launch_rail_length;
+            let off = |p: Vector2D| (p - self.launch_position).magnitude() >= rail;
+            if !off(p0) && off(self.position) {
+                found.push((FlightEventKind::RailExit, 1.0));
+            }
+        }
+        if previous.water_mass > 0.0 && self.water_mass <= 0.0 {
+            found.push((FlightEventKind::Burnout, 1.0));
+        }
+        // el apogeo es el último paso que aún subía (el punto más alto de la integración)
+        if v0.y > 0.0 && self.velocity.y <= 0.0 && !self.has_event(FlightEventKind::Apogee) {
+            found.push((FlightEventKind::Apogee, 0.0));
+        }
+        if p0.y >= 0.0 && self.position.y < 0.0 && !self.has_event(FlightEventKind::Landing) {
+            found.push((FlightEventKind::Landing, crossing(p0.y, self.position.y)));
+        }
+
+        for (kind, f) in found {
+            let mut position = p0 + (self.position - p0) * f;
+            if kind == FlightEventKind::Landing {
+                position.y = 0.0;
+            }
+            self.events.push(FlightEvent {
+                kind,
+                time: t0 + (self.time - t0) * f,
+                position,
+                velocity: v0 + (self.velocity - v0) * f,
+            });
+        }
+    }
+
+    /// Eventos del vuelo en orden (lanzamiento, salida de rampa, fin de propulsión, ...).
+    pub fn events(&self) -> &[FlightEvent] {
+        &self.events
+    }
+
+    /// Apogeo, alcance, tiempos y máximos del vuelo.
+    pub fn summary(&self) -> FlightSummary {
+        let current = (self.time, self.position);
+        FlightSummary::from_events(&self.events, self.peaks, current, self.energy)
+    }
+
+    /// Punto de impacto en latitud/longitud, si hay lugar de lanzamiento.
+    pub fn landing_point(&self) -> Option<GeodeticPoint> {
+        self.launch_site.map(|site| site.planar_to_geodetic(self.landing_position()))
+    }
+
+    /// Trayectoria registrada en latitud/longitud/altura, si hay lugar de lanzamiento.
+    pub fn geodetic_trajectory(&self) -> Option<Vec<GeodeticPoint>> {
+        let site = self.launch_site?;
+        Some(self.records().iter().map(|r| site.planar_to_geodetic(r.position)).collect())
+    }
+
+    /// Vuelo en coordenadas geodésicas para KML/GeoJSON, si hay lugar de lanzamiento.
+    pub fn geo_track(&self, name: &str) -> Option<GeoTrack> {
+        let site = self.launch_site?;
+        let trajectory: Vec<Vector3D> =
+            self.records().iter().map(|r| Vector3D::from_planar(r.position)).collect();
+        let landing = Vector3D::from_planar(self.landing_position());
+        Some(GeoTrack::from_local(name, &site, &trajectory, landing))
+    }
+
+    /// Exporta la trayectoria como línea 3D KML; `landing_scatter` añade impactos de otras
+    /// corridas (Monte Carlo). Necesita `launch_site`.
+    pub fn export_logs_to_kml<P: AsRef<Path>>(
+        &self,
+        path: P,
+        landing_scatter: &[GeodeticPoint],
+    ) -> std::io::Result<()> {
+        let track = self.geo_track("Cohete de agua").ok_or_else(map_export::missing_launch_site)?;
+        track.export_kml(path, landing_scatter)
+    }
+
+    /// Exporta la trayectoria y los puntos clave como GeoJSON. Necesita `launch_site`.
+    pub fn export_logs_to_geojson<P: AsRef<Path>>(
+        &self,
+        path: P,
+        landing_scatter: &[GeodeticPoint],
+    ) -> std::io::Result<()> {
+        let track = self.geo_track("Cohete de agua").ok_or_else(map_export::missing_launch_site)?;
+        track.export_geojson(path, landing_scatter)
+    }
+
+    /// Registra el estado según la política de `SimulationConfig::logging`; con intervalos
+    /// interpola entre el paso anterior y el actual. La primera llamada siempre registra.
+    pub(crate) fn push_logs(&mut self) {
+        let current = self.log_record();
+        let due = match &self.previous_log {
+            None => vec![self.time],
+            Some(previous) => {
+                let burning = self.thrust_force > 0.0 || self.water_mass > 0.0;
+                self.log_scheduler.due(previous.time, self.time, burning)
+            }
+        };
+        for t in due {
+            match &self.previous_log {
+                Some(previous) if t < current.time && current.time > previous.time => {
+                    let fraction = (t - previous.time) / (current.time - previous.time);
+                    let sample = previous.lerp(&current, fraction);
+                    self.write_log(&sample);
+                }
+                _ => self.write_log(&current),
+            }
+        }
+        self.previous_log = Some(current);
+    }
+
+    /// Registra el estado actual si el último registro no es de este instante
+    /// (al acabar `run`, para que el log termine en el aterrizaje).
+    pub(crate) fn flush_logs(&mut self) {
+        if self.last_logged_time != Some(self.time) {
+            let current = self.log_record();
+            self.write_log(&current);
+        }
+    }
+
+    /// Estado actual como registro de log.
+    pub fn log_record(&self) -> LogRecord {
+        LogRecord {
+            time: self.time,
+            position: self.position,
+            velocity: self.velocity,
+            acceleration: self.acceleration,
+            water_mass: self.water_mass,
+            total_mass: self.total_mass,
+            thrust: self.thrust_force,
+            pressure: self.current_pressure_pa, // << presión ACTUAL absoluta
+            pitch: self.pitch,
+            pitch_rate: self.pitch_rate,
+            angle_of_attack: self.angle_of_attack,
+            bottles: self
+                .vessels
+                .iter()
+                .flat_map(|v| {
+                    v.bottles().iter().map(move |b| BottleSample {
+                        pressure_pa: v.pressure_pa(),
+                        water_mass: b.water_mass(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    // un fallo de escritura no para la simulación: se guarda y lo devuelve `finish_logs`
+    fn write_log(&mut self, record: &LogRecord) {
+        self.last_logged_time = Some(record.time);
+        if let Err(e) = self.logger.log(record) {
+            self.log_error.get_or_insert(e);
+        }
+    }
+
+    /// Cambia el destino de los logs; lo ya registrado en memoria se pasa al nuevo.
+    pub fn set_logger(&mut self, mut logger: Box<dyn Logger>) {
+        for record in self.logger.records() {
+            if let Err(e) = logger.log(record) {
+                self.log_error.get_or_insert(e);
+            }
+        }
+        self.logger = logger;
+    }
+
+    /// Escribe los logs al CSV mientras corre la simulación, sin guardarlos en memoria.
+    pub fn stream_logs_to_csv<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        options: CsvOptions,
+    ) -> std::io::Result<()> {
+        let columns = match &options.columns {
+            Some(columns) => columns.clone(),
+            None => self.csv_columns(),
+        };
+        let logger = CsvStreamLogger::create(
+            path,
+            options,
+            columns,
+            self.atmospheric_pressure,
+            self.launch_site,
+        )?;
+        self.set_logger(Box::new(logger));
+        Ok(())
+    }
+
+    /// Escribe un objeto JSON por muestra (NDJSON) mientras corre la simulación.
+    pub fn stream_logs_to_ndjson<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()> {
+        let logger = NdjsonStreamLogger::create(path, self.csv_columns(), self.launch_site)?;
+        self.set_logger(Box::new(logger));
+        Ok(())
+    }
+
+    /// Documento JSON de la corrida: configuración, resumen, eventos y series temporales.
+    pub fn to_json(&self) -> Json {
+        let columns = self.csv_columns();
+        let site = self.launch_site.as_ref();
+        Json::object([
+            ("config", json_export::config_json(&self.config)),
+            ("summary", json_export::summary_json(&self.summary(), self.landing_point())),
+            ("events", Json::Array(self.events().iter().map(json_export::event_json).collect())),
+            ("time_series", json_export::time_series_json(self.records(), &columns, site)),
+        ])
+    }
+
+    /// Exporta `to_json` a un archivo.
+    pub fn export_run_to_json<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", self.to_json())?;
+        writer.flush()
+    }
+
+    /// Gráficas del vuelo (trayectoria y series temporales con los eventos) en `dir`.
+    pub fn export_plots<P: AsRef<Path>>(
+        &self,
+        dir: P,
+        format: PlotFormat,
+    ) -> std::io::Result<Vec<PathBuf>> {
+        if self.records().is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "los registros no se guardan en memoria con este logger",
+            ));
+        }
+        plotting::export_flight_charts(dir, self.records(), &self.events, format)
+    }
+
+    /// Datos para el informe HTML (ver `report::html_report`).
+    pub fn report(&self, name: &str) -> RunReport {
+        RunReport {
+            name: name.to_string(),
+            config: self.config.clone(),
+            summary: self.summary(),
+            events: self.events.clone(),
+            records: self.records().to_vec(),
+            landing_point: self.landing_point(),
+        }
+    }
+
+    /// Logs por columnas (f64, nombres del CSV) con la configuración y el resumen en JSON
+    /// como metadatos.
+    pub fn logs_table(&self) -> ColumnarTable {
+        let site = self.launch_site.as_ref();
+        let names = CsvOptions::default();
+        let mut table = ColumnarTable::new();
+        for column in self.csv_columns() {
+            let values = self.records().iter().map(|r| r.csv_value(column, site)).collect();
+            table.push_column(&names.header(column), ColumnData::Double(values));
+        }
+        let config = json_export::config_json(&self.config);
+        table.push_metadata("simulation_config", config.to_string());
+        let summary = json_export::summary_json(&self.summary(), self.landing_point());
+        table.push_metadata("flight_summary", summary.to_string());
+        table
+    }
+
+    /// Exporta `logs_table` como Parquet.
+    pub fn export_logs_to_parquet<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        if self.records().is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "los registros no se guardan en memoria con este logger",
+            ));
+        }
+        self.logs_table().export_parquet(path)
+    }
+
+    /// Vacía el logger y devuelve el primer error de escritura, si lo hubo.
+    pub fn finish_logs(&mut self) -> std::io::Result<()> {
+        if let Some(e) = self.log_error.take() {
+            return Err(e);
+        }
+        self.logger.finish()
+    }
+
+    /// Registros guardados en memoria (vacío con un logger de streaming o nulo).
+    pub fn records(&self) -> &[LogRecord] {
+        self.logger.records()
     }
 
     /// Exporta los logs a un archivo CSV sin usar crates externos.
@@ -349,64 +1376,55 @@ impl Simulation {
         path: P,
         delimiter: char,
     ) -> std::io::Result<()> {
-        let file = File::create(path)?;
-        let mut writer = BufWriter::new(file);
+        self.export_logs_to_csv_with(path, &CsvOptions { delimiter, ..CsvOptions::default() })
+    }
 
-        // BOM UTF-8 (Excel)
-        writer.write_all("\u{FEFF}".as_bytes())?;
+    /// Exporta los logs con columnas, unidades y formato a elección.
+    pub fn export_logs_to_csv_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &CsvOptions,
+    ) -> std::io::Result<()> {
+        let available = self.csv_columns();
+        let columns = options.columns.as_deref().unwrap_or(&available);
+        if let Some(missing) = columns.iter().find(|c| !available.contains(c)) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("columna {:?} no disponible en esta simulación", missing),
+            ));
+        }
 
-        // Encabezados (renombré presión para dejar claro que es absoluta)
-        writeln!(
-            writer,
-            "time_s{d}x_m{d}y_m{d}vx_m_s{d}vy_m_s{d}v_mag_m_s{d}ax_m_s2{d}ay_m_s2{d}a_mag_m_s2{d}water_mass_kg{d}total_mass_kg{d}thrust_N{d}pressure_abs_Pa",
-            d = delimiter
-        )?;
+        let records = self.records();
+        if records.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "los registros no se guardan en memoria con este logger",
+            ));
+        }
+        let site = self.launch_site.as_ref();
+        options.write_file(path, columns, records.len(), self.atmospheric_pressure, |i, column| {
+            records[i].csv_value(column, site)
+        })
+    }
 
-        // Longitud mínima común
-        let n = [
-            self.time_log.len(),
-            self.x_log.len(),
-            self.y_log.len(),
-            self.x_velocity_log.len(),
-            self.y_velocity_log.len(),
-            self.velocity_magnitur_log.len(),
-            self.x_acceleration_log.len(),
-            self.y_acceleration_log.len(),
-            self.acceleration_magnitur_log.len(),
-            self.water_mass_log.len(),
-            self.mass_log.len(),
-            self.thrust_log.len(),
-            self.pressure_log.len(),
-        ]
-        .iter()
-        .min()
-        .cloned()
-        .unwrap_or(0);
-
-        // Filas
-        for i in 0..n {
-            writeln!(
-                writer,
-                "{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}{d}{:.6}",
-                self.time_log[i],
-                self.x_log[i],
-                self.y_log[i],
-                self.x_velocity_log[i],
-                self.y_velocity_log[i],
-                self.velocity_magnitur_log[i],
-                self.x_acceleration_log[i],
-                self.y_acceleration_log[i],
-                self.acceleration_magnitur_log[i],
-                self.water_mass_log[i],
-                self.mass_log[i],
-                self.thrust_log[i],
-                self.pressure_log[i],
-                d = delimiter
-            )?;
-        }
-
-        writer.flush()?;
-        Ok(())
+    /// Columnas que tiene esta simulación, en el orden del CSV por defecto: las 13 de
+    /// siempre, actitud si hay modelo, coordenadas si hay lugar y cada botella si hay varias.
+    pub fn csv_columns(&self) -> Vec<CsvColumn> {
+        let attitude = self.stability.is_some();
+        let mut columns: Vec<CsvColumn> = logging::FIELDS
+            .iter()
+            .filter(|field| attitude || !field.attitude)
+            .map(|field| field.column)
+            .collect();
+        if self.launch_site.is_some() {
+            columns.extend([CsvColumn::Latitude, CsvColumn::Longitude, CsvColumn::Altitude]);
+        }
+        if self.bottle_count > 1 {
+            for b in 1..=self.bottle_count {
+                columns.extend([CsvColumn::BottlePressure(b), CsvColumn::BottleWaterMass(b)]);
+            }
+        }
+        columns
     }
 }
 
@@ -418,14 +1436,14 @@ impl Simulation {
 #[test]
 fn burnout_time_is_short() {
     let dt = 1e-3;
-    let mut sim = super::Simulation::new(dt);
+    let mut sim = super::Simulation::new(dt).unwrap();
     let mut t_burnout = 0.0;
     for _ in 0..10_000 {
         if sim.water_mass <= 0.0 {
             t_burnout = sim.time;
             break;
         }
-        sim.step_once();
+        sim.step_once().unwrap();
     }
     assert!(
         sim.water_mass <= 1e-9,
@@ -443,14 +1461,14 @@ fn burnout_time_is_short() {
 #[test]
 fn apogee_and_range_reasonable() {
     let dt = 1e-3;
-    let mut sim = super::Simulation::new(dt);
+    let mut sim = super::Simulation::new(dt).unwrap();
     // corre hasta tocar el suelo o 30 s (lo que ocurra primero)
-    sim.run(30.0);
+    sim.run(30.0).unwrap();
 
     // Apogeo
-    let y_max = sim.y_log.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let y_max = sim.records().iter().map(|r| r.position.y).fold(f64::NEG_INFINITY, f64::max);
     // Alcance (x final)
-    let x_final = *sim.x_log.last().unwrap_or(&0.0);
+    let x_final = sim.records().last().map_or(0.0, |r| r.position.x);
 
     // Rangos amplios para no ser frágiles frente a pequeños cambios de modelo:
     // con tus constantes deberías ver decenas de metros en apogeo y ~100 m de alcance.
@@ -465,3 +1483,272 @@ fn apogee_and_range_reasonable() {
         x_final
     );
 }
+
+/// Una botella PET que se hincha guarda más aire (y más energía) que una rígida del mismo tamaño.
+#[test]
+fn compliant_bottle_stores_more_air() {
+    let rigid = super::Simulation::new(1e-3).unwrap();
+    let config = crate::config::SimulationConfig {
+        bottle: crate::physics::bottle::BottleSpec::pet_2l(),
+        ..Default::default()
+    };
+    let mut elastic = super::Simulation::with_config(1e-3, config).unwrap();
+    assert!(elastic.current_air_volume > rigid.current_air_volume);
+
+    // al iniciar, el volumen resuelto debe coincidir con el de la presión de lanzamiento
+    let v0 = elastic.current_air_volume;
+    elastic.update_current_air_volume();
+    assert!((elastic.current_air_volume - v0).abs() < 1e-9);
+}
+
+/// Una tobera restringida descarga más despacio que la boca completa.
+#[test]
+fn restricted_nozzle_burns_longer() {
+    let burnout = |nozzle: Nozzle| {
+        let config = crate::config::SimulationConfig { nozzle, ..Default::default() };
+        let mut sim = super::Simulation::with_config(1e-4, config).unwrap();
+        while sim.water_mass > 0.0 && sim.time < 5.0 {
+            sim.step_once().unwrap();
+        }
+        sim.time
+    };
+    let full = burnout(Nozzle::full_bore());
+    let restricted = burnout(Nozzle::restricted_9mm());
+    assert!(restricted > 3.0 * full, "full = {full}, restricted = {restricted}");
+
+    // la pérdida de la contracción cuenta también sin corrección de C_d
+    let ideal = Nozzle::ideal(0.021);
+    let contracted = Nozzle { contraction_loss: 0.3, ..ideal.clone() };
+    assert!(burnout(contracted) > burnout(ideal));
+}
+
+/// Cuatro botellas independientes iguales se comportan como cuatro cohetes en paralelo:
+/// mismo empuje por botella, y cada botella en su columna del log.
+#[test]
+fn independent_cluster_adds_thrust() {
+    use crate::cluster::{ClusterBottle, Coupling};
+    use crate::config::SimulationConfig;
+
+    let single = SimulationConfig::default();
+    let extra = ClusterBottle {
+        bottle: single.bottle.clone(),
+        nozzle: single.nozzle.clone(),
+        water_mass: single.initial_water_mass,
+    };
+    let cluster_of = |coupling: Coupling| SimulationConfig {
+        cluster: vec![extra.clone(); 3],
+        coupling,
+        dry_mass: single.dry_mass * 4.0,
+        ..single.clone()
+    };
+
+    let mut one = super::Simulation::with_config(1e-3, single.clone()).unwrap();
+    let mut four = super::Simulation::with_config(1e-3, cluster_of(Coupling::Independent)).unwrap();
+    one.step_once().unwrap();
+    four.step_once().unwrap();
+    assert!((four.thrust_force - 4.0 * one.thrust_force).abs() < 1e-9);
+    assert!((four.records()[1].position.y - one.records()[1].position.y).abs() < 1e-12);
+    assert_eq!(four.records()[1].bottles.len(), 4);
+
+    // acoplado en serie: una sola tobera, y el agua sale primero de la botella de arriba
+    let mut series = super::Simulation::with_config(1e-3, cluster_of(Coupling::Series)).unwrap();
+    series.step_once().unwrap();
+    assert_eq!(series.nozzle_flows.len(), 1);
+    let bottles = &series.records()[1].bottles;
+    assert!(bottles[3].water_mass < bottles[0].water_mass);
+}
+
+/// En paralelo con cargas distintas, la botella que se vacía primero suelta el aire: se
+/// acaba el empuje y el agua de la otra se queda dentro, sin romper el balance.
+#[test]
+fn parallel_cluster_blows_out_through_the_empty_bottle() {
+    use crate::cluster::{ClusterBottle, Coupling};
+    use crate::config::SimulationConfig;
+
+    let single = SimulationConfig::default();
+    let config = SimulationConfig {
+        cluster: vec![ClusterBottle {
+            bottle: single.bottle.clone(),
+            nozzle: single.nozzle.clone(),
+            water_mass: 0.2,
+        }],
+        coupling: Coupling::Parallel,
+        dry_mass: single.dry_mass * 2.0,
+        ..single
+    };
+    let mut sim = super::Simulation::with_config(1e-4, config).unwrap();
+    while sim.vessels[0].bottles()[1].water_mass() > 0.0 {
+        sim.step_once().unwrap();
+        assert!(sim.vessels[0].bottles()[0].water_mass() > 0.0);
+    }
+    assert_eq!(sim.current_pressure_pa, sim.atmospheric_pressure);
+    let (trapped, emptied) = (sim.water_mass, sim.time);
+    sim.run(30.0).unwrap();
+    assert!(sim.has_event(FlightEventKind::Landing));
+    assert!(trapped > 0.0 && sim.water_mass == trapped, "{}", sim.water_mass);
+    assert!(sim.records().iter().filter(|r| r.time > emptied).all(|r| r.thrust == 0.0));
+    let balance = sim.energy_balance();
+    assert!(balance.relative_unexplained_error().abs() < 0.01, "{}", balance);
+}
+
+/// Un cohete estable gira hacia el viento (weathercocking) y oscila alrededor del
+/// viento relativo; sin modelo de actitud el ángulo no cambia.
+#[test]
+fn stable_rocket_weathercocks_into_wind() {
+    let fly = |wind_speed: f64| {
+        let config = crate::config::SimulationConfig {
+            launching_angle_deg: 80.0,
+            stability: Some(StabilityModel { wind_speed, ..Default::default() }),
+            ..Default::default()
+        };
+        let mut sim = super::Simulation::with_config(1e-4, config).unwrap();
+        sim.run(30.0).unwrap();
+        sim
+    };
+    let calm = fly(0.0);
+    let headwind = fly(-5.0); // sopla desde delante (hacia -x)
+    assert!(calm.static_margin_calibers().unwrap() > 1.0);
+
+    // con viento de frente el morro se inclina hacia el viento, muy por debajo del caso en calma
+    let at = |s: &super::Simulation, t: f64| s.records()[(t / 1e-4) as usize].pitch.to_degrees();
+    assert!(at(&headwind, 0.4) < at(&calm, 0.4) - 10.0);
+    assert!(at(&headwind, 0.4) < 70.0, "pitch = {}", at(&headwind, 0.4));
+
+    // el ángulo de ataque cambia de signo: hay oscilación, no solo deriva
+    let alphas: Vec<f64> = headwind.records()[..4000].iter().map(|r| r.angle_of_attack).collect();
+    assert!(alphas.iter().any(|a| *a > 0.05) && alphas.iter().any(|a| *a < -0.05));
+
+    let mut fixed = super::Simulation::new(1e-3).unwrap();
+    fixed.run(30.0).unwrap();
+    assert!(fixed.records().iter().all(|r| r.pitch == fixed.launching_angle_rad));
+}
+
+/// Con geometría, el CG se calcula con el agua: al vaciarse sube y el margen crece.
+#[test]
+fn geometry_updates_center_of_gravity() {
+    let geometry = RocketGeometry::two_liter_finned();
+    let config = crate::config::SimulationConfig {
+        dry_mass: geometry.dry_mass(),
+        geometry: Some(geometry),
+        ..Default::default()
+    };
+    let mut sim = super::Simulation::with_config(1e-3, config).unwrap();
+    let loaded_margin = sim.static_margin_calibers().unwrap();
+    let report = sim.design_report().unwrap();
+    assert!((report.margin_loaded() - loaded_margin).abs() < 1e-12);
+
+    sim.run(30.0).unwrap();
+    let empty_margin = sim.static_margin_calibers().unwrap();
+    assert!((report.margin_empty() - empty_margin).abs() < 1e-12);
+    assert!(empty_margin > loaded_margin);
+}
+
+/// Con intervalo fijo el CSV se reduce sin tocar la integración: mismo aterrizaje y
+/// muestras interpoladas sobre la curva del paso a paso.
+#[test]
+fn fixed_interval_logging_keeps_trajectory() {
+    use crate::logging::LoggingPolicy;
+
+    let mut dense = Simulation::with_config(1e-4, SimulationConfig::default()).unwrap();
+    let config = SimulationConfig {
+        logging: LoggingPolicy::FixedInterval(0.01),
+        ..Default::default()
+    };
+    let mut sparse = Simulation::with_config(1e-4, config).unwrap();
+    dense.run(30.0).unwrap();
+    sparse.run(30.0).unwrap();
+
+    let (sparse, dense) = (sparse.records(), dense.records());
+    assert!(sparse.len() * 50 < dense.len());
+    assert_eq!(sparse.last(), dense.last());
+
+    // t = 0.5 s cae entre dos pasos: la muestra interpolada queda sobre la curva densa
+    let k = sparse.iter().position(|r| (r.time - 0.5).abs() < 1e-9).unwrap();
+    let j = dense.iter().position(|r| r.time >= sparse[k].time).unwrap();
+    assert!((sparse[k].position.y - dense[j].position.y).abs() < 1e-3);
+}
+
+/// Escribir el CSV durante la corrida da el mismo archivo que exportarlo al final.
+#[test]
+fn streamed_csv_matches_export() {
+    let dir = std::env::temp_dir();
+    let streamed = dir.join(format!("stream_{}.csv", std::process::id()));
+    let exported = dir.join(format!("export_{}.csv", std::process::id()));
+
+    let mut memory = Simulation::new(1e-3).unwrap();
+    memory.run(30.0).unwrap();
+    memory.export_logs_to_csv(&exported, ';').unwrap();
+
+    let mut streaming = Simulation::new(1e-3).unwrap();
+    let options = CsvOptions { delimiter: ';', ..CsvOptions::default() };
+    streaming.stream_logs_to_csv(&streamed, options).unwrap();
+    streaming.run(30.0).unwrap();
+    streaming.finish_logs().unwrap();
+    assert!(streaming.records().is_empty());
+    assert_eq!(streaming.landing_position(), memory.landing_position());
+
+    let (a, b) = (std::fs::read(&streamed).unwrap(), std::fs::read(&exported).unwrap());
+    let _ = (std::fs::remove_file(&streamed), std::fs::remove_file(&exported));
+    assert_eq!(a, b);
+}
+
+/// Los eventos salen en orden y el resumen cuadra con la trayectoria registrada.
+#[test]
+fn flight_events_and_summary() {
+    let mut sim = Simulation::new(1e-3).unwrap();
+    sim.run(30.0).unwrap();
+    let kinds: Vec<FlightEventKind> = sim.events().iter().map(|e| e.kind).collect();
+    use FlightEventKind::*;
+    assert_eq!(kinds, vec![Launch, Burnout, Apogee, Landing]);
+
+    let summary = sim.summary();
+    let y_max = sim.records().iter().map(|r| r.position.y).fold(f64::NEG_INFINITY, f64::max);
+    assert_eq!(summary.apogee, y_max);
+    assert!((summary.range - sim.landing_position().x).abs() < 1e-12);
+    assert!(summary.burnout_time.unwrap() < summary.apogee_time);
+    let max_thrust = sim.records().iter().map(|r| r.thrust).fold(0.0, f64::max);
+    assert_eq!(summary.peaks.max_thrust, max_thrust);
+
+    let json = sim.to_json().to_string();
+    assert!(json.starts_with(r#"{"config":{"gravity_m_s2":9.81"#), "{}", &json[..60]);
+    assert!(json.contains(r#""event":"apogee""#));
+}
+
+/// Un paso negativo, nulo o demasiado largo no llega a integrar (antes el negativo no
+/// terminaba nunca).
+#[test]
+fn invalid_step_is_rejected() {
+    for step in [-1e-3, 0.0, f64::NAN, 2.0 * MAX_TIME_STEP] {
+        assert!(matches!(Simulation::new(step), Err(SimulationError::InvalidStep { .. })));
+    }
+    let mut sim = Simulation::new(1e-3).unwrap();
+    assert!(sim.run(f64::INFINITY).is_err());
+    assert_eq!(sim.time(), 0.0);
+}
+
+/// Un estado imposible para en el primer paso, con el diagnóstico, y ese paso no se registra.
+#[test]
+fn unhealthy_state_stops_the_run() {
+    let config = crate::config::SimulationConfig::default();
+    let start = Simulation::new(1e-3).unwrap().snapshot();
+    let diagnose = |state: &SimulationState| {
+        let mut sim = Simulation::restore(config.clone(), state).unwrap();
+        let error = sim.run(30.0).unwrap_err();
+        assert_eq!(sim.records().len(), 1);
+        error
+    };
+
+    let mut nan = start.clone();
+    nan.velocity.x = f64::NAN;
+    let error = diagnose(&nan);
+    assert!(matches!(
+        error,
+        SimulationError::UnhealthyState { iteration: 1, quantity: "position.x", .. }
+    ));
+    assert!(error.to_string().starts_with("paso 1 (t = 0.001000 s): `position.x` = NaN"));
+
+    let mut vacuum = start;
+    vacuum.vessels[0].polytropic_constant = -1.0;
+    let error = diagnose(&vacuum);
+    assert!(matches!(error, SimulationError::UnhealthyState { quantity: "vessel.pressure", .. }));
+}
@@ -0,0 +1,756 @@
+// six_dof.rs
+use std::path::Path;
+
+use crate::{
+    config::SimulationConfig,
+    csv_export::{CsvColumn, CsvOptions},
+    error::{check, SimulationError},
+    events::FlightEventKind,
+    geodesy::{GeodeticPoint, LaunchSite},
+    logging::LogScheduler,
+    observer::{Observer, Termination},
+    map_export::{self, GeoTrack},
+    math_utils::{quaternion::Quaternion, vector_3d::Vector3D},
+    simulation::Simulation,
+};
+
+/// Parámetros propios del modo 6-DOF. Ejes del mundo: x en la dirección de lanzamiento,
+/// y hacia arriba, z a la derecha (x × y = z). El rumbo de +x sobre el terreno es uno
+/// solo, `LaunchSite::azimuth_deg` de la configuración, igual que en el modelo plano.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SixDofConfig {
+    /// Viento lateral (eje z) [m/s]; el de frente/cola viene de `StabilityModel::wind_speed`.
+    pub crosswind_speed: f64,
+}
+
+/// Estado en un instante; se registra según `config.logging`, como en el modelo plano.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SixDofSample {
+    /// Tiempo [s].
+    pub time: f64,
+    /// Posición [m].
+    pub position: Vector3D,
+    /// Velocidad [m/s].
+    pub velocity: Vector3D,
+    /// Cuerpo -> mundo.
+    pub attitude: Quaternion,
+    /// En ejes del mundo [rad/s].
+    pub angular_velocity: Vector3D,
+    /// Ángulo de ataque [rad].
+    pub angle_of_attack: f64,
+    /// Empuje [N].
+    pub thrust: f64,
+    /// Masa total [kg].
+    pub total_mass: f64,
+    /// Agua que queda [kg].
+    pub water_mass: f64,
+}
+
+impl SixDofSample {
+    /// Interpolación: lineal, salvo la actitud (por el arco corto); 0 = `self`, 1 = `other`.
+    pub fn lerp(&self, other: &Self, t: f64) -> Self {
+        let mix = |a: f64, b: f64| a + (b - a) * t;
+        let mix_vec = |a: Vector3D, b: Vector3D| a + (b - a) * t;
+        Self {
+            time: mix(self.time, other.time),
+            position: mix_vec(self.position, other.position),
+            velocity: mix_vec(self.velocity, other.velocity),
+            attitude: self.attitude.nlerp(other.attitude, t),
+            angular_velocity: mix_vec(self.angular_velocity, other.angular_velocity),
+            angle_of_attack: mix(self.angle_of_attack, other.angle_of_attack),
+            thrust: mix(self.thrust, other.thrust),
+            total_mass: mix(self.total_mass, other.total_mass),
+            water_mass: mix(self.water_mass, other.water_mass),
+        }
+    }
+
+    /// Valor SI de una columna (ángulos en rad; NaN si el modelo 6-DOF no la da).
+    pub fn csv_value(&self, column: CsvColumn, site: Option<&LaunchSite>) -> f64 {
+        let geodetic = |pick: fn(&GeodeticPoint) -> f64| {
+            site.map_or(f64::NAN, |s| pick(&s.local_to_geodetic(self.position)))
+        };
+        match column {
+            CsvColumn::Time => self.time,
+            CsvColumn::X => self.position.x,
+            CsvColumn::Y => self.position.y,
+            CsvColumn::Z => self.position.z,
+            CsvColumn::Vx => self.velocity.x,
+            CsvColumn::Vy => self.velocity.y,
+            CsvColumn::Vz => self.velocity.z,
+            CsvColumn::VelocityMagnitude => self.velocity.magnitude(),
+            CsvColumn::Qw => self.attitude.w,
+            CsvColumn::Qx => self.attitude.x,
+            CsvColumn::Qy => self.attitude.y,
+            CsvColumn::Qz => self.attitude.z,
+            CsvColumn::Wx => self.angular_velocity.x,
+            CsvColumn::Wy => self.angular_velocity.y,
+            CsvColumn::Wz => self.angular_velocity.z,
+            CsvColumn::AngleOfAttack => self.angle_of_attack,
+            CsvColumn::Thrust => self.thrust,
+            CsvColumn::TotalMass => self.total_mass,
+            CsvColumn::WaterMass => self.water_mass,
+            CsvColumn::Latitude => geodetic(|p| p.latitude_deg),
+            CsvColumn::Longitude => geodetic(|p| p.longitude_deg),
+            CsvColumn::Altitude => geodetic(|p| p.altitude_m),
+            _ => f64::NAN,
+        }
+    }
+}
+
+/// Evento del vuelo en 3D (los mismos tipos que en el modelo plano).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SixDofEvent {
+    /// Qué ha pasado.
+    pub kind: FlightEventKind,
+    /// Cuándo [s].
+    pub time: f64,
+    /// Dónde [m].
+    pub position: Vector3D,
+    /// Velocidad del cohete [m/s].
+    pub velocity: Vector3D,
+}
+
+/// Vuelo en 3D: posición/velocidad, actitud (cuaternión) y velocidad angular.
+/// La propulsión y las masas las calcula una `Simulation` interna; aquí solo
+/// cambia la cinemática. Con acimut y viento lateral nulos reproduce el modelo plano.
+pub struct SixDofSimulation {
+    core: Simulation,
+    crosswind_speed: f64,
+
+    position: Vector3D,         // [m]
+    velocity: Vector3D,         // [m/s]
+    acceleration: Vector3D,     // [m/s²]
+    launch_position: Vector3D,  // inicio de la rampa [m]
+    attitude: Quaternion,       // cuerpo -> mundo; el eje del cohete es +x del cuerpo
+    angular_velocity: Vector3D, // en ejes del mundo [rad/s]
+    angle_of_attack: f64,       // [rad]
+    normal_direction: Vector3D, // sentido de la fuerza normal (unitario o cero)
+
+    events: Vec<SixDofEvent>,
+    previous: SixDofSample, // estado al final del paso anterior
+    log_scheduler: LogScheduler,
+    samples: Vec<SixDofSample>,
+    observers: Vec<Box<dyn Observer<Self>>>,
+}
+
+impl SixDofSimulation {
+    /// Vuelo de `config` con el viento lateral de `six_dof`.
+    pub fn new(
+        time_steps: f64,
+        config: SimulationConfig,
+        six_dof: SixDofConfig,
+    ) -> Result<Self, SimulationError> {
+        check("six_dof.crosswind_speed", six_dof.crosswind_speed, true, "finito")?;
+        let elevation = config.launching_angle_deg.to_radians();
+        let attitude = Quaternion::from_axis_angle(Vector3D::UNIT_Z, elevation);
+
+        let position = Vector3D::from_planar(config.initial_position);
+        let velocity = Vector3D::from_planar(config.initial_velocity);
+        let acceleration = Vector3D::from_planar(config.initial_acceleration);
+
+        let log_scheduler = LogScheduler::new(config.logging, config.initial_time);
+        let mut sim = Self {
+            core: Simulation::with_config(time_steps, config)?,
+            crosswind_speed: six_dof.crosswind_speed,
+            position,
+            velocity,
+            acceleration,
+            launch_position: position,
+            attitude,
+            angular_velocity: Vector3D::ZERO,
+            angle_of_attack: 0.0,
+            normal_direction: Vector3D::ZERO,
+            events: Vec::new(),
+            previous: SixDofSample::default(),
+            log_scheduler,
+            samples: Vec::new(),
+            observers: Vec::new(),
+        };
+        sim.previous = sim.sample();
+        sim.samples.push(sim.previous); // primer muestreo
+        sim.record_event(FlightEventKind::Launch, sim.previous);
+        Ok(sim)
+    }
+
+    // =========================
+    // bucle principal
+    // =========================
+    /// Como `Simulation::run`: hasta `t_max` o `is_finished`; para si un paso deja el
+    /// estado enfermo.
+    pub fn run(&mut self, t_max: f64) -> Result<(), SimulationError> {
+        check("t_max", t_max, true, "finito")?;
+        while self.core.time() < t_max {
+            self.step_once()?;
+            if self.is_finished() {
+                break;
+            }
+        }
+        // el registro termina en el último paso aunque la política no lo tocara
+        if self.samples.last().map(|s| s.time) != Some(self.core.time()) {
+            self.samples.push(self.previous);
+        }
+        Ok(())
+    }
+
+    /// Ha bajado de y = 0 pasado un instante desde el lanzamiento.
+    pub fn has_landed(&self) -> bool {
+        self.core.time() > 0.02 && self.position.y < 0.0
+    }
+
+    /// Como `Simulation::is_finished`: `config.termination` o un observador.
+    pub fn is_finished(&self) -> bool {
+        let termination: &[Termination] = &self.core.config().termination;
+        termination.iter().any(|t| t.is_met(self))
+            || self.observers.iter().any(|o| o.should_stop(self))
+    }
+
+    /// Registra un observador; se le llama al final de cada `step_once`.
+    pub fn add_observer(&mut self, observer: Box<dyn Observer<Self>>) {
+        self.observers.push(observer);
+    }
+
+    // eventos nuevos desde `first_new_event` y luego el paso, a cada observador
+    fn notify_observers(&mut self, first_new_event: usize) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let mut observers = std::mem::take(&mut self.observers);
+        for observer in &mut observers {
+            for event in &self.events[first_new_event..] {
+                observer.on_event(self, event);
+            }
+            observer.on_step(self);
+        }
+        self.observers = observers;
+    }
+
+    // =========================
+    // eventos
+    // =========================
+    fn record_event(&mut self, kind: FlightEventKind, at: SixDofSample) {
+        let (time, position, velocity) = (at.time, at.position, at.velocity);
+        self.events.push(SixDofEvent { kind, time, position, velocity });
+    }
+
+    /// Si ya se ha registrado un evento de ese tipo.
+    pub fn has_event(&self, kind: FlightEventKind) -> bool {
+        self.events.iter().any(|e| e.kind == kind)
+    }
+
+    /// Eventos del vuelo en orden, como `Simulation::events`.
+    pub fn events(&self) -> &[SixDofEvent] {
+        &self.events
+    }
+
+    // mismas reglas que el modelo plano, interpolando dentro del paso
+    fn update_flight_events(&mut self, current: SixDofSample) {
+        let previous = self.previous;
+        let (p0, p1) = (previous.position, current.position);
+        let mut found = Vec::new();
+
+        if let Some(model) = self.core.stability() {
+            let rail = model.launch_rail_length;
+            let off = |p: Vector3D| (p - self.launch_position).magnitude() >= rail;
+            if !off(p0) && off(p1) {
+                found.push((FlightEventKind::RailExit, 1.0));
+            }
+        }
+        if previous.water_mass > 0.0 && current.water_mass <= 0.0 {
+            found.push((FlightEventKind::Burnout, 1.0));
+        }
+        let (v0, v1) = (previous.velocity.y, current.velocity.y);
+        if v0 > 0.0 && v1 <= 0.0 && !self.has_event(FlightEventKind::Apogee) {
+            found.push((FlightEventKind::Apogee, 0.0));
+        }
+        if p0.y >= 0.0 && p1.y < 0.0 && !self.has_event(FlightEventKind::Landing) {
+            found.push((FlightEventKind::Landing, p0.y / (p0.y - p1.y)));
+        }
+
+        for (kind, f) in found {
+            let mut at = previous.lerp(&current, f);
+            if kind == FlightEventKind::Landing {
+                at.position.y = 0.0;
+            }
+            self.record_event(kind, at);
+        }
+    }
+
+    /// Avanza un paso; como `Simulation::step_once`, no registra un paso enfermo.
+    pub fn step_once(&mut self) -> Result<(), SimulationError> {
+        // propulsión y masas: las mismas funciones que el modelo plano
+        self.core.update_current_air_volume();
+        self.core.update_current_internal_pressure();
+        self.core.update_nozzle_flows();
+        self.core.update_mass_properties();
+        self.core.update_thrust_and_mass();
+
+        let normal_force = self.compute_normal_force();
+        let dt = self.core.step();
+        let mass = self.core.total_mass();
+        let force = self.axis() * self.core.thrust() + normal_force;
+        self.acceleration = force / mass - Vector3D::UNIT_Y * self.core.gravity();
+        self.velocity += self.acceleration * dt;
+        self.position += self.velocity * dt;
+
+        self.update_attitude();
+
+        self.core.update_current_water_flow();
+        self.core.blow_out_empty_bottles();
+        self.core.update_time();
+        self.check_health()?;
+
+        let first_new_event = self.events.len();
+        let current = self.sample();
+        self.update_flight_events(current);
+        self.push_logs(current);
+        self.previous = current;
+        self.notify_observers(first_new_event);
+        Ok(())
+    }
+
+    // la propulsión la revisa el modelo plano; la cinemática y la energía, con el estado 3D
+    // (el `core` no mueve su posición ni su velocidad)
+    fn check_health(&self) -> Result<(), SimulationError> {
+        let mut finite = Vec::new();
+        for (name, v) in [
+            (["position.x", "position.y", "position.z"], self.position),
+            (["velocity.x", "velocity.y", "velocity.z"], self.velocity),
+            (["acceleration.x", "acceleration.y", "acceleration.z"], self.acceleration),
+            (["angular_velocity.x", "angular_velocity.y", "angular_velocity.z"], self.angular_velocity),
+        ] {
+            finite.extend(name.into_iter().zip([v.x, v.y, v.z]));
+        }
+        self.core.check_finite(&finite)?;
+        self.core.check_propulsion_health()?;
+
+        let mass = self.core.total_mass();
+        let height = self.position.y - self.launch_position.y;
+        let kinetic = 0.5 * mass * self.velocity.length().powi(2);
+        self.core.check_energy(kinetic + mass * self.core.gravity() * height)
+    }
+
+    /// Simulación plana interna: propulsión, masas y presión.
+    pub fn core(&self) -> &Simulation {
+        &self.core
+    }
+
+    /// Tiempo actual [s].
+    pub fn time(&self) -> f64 {
+        self.core.time()
+    }
+
+    /// Posición [m].
+    pub fn position(&self) -> Vector3D {
+        self.position
+    }
+
+    /// Velocidad [m/s].
+    pub fn velocity(&self) -> Vector3D {
+        self.velocity
+    }
+
+    /// Aceleración del último paso [m/s²].
+    pub fn acceleration(&self) -> Vector3D {
+        self.acceleration
+    }
+
+    /// Cuerpo -> mundo.
+    pub fn attitude(&self) -> Quaternion {
+        self.attitude
+    }
+
+    /// Velocidad angular en ejes del mundo [rad/s].
+    pub fn angular_velocity(&self) -> Vector3D {
+        self.angular_velocity
+    }
+
+    /// Ángulo de ataque [rad].
+    pub fn angle_of_attack(&self) -> f64 {
+        self.angle_of_attack
+    }
+
+    /// Eje longitudinal del cohete (hacia el morro) en ejes del mundo.
+    pub fn axis(&self) -> Vector3D {
+        self.attitude.rotate(Vector3D::UNIT_X)
+    }
+
+    /// Viento en ejes del mundo [m/s].
+    pub fn wind(&self) -> Vector3D {
+        let headwind = self.core.stability().map_or(0.0, |m| m.wind_speed);
+        Vector3D::new(headwind, 0.0, self.crosswind_speed)
+    }
+
+    /// Sigue en la rampa, donde no puede girar (siempre, sin modelo de actitud).
+    pub fn is_on_launch_rail(&self) -> bool {
+        match self.core.stability() {
+            Some(model) => {
+                (self.position - self.launch_position).magnitude() < model.launch_rail_length
+            }
+            None => true,
+        }
+    }
+
+    // fuerza normal al eje por el ángulo de ataque (cero sobre la rampa o sin modelo)
+    fn compute_normal_force(&mut self) -> Vector3D {
+        let Some(model) = self.core.stability() else {
+            return Vector3D::ZERO;
+        };
+        let air = self.velocity - self.wind();
+        let speed = air.magnitude();
+        if speed <= 0.0 {
+            self.angle_of_attack = 0.0;
+            self.normal_direction = Vector3D::ZERO;
+            return Vector3D::ZERO;
+        }
+        let axis = self.axis();
+        let flow = air / speed;
+        // atan2 en vez de acos: precisa también con ángulos pequeños
+        self.angle_of_attack = axis.cross(flow).magnitude().atan2(axis.dot(flow));
+        // empuja hacia el lado del eje opuesto al flujo
+        let across = flow - axis * axis.dot(flow);
+        self.normal_direction =
+            if across == Vector3D::ZERO { Vector3D::ZERO } else { -across.normalized() };
+        if self.is_on_launch_rail() {
+            return Vector3D::ZERO; // la rampa absorbe la fuerza lateral
+        }
+        self.normal_direction * model.normal_force(speed, self.angle_of_attack)
+    }
+
+    // momento restaurador (CP frente a CG) y amortiguamiento de los giros transversales
+    fn update_attitude(&mut self) {
+        let Some(model) = self.core.stability() else {
+            return;
+        };
+        if self.is_on_launch_rail() {
+            self.angular_velocity = Vector3D::ZERO;
+            return;
+        }
+        let dt = self.core.step();
+        let axis = self.axis();
+        let speed = (self.velocity - self.wind()).magnitude();
+        let arm = model.center_of_pressure - model.center_of_gravity;
+
+        // como en el modelo plano: ángulo del inicio del paso, velocidad del final
+        let normal = self.normal_direction * model.normal_force(speed, self.angle_of_attack);
+        let transverse_rate = self.angular_velocity - axis * axis.dot(self.angular_velocity);
+        let torque = axis.cross(normal) * -arm - transverse_rate * model.damping_coefficient(speed);
+
+        self.angular_velocity += torque / model.pitch_inertia * dt;
+        self.attitude = self.attitude.integrate(self.angular_velocity, dt);
+    }
+
+    // =========================
+    // logs y export
+    // =========================
+    // las muestras que toca registrar en este paso, interpoladas como en el modelo plano
+    fn push_logs(&mut self, current: SixDofSample) {
+        let burning = current.thrust > 0.0 || current.water_mass > 0.0;
+        let previous = self.previous;
+        for t in self.log_scheduler.due(previous.time, current.time, burning) {
+            if t < current.time && current.time > previous.time {
+                let fraction = (t - previous.time) / (current.time - previous.time);
+                self.samples.push(previous.lerp(&current, fraction));
+            } else {
+                self.samples.push(current);
+            }
+        }
+    }
+
+    /// Estado actual como muestra.
+    pub fn sample(&self) -> SixDofSample {
+        SixDofSample {
+            time: self.core.time(),
+            position: self.position,
+            velocity: self.velocity,
+            attitude: self.attitude,
+            angular_velocity: self.angular_velocity,
+            angle_of_attack: self.angle_of_attack,
+            thrust: self.core.thrust(),
+            total_mass: self.core.total_mass(),
+            water_mass: self.core.water_mass(),
+        }
+    }
+
+    /// Estados registrados, en orden.
+    pub fn samples(&self) -> &[SixDofSample] {
+        &self.samples
+    }
+
+    /// Punto de impacto (interpolado dentro del paso). Si aún no ha caído, la posición actual.
+    pub fn landing_position(&self) -> Vector3D {
+        self.events
+            .iter()
+            .find(|e| e.kind == FlightEventKind::Landing)
+            .map_or(self.position, |e| e.position)
+    }
+
+    /// Punto de impacto en latitud/longitud, si hay lugar de lanzamiento.
+    pub fn landing_point(&self) -> Option<GeodeticPoint> {
+        self.core.launch_site().map(|site| site.local_to_geodetic(self.landing_position()))
+    }
+
+    /// Trayectoria registrada en latitud/longitud/altura, si hay lugar de lanzamiento.
+    pub fn geodetic_trajectory(&self) -> Option<Vec<GeodeticPoint>> {
+        let site = self.core.launch_site()?;
+        Some(self.samples.iter().map(|s| site.local_to_geodetic(s.position)).collect())
+    }
+
+    /// Vuelo en coordenadas geodésicas para KML/GeoJSON, si hay lugar de lanzamiento.
+    pub fn geo_track(&self, name: &str) -> Option<GeoTrack> {
+        let site = self.core.launch_site()?;
+        let trajectory: Vec<Vector3D> = self.samples.iter().map(|s| s.position).collect();
+        Some(GeoTrack::from_local(name, &site, &trajectory, self.landing_position()))
+    }
+
+    /// Como `Simulation::export_logs_to_kml`, con la deriva lateral.
+    pub fn export_logs_to_kml<P: AsRef<Path>>(
+        &self,
+        path: P,
+        landing_scatter: &[GeodeticPoint],
+    ) -> std::io::Result<()> {
+        let track = self
+            .geo_track("Cohete de agua (6-DOF)")
+            .ok_or_else(map_export::missing_launch_site)?;
+        track.export_kml(path, landing_scatter)
+    }
+
+    /// Como `Simulation::export_logs_to_geojson`, con la deriva lateral.
+    pub fn export_logs_to_geojson<P: AsRef<Path>>(
+        &self,
+        path: P,
+        landing_scatter: &[GeodeticPoint],
+    ) -> std::io::Result<()> {
+        let track = self
+            .geo_track("Cohete de agua (6-DOF)")
+            .ok_or_else(map_export::missing_launch_site)?;
+        track.export_geojson(path, landing_scatter)
+    }
+
+    /// Exporta los estados a CSV con las opciones por defecto, como
+    /// `Simulation::export_logs_to_csv` pero con las columnas de `csv_columns`.
+    pub fn export_logs_to_csv<P: AsRef<Path>>(
+        &self,
+        path: P,
+        delimiter: char,
+    ) -> std::io::Result<()> {
+        self.export_logs_to_csv_with(path, &CsvOptions { delimiter, ..CsvOptions::default() })
+    }
+
+    /// Exporta los estados con columnas, unidades y formato a elección.
+    pub fn export_logs_to_csv_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &CsvOptions,
+    ) -> std::io::Result<()> {
+        let available = self.csv_columns();
+        let columns = options.columns.as_deref().unwrap_or(&available);
+        if let Some(missing) = columns.iter().find(|c| !available.contains(c)) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("columna {:?} no disponible en el modelo 6-DOF", missing),
+            ));
+        }
+        let site = self.core.launch_site();
+        let atmospheric_pressure = self.core.atmospheric_pressure();
+        options.write_file(path, columns, self.samples.len(), atmospheric_pressure, |i, column| {
+            self.samples[i].csv_value(column, site.as_ref())
+        })
+    }
+
+    /// Columnas que puede exportar: estado 3D, actitud, empuje y masas, y latitud/longitud/
+    /// altitud si hay lugar de lanzamiento.
+    pub fn csv_columns(&self) -> Vec<CsvColumn> {
+        let mut columns = vec![
+            CsvColumn::Time,
+            CsvColumn::X,
+            CsvColumn::Y,
+            CsvColumn::Z,
+            CsvColumn::Vx,
+            CsvColumn::Vy,
+            CsvColumn::Vz,
+            CsvColumn::Qw,
+            CsvColumn::Qx,
+            CsvColumn::Qy,
+            CsvColumn::Qz,
+            CsvColumn::Wx,
+            CsvColumn::Wy,
+            CsvColumn::Wz,
+            CsvColumn::AngleOfAttack,
+            CsvColumn::Thrust,
+            CsvColumn::TotalMass,
+            CsvColumn::WaterMass,
+        ];
+        if self.core.launch_site().is_some() {
+            columns.extend([CsvColumn::Latitude, CsvColumn::Longitude, CsvColumn::Altitude]);
+        }
+        columns
+    }
+}
+
+/// Sin acimut ni viento lateral el 6-DOF sigue al modelo plano; con viento lateral deriva
+/// y el morro gira hacia el viento.
+#[test]
+fn six_dof_reduces_to_planar_model() {
+    use crate::physics::stability::StabilityModel;
+
+    let config = SimulationConfig {
+        launching_angle_deg: 70.0,
+        stability: Some(StabilityModel { wind_speed: -3.0, ..Default::default() }),
+        ..Default::default()
+    };
+    let mut planar = Simulation::with_config(1e-3, config.clone()).unwrap();
+    let mut six = SixDofSimulation::new(1e-3, config.clone(), SixDofConfig::default()).unwrap();
+    planar.run(30.0).unwrap();
+    six.run(30.0).unwrap();
+
+    assert_eq!(planar.records().len(), six.samples().len());
+    let last = six.samples().last().unwrap();
+    let planar_last = planar.records().last().unwrap().position;
+    assert!((last.position.x - planar_last.x).abs() < 1e-6);
+    assert!((last.position.y - planar_last.y).abs() < 1e-6);
+    assert_eq!(last.position.z, 0.0);
+
+    let mut drifting = SixDofSimulation::new(
+        1e-3,
+        config,
+        SixDofConfig { crosswind_speed: 4.0 },
+    ).unwrap();
+    drifting.run(30.0).unwrap();
+    // el viento sopla hacia +z: el morro gira hacia -z, de donde viene el viento
+    let turned = drifting.samples().iter().find(|s| s.time >= 0.4).unwrap();
+    let axis = turned.attitude.rotate(Vector3D::UNIT_X);
+    assert!(axis.z < 0.0, "eje = {:?}", axis);
+    assert!(drifting.samples().last().unwrap().position.z.abs() > 0.1);
+}
+
+/// El rumbo sale solo del lugar de lanzamiento: gira el impacto sobre el terreno igual que
+/// en el modelo plano, sin tocar la trayectoria en ejes de la simulación.
+#[test]
+fn azimuth_rotates_trajectory() {
+    use crate::geodesy::LaunchSite;
+
+    let config = SimulationConfig {
+        launch_site: Some(LaunchSite::new(40.0, -3.0, 650.0, 30.0)),
+        ..SimulationConfig::default()
+    };
+    let mut planar = Simulation::with_config(1e-3, config.clone()).unwrap();
+    let mut rotated = SixDofSimulation::new(1e-3, config, SixDofConfig::default()).unwrap();
+    planar.run(30.0).unwrap();
+    rotated.run(30.0).unwrap();
+
+    let last = rotated.samples().last().unwrap().position;
+    assert!((last.x - planar.records().last().unwrap().position.x).abs() < 1e-9);
+    assert_eq!(last.z, 0.0);
+    let site = rotated.core.launch_site().unwrap();
+    let enu = site.local_to_enu(rotated.landing_position());
+    assert!((enu.x / enu.y - 30f64.to_radians().tan()).abs() < 1e-9);
+    let (six, flat) = (rotated.landing_point().unwrap(), planar.landing_point().unwrap());
+    assert!((six.latitude_deg - flat.latitude_deg).abs() < 1e-9);
+    assert!((six.longitude_deg - flat.longitude_deg).abs() < 1e-9);
+}
+
+/// Los eventos y el registro siguen las mismas reglas que el modelo plano.
+#[test]
+fn events_and_logging_match_planar_model() {
+    use crate::logging::LoggingPolicy;
+
+    let config = SimulationConfig {
+        logging: LoggingPolicy::FixedInterval(0.05),
+        ..Default::default()
+    };
+    let mut planar = Simulation::with_config(1e-3, config.clone()).unwrap();
+    let mut six = SixDofSimulation::new(1e-3, config, SixDofConfig::default()).unwrap();
+    planar.run(30.0).unwrap();
+    six.run(30.0).unwrap();
+
+    assert_eq!(planar.records().len(), six.samples().len());
+    assert!(six.samples().len() < 1000);
+    assert_eq!(planar.events().len(), six.events().len());
+    for (event, six_event) in planar.events().iter().zip(six.events()) {
+        assert_eq!(event.kind, six_event.kind);
+        assert!((event.time - six_event.time).abs() < 1e-9, "{:?}", event.kind);
+    }
+    for kind in [FlightEventKind::Burnout, FlightEventKind::Apogee, FlightEventKind::Landing] {
+        assert!(six.has_event(kind), "{:?}", kind);
+    }
+    assert_eq!(six.landing_position().y, 0.0);
+}
+
+/// `config.termination` y los observadores paran el vuelo en 3D igual que el plano.
+#[test]
+fn termination_and_observers() {
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Default)]
+    struct Recorder {
+        steps: usize,
+        events: Vec<FlightEventKind>,
+    }
+    impl Observer<SixDofSimulation> for Recorder {
+        fn on_step(&mut self, _sim: &SixDofSimulation) {
+            self.steps += 1;
+        }
+        fn on_event(&mut self, _sim: &SixDofSimulation, event: &SixDofEvent) {
+            self.events.push(event.kind);
+        }
+        fn should_stop(&self, sim: &SixDofSimulation) -> bool {
+            sim.has_event(FlightEventKind::Apogee)
+        }
+    }
+
+    let recorder = Rc::new(RefCell::new(Recorder::default()));
+    let mut six =
+        SixDofSimulation::new(1e-3, SimulationConfig::default(), SixDofConfig::default()).unwrap();
+    six.add_observer(Box::new(recorder.clone()));
+    six.run(30.0).unwrap();
+    assert!(six.position().y > 5.0 && !six.has_event(FlightEventKind::Landing));
+    assert_eq!(recorder.borrow().events, [FlightEventKind::Burnout, FlightEventKind::Apogee]);
+    assert_eq!(recorder.borrow().steps, six.core.iteration());
+
+    let config = SimulationConfig {
+        termination: vec![Termination::Time(0.25)],
+        ..SimulationConfig::default()
+    };
+    let mut short = SixDofSimulation::new(1e-3, config, SixDofConfig::default()).unwrap();
+    short.run(30.0).unwrap();
+    assert!((short.time() - 0.25).abs() < 1e-9);
+}
+
+/// El CSV 3D sale por `CsvOptions` como el plano: mismas cabeceras, unidades y decimales, y
+/// error si se pide una columna que el 6-DOF no tiene.
+#[test]
+fn csv_export_uses_the_shared_options() {
+    use crate::csv_export::HeaderLanguage;
+
+    let config = SimulationConfig {
+        termination: vec![Termination::Time(0.05)],
+        ..SimulationConfig::default()
+    };
+    let mut six = SixDofSimulation::new(1e-3, config, SixDofConfig::default()).unwrap();
+    six.run(30.0).unwrap();
+    let path = std::env::temp_dir().join(format!("six_dof_{}.csv", std::process::id()));
+
+    six.export_logs_to_csv(&path, ';').unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    let mut lines = text.trim_start_matches('\u{feff}').lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        "time_s;x_m;y_m;z_m;vx_m_s;vy_m_s;vz_m_s;qw;qx;qy;qz;wx_deg_s;wy_deg_s;wz_deg_s;\
+         aoa_deg;thrust_N;total_mass_kg;water_mass_kg"
+    );
+    assert_eq!(lines.count(), six.samples().len());
+
+    let options = CsvOptions {
+        columns: Some(vec![CsvColumn::Time, CsvColumn::Qw, CsvColumn::Z]),
+        header_language: HeaderLanguage::English,
+        bom: false,
+        ..CsvOptions::imperial()
+    };
+    six.export_logs_to_csv_with(&path, &options).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with("Time [s],Attitude quaternion w,Crossrange distance [ft]\n"));
+
+    let pressure = CsvOptions { columns: Some(vec![CsvColumn::Pressure]), ..CsvOptions::default() };
+    assert!(six.export_logs_to_csv_with(&path, &pressure).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
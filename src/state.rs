@@ -0,0 +1,260 @@
+// state.rs
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{
+    cluster::VesselState,
+    energy::{EnergyBalance, MomentumBalance},
+    events::{FlightEvent, FlightEventKind, FlightPeaks},
+    json_export::{self, Json},
+    math_utils::vector_2d::Vector2D,
+};
+
+/// Todo lo que cambia durante una corrida, tomado en un paso con `Simulation::snapshot`.
+/// Con `Simulation::restore` se sigue integrando desde ahí, con la misma configuración
+/// (reanudar una corrida larga) o con otra (ramificar, p. ej. el planeo tras el fin de
+/// propulsión con otro viento). La configuración no va aquí: la pone quien restaura.
+/// Los registros ya guardados tampoco; el log de la simulación restaurada empieza en `time`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationState {
+    /// Paso de integración [s].
+    pub step: f64,
+    /// Tiempo [s].
+    pub time: f64,
+
+    /// Posición [m].
+    pub position: Vector2D,
+    /// Velocidad [m/s].
+    pub velocity: Vector2D,
+    /// Aceleración del último paso [m/s²].
+    pub acceleration: Vector2D,
+    /// Inicio de la rampa [m].
+    pub launch_position: Vector2D,
+
+    /// Cabeceo [rad].
+    pub pitch: f64,
+    /// Velocidad de cabeceo [rad/s].
+    pub pitch_rate: f64,
+    /// Ángulo de ataque [rad].
+    pub angle_of_attack: f64,
+    /// Fuerza aerodinámica normal [N].
+    pub normal_force: f64,
+    /// Empuje [N].
+    pub thrust: f64,
+    /// Masa en seco (cambia al soltar etapas) [kg].
+    pub dry_mass: f64,
+
+    /// Aire y agua de cada depósito.
+    pub vessels: Vec<VesselState>,
+
+    /// Eventos hasta ahora.
+    pub events: Vec<FlightEvent>,
+    /// Máximos hasta ahora.
+    pub peaks: FlightPeaks,
+    /// Balance de energía hasta ahora.
+    pub energy: EnergyBalance,
+    /// Balance de cantidad de movimiento hasta ahora.
+    pub momentum: MomentumBalance,
+
+    // cuenta del `LogScheduler`
+    /// Pasos desde la última muestra.
+    pub steps_since_log: usize,
+    /// Instante de la última muestra programada [s].
+    pub last_log_time: f64,
+}
+
+impl SimulationState {
+    /// Como JSON; los números se vuelven a leer bit a bit.
+    pub fn to_json(&self) -> Json {
+        let floats = |values: &[f64]| Json::Array(values.iter().map(|&v| v.into()).collect());
+        let vessel = |v: &VesselState| {
+            Json::object([
+                ("polytropic_constant", v.polytropic_constant.into()),
+                ("pressure_pa", v.pressure_pa.into()),
+                ("air_volume_m3", v.air_volume.into()),
+                ("water_mass_kg", floats(&v.water_masses)),
+                ("volume_m3", floats(&v.volumes)),
+            ])
+        };
+        Json::object([
+            ("step_s", self.step.into()),
+            ("time_s", self.time.into()),
+            ("position_m", self.position.into()),
+            ("velocity_m_s", self.velocity.into()),
+            ("acceleration_m_s2", self.acceleration.into()),
+            ("launch_position_m", self.launch_position.into()),
+            ("pitch_rad", self.pitch.into()),
+            ("pitch_rate_rad_s", self.pitch_rate.into()),
+            ("angle_of_attack_rad", self.angle_of_attack.into()),
+            ("normal_force_n", self.normal_force.into()),
+            ("thrust_n", self.thrust.into()),
+            ("dry_mass_kg", self.dry_mass.into()),
+            ("vessels", Json::Array(self.vessels.iter().map(vessel).collect())),
+            ("events", Json::Array(self.events.iter().map(json_export::event_json).collect())),
+            (
+                "peaks",
+                Json::object([
+                    ("max_thrust_n", self.peaks.max_thrust.into()),
+                    ("max_speed_m_s", self.peaks.max_speed.into()),
+                    ("max_acceleration_m_s2", self.peaks.max_acceleration.into()),
+                    ("max_pressure_pa", self.peaks.max_pressure.into()),
+                ]),
+            ),
+            ("energy", json_export::energy_json(&self.energy)),
+            ("momentum", json_export::momentum_json(&self.momentum)),
+            ("steps_since_log", self.steps_since_log.into()),
+            ("last_log_time_s", self.last_log_time.into()),
+        ])
+    }
+
+    /// Lee lo que escribe `to_json`; error si falta o no vale algún campo.
+    pub fn from_json(json: &Json) -> io::Result<Self> {
+        let invalid = |key: &str| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("estado: falta o no vale `{}`", key))
+        };
+        let number = |json: &Json, key: &str| {
+            json.get(key).and_then(Json::as_f64).ok_or_else(|| invalid(key))
+        };
+        let array = |json: &Json, key: &str| -> io::Result<Vec<Json>> {
+            let items = json.get(key).and_then(Json::as_array);
+            items.map(<[Json]>::to_vec).ok_or_else(|| invalid(key))
+        };
+        let floats = |json: &Json, key: &str| -> io::Result<Vec<f64>> {
+            array(json, key)?.iter().map(|v| v.as_f64().ok_or_else(|| invalid(key))).collect()
+        };
+        let vector = |json: &Json, key: &str| match floats(json, key)?.as_slice() {
+            &[x, y] => Ok(Vector2D::new(x, y)),
+            _ => Err(invalid(key)),
+        };
+
+        let vessels = array(json, "vessels")?
+            .iter()
+            .map(|v| {
+                Ok(VesselState {
+                    polytropic_constant: number(v, "polytropic_constant")?,
+                    pressure_pa: number(v, "pressure_pa")?,
+                    air_volume: number(v, "air_volume_m3")?,
+                    water_masses: floats(v, "water_mass_kg")?,
+                    volumes: floats(v, "volume_m3")?,
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let events = array(json, "events")?
+            .iter()
+            .map(|e| {
+                let key = e.get("event").and_then(Json::as_str);
+                Ok(FlightEvent {
+                    kind: key.and_then(FlightEventKind::from_key).ok_or_else(|| invalid("event"))?,
+                    time: number(e, "time_s")?,
+                    position: vector(e, "position_m")?,
+                    velocity: vector(e, "velocity_m_s")?,
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let peaks = json.get("peaks").ok_or_else(|| invalid("peaks"))?;
+        let energy = json.get("energy").ok_or_else(|| invalid("energy"))?;
+        let momentum = json.get("momentum").ok_or_else(|| invalid("momentum"))?;
+
+        Ok(Self {
+            step: number(json, "step_s")?,
+            time: number(json, "time_s")?,
+            position: vector(json, "position_m")?,
+            velocity: vector(json, "velocity_m_s")?,
+            acceleration: vector(json, "acceleration_m_s2")?,
+            launch_position: vector(json, "launch_position_m")?,
+            pitch: number(json, "pitch_rad")?,
+            pitch_rate: number(json, "pitch_rate_rad_s")?,
+            angle_of_attack: number(json, "angle_of_attack_rad")?,
+            normal_force: number(json, "normal_force_n")?,
+            thrust: number(json, "thrust_n")?,
+            dry_mass: number(json, "dry_mass_kg")?,
+            vessels,
+            events,
+            peaks: FlightPeaks {
+                max_thrust: number(peaks, "max_thrust_n")?,
+                max_speed: number(peaks, "max_speed_m_s")?,
+                max_acceleration: number(peaks, "max_acceleration_m_s2")?,
+                max_pressure: number(peaks, "max_pressure_pa")?,
+            },
+            energy: EnergyBalance {
+                initial: number(energy, "initial_j")?,
+                pressure_thrust_work: number(energy, "pressure_thrust_work_j")?,
+                gas: number(energy, "gas_j")?,
+                atmosphere_work: number(energy, "atmosphere_work_j")?,
+                rocket_kinetic: number(energy, "rocket_kinetic_j")?,
+                rocket_potential: number(energy, "rocket_potential_j")?,
+                water_kinetic: number(energy, "water_kinetic_j")?,
+                water_potential: number(energy, "water_potential_j")?,
+                nozzle_loss: number(energy, "nozzle_loss_j")?,
+                drag_loss: number(energy, "drag_loss_j")?,
+            },
+            momentum: MomentumBalance {
+                initial: vector(momentum, "initial_kg_m_s")?,
+                rocket: vector(momentum, "rocket_kg_m_s")?,
+                water: vector(momentum, "water_kg_m_s")?,
+                gravity_impulse: vector(momentum, "gravity_impulse_n_s")?,
+                aero_impulse: vector(momentum, "aero_impulse_n_s")?,
+                pressure_thrust_impulse: vector(momentum, "pressure_thrust_impulse_n_s")?,
+            },
+            steps_since_log: number(json, "steps_since_log")? as usize,
+            last_log_time: number(json, "last_log_time_s")?,
+        })
+    }
+
+    /// Guarda el estado como JSON (punto de control).
+    pub fn write_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, format!("{}\n", self.to_json()))
+    }
+
+    /// Lee un punto de control de `write_json`.
+    pub fn read_json<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_json(&Json::parse(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Parar, guardar y seguir da lo mismo que no parar; y desde el mismo estado
+/// se puede ramificar con otra configuración.
+#[test]
+fn snapshot_resume_and_branch() {
+    use crate::{
+        config::SimulationConfig, physics::stability::StabilityModel, simulation::Simulation,
+    };
+
+    let windy = |wind_speed: f64| SimulationConfig {
+        stability: Some(StabilityModel { wind_speed, ..StabilityModel::default() }),
+        ..SimulationConfig::default()
+    };
+    let config = windy(0.0);
+    let mut straight = Simulation::with_config(0.001, config.clone()).unwrap();
+    straight.run(30.0).unwrap();
+
+    let mut first = Simulation::with_config(0.001, config.clone()).unwrap();
+    while first.water_mass() > 0.0 {
+        first.step_once().unwrap();
+    }
+    let state = first.snapshot();
+    let read_back = SimulationState::from_json(&Json::parse(&state.to_json().to_string()).unwrap());
+    assert_eq!(read_back.unwrap(), state);
+
+    let mut resumed = Simulation::restore(config.clone(), &state).unwrap();
+    resumed.run(30.0).unwrap();
+    assert_eq!(resumed.position(), straight.position());
+    assert_eq!(resumed.summary(), straight.summary());
+    let tail = &straight.records()[straight.records().len() - resumed.records().len()..];
+    assert_eq!(resumed.records(), tail);
+
+    // la misma propulsión, otro viento en el planeo
+    let mut headwind = Simulation::restore(windy(-5.0), &state).unwrap();
+    headwind.run(30.0).unwrap();
+    assert!(headwind.summary().range < straight.summary().range);
+
+    // otra botella no encaja con el estado
+    let mut other = config;
+    other.cluster.push(crate::cluster::ClusterBottle {
+        bottle: other.bottle.clone(),
+        nozzle: other.nozzle.clone(),
+        water_mass: 0.5,
+    });
+    assert!(Simulation::restore(other, &state).is_err());
+}
@@ -0,0 +1,126 @@
+// sweep.rs
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::{
+    columnar::{self, ColumnarTable},
+    config::SimulationConfig,
+    constants::MAX_FLIGHT_TIME,
+    error::{check, SimulationError},
+    events::FlightSummary,
+    logging::NullLogger,
+    simulation::Simulation,
+};
+
+/// Barrido de parámetros: una corrida completa por configuración, con su resumen.
+/// Se guarda como tabla Parquet (`table`, `export_parquet`) para analizarlo fuera.
+#[derive(Clone, Debug)]
+pub struct Sweep {
+    /// Cada configuración con el resumen de su vuelo, en orden.
+    pub runs: Vec<(SimulationConfig, FlightSummary)>,
+}
+
+impl Sweep {
+    /// Vuela cada configuración con paso `step`, sin guardar registros (solo interesa el
+    /// resumen). Para en la primera que falle.
+    pub fn run(
+        configs: impl IntoIterator<Item = SimulationConfig>,
+        step: f64,
+    ) -> Result<Self, SimulationError> {
+        let mut runs = Vec::new();
+        for config in configs {
+            let mut sim = Simulation::with_config(step, config.clone())?;
+            sim.set_logger(Box::new(NullLogger));
+            sim.run(MAX_FLIGHT_TIME)?;
+            runs.push((config, sim.summary()));
+        }
+        Ok(Self { runs })
+    }
+
+    /// `base` con cada presión inicial de `pressures_psi`.
+    pub fn over_pressure(
+        base: &SimulationConfig,
+        pressures_psi: &[f64],
+        step: f64,
+    ) -> Result<Self, SimulationError> {
+        for &psi in pressures_psi {
+            check("initial_air_pressure_psi", psi, psi > 0.0, "> 0")?;
+        }
+        let configs = pressures_psi
+            .iter()
+            .map(|&psi| SimulationConfig { initial_air_pressure_psi: psi, ..base.clone() });
+        Self::run(configs, step)
+    }
+
+    /// Una fila por corrida, con los parámetros y el resumen.
+    pub fn table(&self) -> ColumnarTable {
+        columnar::sweep_summary_table(&self.runs)
+    }
+
+    /// `table` como archivo Parquet.
+    pub fn export_parquet<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.table().export_parquet(path)
+    }
+}
+
+impl fmt::Display for Sweep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = ("corrida", "presión [psi]", "apogeo [m]", "alcance [m]");
+        writeln!(f, "{:>7} {:>14} {:>12} {:>12}", header.0, header.1, header.2, header.3)?;
+        for (i, (config, summary)) in self.runs.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>7} {:>14.1} {:>12.2} {:>12.2}",
+                i, config.initial_air_pressure_psi, summary.apogee, summary.range
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Una fila por corrida, con los parámetros y el resumen en sus columnas, y la
+/// configuración de cada fila en los metadatos.
+#[test]
+fn sweep_table_has_one_row_per_run() {
+    use crate::columnar::ColumnData;
+    use crate::json_export::Json;
+
+    let pressures = [40.0, 60.0, 80.0];
+    let sweep = Sweep::over_pressure(&SimulationConfig::default(), &pressures, 1e-3).unwrap();
+    assert!(Sweep::over_pressure(&SimulationConfig::default(), &[-1.0], 1e-3).is_err());
+    let table = sweep.table();
+    assert_eq!(table.num_rows(), 3);
+
+    let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "run", "bottle", "nozzle", "pressure_psi", "nozzle_diam_m", "water_mass_kg",
+            "dry_mass_kg", "launching_angle_deg", "t_burnout_s", "apogee_m", "range_m",
+            "flight_time_s", "max_thrust_N", "max_speed_m_s",
+        ]
+    );
+    let column = |name: &str| &table.columns.iter().find(|c| c.name == name).unwrap().data;
+    assert_eq!(column("run"), &ColumnData::Int64(vec![0, 1, 2]));
+    assert_eq!(column("pressure_psi"), &ColumnData::Double(pressures.to_vec()));
+    let ColumnData::Double(apogees) = column("apogee_m") else { panic!("apogee_m") };
+    assert!(apogees.windows(2).all(|w| w[1] > w[0]), "{:?}", apogees);
+    for (value, (_, summary)) in apogees.iter().zip(&sweep.runs) {
+        assert_eq!(*value, summary.apogee);
+    }
+
+    let keys: Vec<&str> = table.metadata.iter().map(|(k, _)| k.as_str()).collect();
+    assert_eq!(keys, ["base_config", "run_configs"]);
+    let config = Json::parse(&table.metadata[0].1).unwrap();
+    let psi = config.get("initial_air_pressure_psi").and_then(Json::as_f64);
+    assert_eq!(psi, Some(40.0));
+    let configs = Json::parse(&table.metadata[1].1).unwrap();
+    let psi: Vec<Option<f64>> = configs
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.get("initial_air_pressure_psi").and_then(Json::as_f64))
+        .collect();
+    assert_eq!(psi, pressures.map(Some));
+}
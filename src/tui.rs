@@ -0,0 +1,323 @@
+// tui.rs
+use std::fmt::Write as _;
+use std::io::{self, BufRead, Read, Write};
+use std::process::{Command, Output, Stdio};
+
+use physics_calculations::{
+    config::SimulationConfig,
+    logging::{LogRecord, LoggingPolicy},
+    simulation::Simulation,
+};
+
+// paso y muestreo de la vista en vivo: basta para la gráfica y cada corrida tarda milisegundos
+const LIVE_STEP: f64 = 0.0002; // [s]
+const LIVE_LOG_INTERVAL: f64 = 0.002; // [s]
+
+const PRESSURE_STEP_PSI: f64 = 5.0;
+const WATER_STEP_KG: f64 = 0.05;
+const ANGLE_STEP_DEG: f64 = 5.0;
+
+const HELP: &str = "[p/P] presión ∓  [w/W] agua ∓  [a/A] ángulo ∓  [r] repetir  [q] salir";
+
+/// Qué hacer tras una tecla.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Rerun,
+    Ignore,
+    Quit,
+}
+
+/// Aplica una tecla a la configuración, sin salirse de rangos razonables.
+fn apply_key(config: &mut SimulationConfig, key: char) -> Action {
+    let max_water = 0.95 * config.bottle.compliance.nominal_volume() * config.water_density;
+    match key {
+        'p' => {
+            config.initial_air_pressure_psi =
+                (config.initial_air_pressure_psi - PRESSURE_STEP_PSI).max(0.0)
+        }
+        'P' => config.initial_air_pressure_psi += PRESSURE_STEP_PSI,
+        'w' => config.initial_water_mass = (config.initial_water_mass - WATER_STEP_KG).max(0.0),
+        'W' => {
+            config.initial_water_mass = (config.initial_water_mass + WATER_STEP_KG).min(max_water)
+        }
+        'a' => config.launching_angle_deg = (config.launching_angle_deg - ANGLE_STEP_DEG).max(5.0),
+        'A' => {
+            config.launching_angle_deg = (config.launching_angle_deg + ANGLE_STEP_DEG).min(90.0)
+        }
+        'r' | 'R' => {}
+        // en modo crudo Ctrl-C y Ctrl-D llegan como teclas (ver `RawMode`)
+        'q' | 'Q' | '\u{3}' | '\u{4}' => return Action::Quit,
+        _ => return Action::Ignore,
+    }
+    Action::Rerun
+}
+
+/// Lienzo de caracteres Braille: cada celda son 2×4 puntos.
+struct BrailleCanvas {
+    columns: usize,
+    rows: usize,
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    fn new(columns: usize, rows: usize) -> Self {
+        Self { columns, rows, cells: vec![0; columns * rows] }
+    }
+
+    fn set(&mut self, x: i64, y: i64) {
+        const BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        if x < 0 || y < 0 || x >= 2 * self.columns as i64 || y >= 4 * self.rows as i64 {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        self.cells[(y / 4) * self.columns + x / 2] |= BITS[y % 4][x % 2];
+    }
+
+    fn line(&mut self, from: (i64, i64), to: (i64, i64)) {
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).max(1);
+        for k in 0..=steps {
+            let x = from.0 + (to.0 - from.0) * k / steps;
+            let y = from.1 + (to.1 - from.1) * k / steps;
+            self.set(x, y);
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.cells
+            .chunks(self.columns)
+            .map(|row| {
+                // celda vacía como espacio, para que el fondo no tenga puntos sueltos
+                row.iter()
+                    .map(|&bits| match bits {
+                        0 => ' ',
+                        bits => char::from_u32(0x2800 + bits as u32).unwrap_or(' '),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Gráfica de una curva en `columns` × `rows` caracteres, con el rango a la izquierda y abajo.
+fn braille_chart(title: &str, points: &[(f64, f64)], columns: usize, rows: usize) -> String {
+    let (columns, rows) = (columns.max(1), rows.max(1));
+    let finite: Vec<(f64, f64)> =
+        points.iter().copied().filter(|(x, y)| x.is_finite() && y.is_finite()).collect();
+    let bounds = |pick: fn(&(f64, f64)) -> f64| {
+        let (min, max) = finite
+            .iter()
+            .map(pick)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        if !(min.is_finite() && max.is_finite()) {
+            (0.0, 1.0)
+        } else if max - min < 1e-12 {
+            (min - 0.5, max + 0.5)
+        } else {
+            (min, max)
+        }
+    };
+    let (x_min, x_max) = bounds(|p| p.0);
+    let (y_min, y_max) = bounds(|p| p.1);
+
+    let mut canvas = BrailleCanvas::new(columns, rows);
+    let (width, height) = ((2 * columns - 1) as f64, (4 * rows - 1) as f64);
+    let to_dot = |(x, y): (f64, f64)| {
+        let px = ((x - x_min) / (x_max - x_min) * width).round() as i64;
+        let py = ((y_max - y) / (y_max - y_min) * height).round() as i64;
+        (px, py)
+    };
+    for pair in finite.windows(2) {
+        canvas.line(to_dot(pair[0]), to_dot(pair[1]));
+    }
+
+    let mut out = format!("{}\n", title);
+    for (i, line) in canvas.lines().iter().enumerate() {
+        let label = match i {
+            0 => format!("{:>8.1}", y_max),
+            i if i + 1 == rows => format!("{:>8.1}", y_min),
+            _ => String::new(),
+        };
+        let _ = writeln!(out, "{:>8} ┤{}", label, line);
+    }
+    let _ = writeln!(out, "{:>8} └{}", "", "─".repeat(columns));
+    let _ = writeln!(out, "{:>10}{:<w$.2}{:>8.2}", "", x_min, x_max, w = columns.saturating_sub(8));
+    out
+}
+
+/// Pantalla completa: parámetros, resultados y gráficas de trayectoria y empuje.
+fn render(config: &SimulationConfig, sim: &Simulation, columns: usize) -> String {
+    let records = sim.records();
+    let summary = sim.summary();
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "Presión: {:.0} psi   Agua: {:.2} kg   Ángulo: {:.0}°",
+        config.initial_air_pressure_psi, config.initial_water_mass, config.launching_angle_deg
+    );
+    let _ = writeln!(out, "{}\n", summary);
+
+    let trajectory: Vec<(f64, f64)> =
+        records.iter().map(|r| (r.position.x, r.position.y)).collect();
+    let title = "Trayectoria: altura (m) frente a distancia (m)";
+    out.push_str(&braille_chart(title, &trajectory, columns, 12));
+
+    // el empuje solo tiene interés hasta poco después de vaciarse
+    let burn_end = summary.burnout_time.map_or(f64::INFINITY, |t| 1.5 * t);
+    let thrust: Vec<(f64, f64)> = records
+        .iter()
+        .take_while(|r: &&LogRecord| r.time <= burn_end)
+        .map(|r| (r.time, r.thrust))
+        .collect();
+    out.push('\n');
+    out.push_str(&braille_chart("Empuje (N) frente a tiempo (s)", &thrust, columns, 8));
+    out
+}
+
+// Lectura tecla a tecla en Unix (`stty`); si no se puede, se lee por líneas. Guarda la
+// configuración de la terminal (`stty -g`) y la repone tal cual al soltarse, también si
+// `run` acaba con error o con pánico. Sin `isig`, Ctrl-C no mata el proceso (lo que dejaría
+// la terminal sin eco): llega como tecla y sale por el camino normal.
+struct RawMode {
+    saved: Option<String>,
+}
+
+// `stty` sobre la terminal de la entrada estándar
+fn stty(args: &[&str]) -> Option<Output> {
+    let output = Command::new("stty").args(args).stdin(Stdio::inherit()).output().ok()?;
+    output.status.success().then_some(output)
+}
+
+impl RawMode {
+    fn enable() -> Self {
+        let saved = if cfg!(unix) { stty(&["-g"]) } else { None }
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|_| stty(&["-icanon", "-echo", "-isig", "min", "1"]).is_some());
+        Self { saved }
+    }
+
+    fn enabled(&self) -> bool {
+        self.saved.is_some()
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.saved {
+            let _ = stty(&[saved]);
+        }
+    }
+}
+
+/// Convierte los bytes leídos en modo crudo en teclas, descartando las secuencias de escape
+/// completas (flechas, teclas de función, Alt+tecla) para que `ESC [ A` no cuente como 'A'.
+fn keys_from_bytes(bytes: &[u8]) -> Vec<char> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != 0x1b {
+            keys.push(bytes[i] as char);
+            i += 1;
+            continue;
+        }
+        match bytes.get(i + 1) {
+            // CSI: parámetros e intermedios hasta el byte final 0x40..=0x7e
+            Some(b'[') => {
+                i += 2;
+                while i < bytes.len() && !(0x40..=0x7e).contains(&bytes[i]) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            // SS3: un solo byte tras `ESC O`
+            Some(b'O') => i += 3,
+            // Alt+tecla o un ESC suelto
+            Some(_) => i += 2,
+            None => i += 1,
+        }
+    }
+    keys
+}
+
+/// Vista en vivo: simula, dibuja y espera teclas para cambiar presión, agua y ángulo.
+pub fn run(mut config: SimulationConfig) -> io::Result<()> {
+    config.logging = LoggingPolicy::FixedInterval(LIVE_LOG_INTERVAL);
+    let raw = RawMode::enable();
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    loop {
+        let mut screen = String::from("\x1b[2J\x1b[H");
+        match Simulation::with_config(LIVE_STEP, config.clone()) {
+            Ok(mut sim) => {
+                let outcome = sim.run(30.0);
+                screen.push_str(&render(&config, &sim, 64));
+                if let Err(e) = outcome {
+                    let _ = writeln!(screen, "\nLa simulación se detuvo: {}", e);
+                }
+            }
+            Err(e) => {
+                let _ = writeln!(screen, "No se puede simular: {}", e);
+            }
+        }
+        let _ = writeln!(screen, "\n{}", HELP);
+        if !raw.enabled() {
+            screen.push_str("(escribe las teclas y pulsa Enter)\n");
+        }
+        stdout.write_all(screen.as_bytes())?;
+        stdout.flush()?;
+
+        // una tecla en modo crudo o una línea entera (varias teclas) en modo normal
+        let keys: Vec<char> = if raw.enabled() {
+            // una flecha llega entera en una sola lectura; se leen todos sus bytes juntos
+            let mut bytes = [0u8; 32];
+            let n = stdin.lock().read(&mut bytes)?;
+            if n == 0 {
+                return Ok(());
+            }
+            keys_from_bytes(&bytes[..n])
+        } else {
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            line.trim().chars().collect()
+        };
+        for key in keys {
+            if apply_key(&mut config, key) == Action::Quit {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Las teclas respetan los límites y la pantalla trae resumen y ambas gráficas.
+#[test]
+fn keys_and_render() {
+    let mut config =
+        SimulationConfig { initial_air_pressure_psi: 3.0, ..SimulationConfig::default() };
+    assert_eq!(apply_key(&mut config, 'p'), Action::Rerun);
+    assert_eq!(config.initial_air_pressure_psi, 0.0);
+    for _ in 0..100 {
+        apply_key(&mut config, 'W');
+        apply_key(&mut config, 'A');
+    }
+    assert!(config.initial_water_mass < config.bottle.compliance.nominal_volume() * 1000.0);
+    assert_eq!(config.launching_angle_deg, 90.0);
+    assert_eq!(apply_key(&mut config, 'x'), Action::Ignore);
+    assert_eq!(apply_key(&mut config, 'q'), Action::Quit);
+    assert_eq!(apply_key(&mut config, '\u{3}'), Action::Quit, "Ctrl-C");
+    // las flechas y teclas de función no se confunden con letras
+    assert_eq!(keys_from_bytes(b"\x1b[A"), Vec::<char>::new());
+    assert_eq!(keys_from_bytes(b"p\x1b[1;5Cw\x1bOPq\x1b"), vec!['p', 'w', 'q']);
+
+    let logging = LoggingPolicy::FixedInterval(0.01);
+    let config = SimulationConfig { logging, ..SimulationConfig::default() };
+    let mut sim = Simulation::with_config(0.001, config.clone()).unwrap();
+    sim.run(30.0).unwrap();
+    let screen = render(&config, &sim, 40);
+    assert!(screen.contains("Apogeo") && screen.contains("Empuje (N)"));
+    assert!(screen.chars().any(|c| ('\u{2801}'..='\u{28ff}').contains(&c)));
+    // una terminal estrecha no hace desbordar el ancho de las etiquetas
+    assert!(braille_chart("t", &[(0.0, 0.0), (1.0, 1.0)], 4, 2).contains("1.00"));
+    assert!(!braille_chart("t", &[], 0, 0).is_empty());
+}